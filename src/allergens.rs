@@ -0,0 +1,611 @@
+use crate::text::{contains_phrase, find_phrase, normalize_text};
+use crate::{Ingredient, Recipe};
+
+/// One of the 14 major allergens that must be declared in the EU
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Allergen {
+    /// Cereals containing gluten (wheat, rye, barley, oats, spelt)
+    Gluten,
+    Crustaceans,
+    Eggs,
+    Fish,
+    Peanuts,
+    Soy,
+    Milk,
+    /// Tree nuts (almonds, hazelnuts, walnuts, ...)
+    Nuts,
+    Celery,
+    Mustard,
+    Sesame,
+    /// Sulphur dioxide and sulphites, e.g. in wine and dried fruit
+    Sulphites,
+    Lupin,
+    Molluscs,
+}
+
+impl Allergen {
+    /// All allergens in the order of the EU regulation
+    pub const ALL: [Allergen; 14] = [
+        Allergen::Gluten,
+        Allergen::Crustaceans,
+        Allergen::Eggs,
+        Allergen::Fish,
+        Allergen::Peanuts,
+        Allergen::Soy,
+        Allergen::Milk,
+        Allergen::Nuts,
+        Allergen::Celery,
+        Allergen::Mustard,
+        Allergen::Sesame,
+        Allergen::Sulphites,
+        Allergen::Lupin,
+        Allergen::Molluscs,
+    ];
+
+    /// Returns the name of the allergen for display, e.g. "Peanuts"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Allergen::Gluten => "Gluten",
+            Allergen::Crustaceans => "Crustaceans",
+            Allergen::Eggs => "Eggs",
+            Allergen::Fish => "Fish",
+            Allergen::Peanuts => "Peanuts",
+            Allergen::Soy => "Soy",
+            Allergen::Milk => "Milk",
+            Allergen::Nuts => "Nuts",
+            Allergen::Celery => "Celery",
+            Allergen::Mustard => "Mustard",
+            Allergen::Sesame => "Sesame",
+            Allergen::Sulphites => "Sulphites",
+            Allergen::Lupin => "Lupin",
+            Allergen::Molluscs => "Molluscs",
+        }
+    }
+
+    /// Returns the keyword that identifies this allergen in the ingredient, if any
+    ///
+    /// Keywords are matched as whole words in English and German, ignoring case and
+    /// plural forms. Known false friends like "peanut butter" for milk or "eggplant" for
+    /// eggs are excluded. Ingredients labelled free from the allergen, e.g. "gluten-free
+    /// flour" or "glutenfreies Mehl", never contain it.
+    pub fn find_in(&self, ingredient: &str) -> Option<&'static str> {
+        let (_, keywords, exclusions) = ALLERGEN_KEYWORDS
+            .iter()
+            .find(|(allergen, _, _)| allergen == self)?;
+        let text = normalize_text(ingredient);
+        if self.is_free_from(ingredient, &text) {
+            return None;
+        }
+        let excluded = exclusions
+            .iter()
+            .map(|exclusion| normalize_text(exclusion))
+            .flat_map(|exclusion| find_phrase(&text, &exclusion).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        keywords.iter().copied().find(|keyword| {
+            let keyword = normalize_text(keyword);
+            find_phrase(&text, &keyword).any(|range| {
+                !excluded
+                    .iter()
+                    .any(|e| e.start <= range.start && range.end <= e.end)
+            })
+        })
+    }
+}
+
+impl Allergen {
+    /// Checks whether the ingredient is labelled free from this allergen, e.g. "dairy-free
+    /// butter" or "milchfreie Schokolade". The text is the normalized ingredient.
+    fn is_free_from(&self, ingredient: &str, text: &str) -> bool {
+        let Some((_, names)) = FREE_FROM_NAMES
+            .iter()
+            .find(|(allergen, _)| allergen == self)
+        else {
+            return false;
+        };
+        let ingredient = ingredient.to_lowercase();
+        names.iter().any(|name| {
+            contains_phrase(text, &format!("{name} free"))
+                || ingredient.split(' ').any(|word| {
+                    word.strip_prefix(name)
+                        .is_some_and(|rest| rest.starts_with("frei"))
+                })
+        })
+    }
+}
+
+/// An [allergen](Allergen) found in an ingredient of a recipe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllergenMatch<'a> {
+    /// The allergen that was found.
+    pub allergen: Allergen,
+    /// The ingredient that contains the allergen.
+    pub ingredient: &'a Ingredient,
+    /// The keyword that triggered the match, e.g. "flour".
+    pub keyword: &'static str,
+}
+
+/// Finds all allergens in the ingredients of the recipe, ordered by allergen
+pub(crate) fn find_allergens(recipe: &Recipe) -> Vec<AllergenMatch<'_>> {
+    let mut result = Vec::new();
+    for allergen in Allergen::ALL {
+        for ingredient in recipe.ingredients() {
+            if let Some(keyword) = allergen.find_in(ingredient) {
+                result.push(AllergenMatch {
+                    allergen,
+                    ingredient,
+                    keyword,
+                });
+            }
+        }
+    }
+    result
+}
+
+/// Keywords (English and German) and exclusions for each allergen
+#[allow(clippy::type_complexity)]
+const ALLERGEN_KEYWORDS: &[(Allergen, &[&str], &[&str])] = &[
+    (
+        Allergen::Gluten,
+        &[
+            "wheat",
+            "flour",
+            "bread",
+            "breadcrumbs",
+            "panko",
+            "pasta",
+            "spaghetti",
+            "noodles",
+            "barley",
+            "rye",
+            "spelt",
+            "semolina",
+            "couscous",
+            "bulgur",
+            "oats",
+            "beer",
+            "soy sauce",
+            "tortilla",
+            "baguette",
+            "weizen",
+            "weizenmehl",
+            "mehl",
+            "dinkel",
+            "dinkelmehl",
+            "roggen",
+            "gerste",
+            "hafer",
+            "haferflocken",
+            "brot",
+            "nudeln",
+            "paniermehl",
+            "semmelbrösel",
+            "grieß",
+            "bier",
+            "sojasauce",
+        ],
+        &[
+            "almond flour",
+            "rice flour",
+            "coconut flour",
+            "corn flour",
+            "buckwheat flour",
+            "chickpea flour",
+            "potato flour",
+            "rice noodles",
+            "glass noodles",
+            "mandelmehl",
+            "reismehl",
+            "kokosmehl",
+            "maismehl",
+            "reisnudeln",
+        ],
+    ),
+    (
+        Allergen::Crustaceans,
+        &[
+            "shrimp",
+            "prawn",
+            "crab",
+            "lobster",
+            "crayfish",
+            "langoustine",
+            "garnele",
+            "garnelen",
+            "krabbe",
+            "krabben",
+            "hummer",
+            "languste",
+            "flusskrebs",
+            "krebs",
+        ],
+        &[],
+    ),
+    (
+        Allergen::Eggs,
+        &[
+            "egg",
+            "yolk",
+            "mayonnaise",
+            "mayo",
+            "meringue",
+            "aioli",
+            "ei",
+            "eier",
+            "eigelb",
+            "eidotter",
+            "eiweiß",
+            "eiklar",
+        ],
+        &["egg replacer"],
+    ),
+    (
+        Allergen::Fish,
+        &[
+            "fish",
+            "salmon",
+            "tuna",
+            "cod",
+            "anchovy",
+            "anchovies",
+            "sardine",
+            "trout",
+            "haddock",
+            "mackerel",
+            "herring",
+            "fish sauce",
+            "worcestershire",
+            "fisch",
+            "lachs",
+            "thunfisch",
+            "kabeljau",
+            "sardelle",
+            "sardellen",
+            "forelle",
+            "makrele",
+            "hering",
+            "fischsauce",
+        ],
+        &[],
+    ),
+    (
+        Allergen::Peanuts,
+        &[
+            "peanut",
+            "groundnut",
+            "erdnuss",
+            "erdnüsse",
+            "erdnussbutter",
+            "erdnussöl",
+        ],
+        &[],
+    ),
+    (
+        Allergen::Soy,
+        &[
+            "soy",
+            "soya",
+            "soy sauce",
+            "tofu",
+            "tempeh",
+            "edamame",
+            "miso",
+            "soja",
+            "sojasauce",
+            "sojasoße",
+            "sojamilch",
+        ],
+        &[],
+    ),
+    (
+        Allergen::Milk,
+        &[
+            "milk",
+            "butter",
+            "cream",
+            "cheese",
+            "yogurt",
+            "yoghurt",
+            "buttermilk",
+            "parmesan",
+            "mozzarella",
+            "cheddar",
+            "ricotta",
+            "feta",
+            "ghee",
+            "whey",
+            "mascarpone",
+            "crème fraîche",
+            "creme fraiche",
+            "milch",
+            "sahne",
+            "käse",
+            "joghurt",
+            "quark",
+            "buttermilch",
+            "schmand",
+            "frischkäse",
+            "schlagsahne",
+            "butterschmalz",
+            "vollmilch",
+        ],
+        &[
+            "peanut butter",
+            "almond butter",
+            "nut butter",
+            "cocoa butter",
+            "shea butter",
+            "vegan butter",
+            "coconut milk",
+            "almond milk",
+            "oat milk",
+            "soy milk",
+            "rice milk",
+            "coconut cream",
+            "cream of tartar",
+            "kokosmilch",
+            "mandelmilch",
+            "hafermilch",
+            "sojamilch",
+            "erdnussbutter",
+        ],
+    ),
+    (
+        Allergen::Nuts,
+        &[
+            "nut",
+            "almond",
+            "hazelnut",
+            "walnut",
+            "cashew",
+            "pecan",
+            "pistachio",
+            "macadamia",
+            "brazil nut",
+            "praline",
+            "marzipan",
+            "nuss",
+            "nüsse",
+            "mandel",
+            "mandeln",
+            "haselnuss",
+            "haselnüsse",
+            "walnuss",
+            "walnüsse",
+            "pekannuss",
+            "pistazie",
+            "pistazien",
+            "mandelmehl",
+        ],
+        &["butternut"],
+    ),
+    (
+        Allergen::Celery,
+        &[
+            "celery",
+            "celeriac",
+            "sellerie",
+            "staudensellerie",
+            "knollensellerie",
+            "selleriesalz",
+        ],
+        &[],
+    ),
+    (
+        Allergen::Mustard,
+        &["mustard", "dijon", "senf", "senfkörner"],
+        &[],
+    ),
+    (
+        Allergen::Sesame,
+        &["sesame", "tahini", "sesam", "sesamöl"],
+        &[],
+    ),
+    (
+        Allergen::Sulphites,
+        &[
+            "wine",
+            "sulphite",
+            "sulfite",
+            "sulphur dioxide",
+            "dried apricot",
+            "balsamic",
+            "wein",
+            "weißwein",
+            "rotwein",
+            "sulfit",
+            "balsamico",
+        ],
+        &[],
+    ),
+    (Allergen::Lupin, &["lupin", "lupine", "lupinenmehl"], &[]),
+    (
+        Allergen::Molluscs,
+        &[
+            "mussel",
+            "clam",
+            "oyster",
+            "scallop",
+            "squid",
+            "octopus",
+            "snail",
+            "calamari",
+            "muschel",
+            "muscheln",
+            "miesmuschel",
+            "miesmuscheln",
+            "auster",
+            "austern",
+            "jakobsmuschel",
+            "tintenfisch",
+            "oktopus",
+            "schnecke",
+            "schnecken",
+        ],
+        &["oyster mushroom", "austernpilz", "austernpilze"],
+    ),
+];
+
+/// The names used in "<name>-free" and "<name>frei" labels for each allergen, in English
+/// and German
+const FREE_FROM_NAMES: &[(Allergen, &[&str])] = &[
+    (Allergen::Gluten, &["gluten", "wheat", "weizen"]),
+    (Allergen::Crustaceans, &["shellfish"]),
+    (Allergen::Eggs, &["egg", "ei"]),
+    (Allergen::Fish, &["fish", "fisch"]),
+    (Allergen::Peanuts, &["peanut", "erdnuss"]),
+    (Allergen::Soy, &["soy", "soja"]),
+    (Allergen::Milk, &["dairy", "milk", "milch"]),
+    (Allergen::Nuts, &["nut", "tree nut", "nuss"]),
+    (Allergen::Celery, &["celery", "sellerie"]),
+    (Allergen::Mustard, &["mustard", "senf"]),
+    (Allergen::Sesame, &["sesame", "sesam"]),
+    (Allergen::Sulphites, &["sulphite", "sulfite", "sulfit"]),
+    (Allergen::Lupin, &["lupin", "lupinen"]),
+    (Allergen::Molluscs, &["shellfish"]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod keywords {
+        use super::*;
+
+        #[test]
+        fn find_english() {
+            assert_eq!(
+                Allergen::Gluten.find_in("2 cups all-purpose flour"),
+                Some("flour")
+            );
+            assert_eq!(Allergen::Eggs.find_in("3 large eggs"), Some("egg"));
+            assert_eq!(
+                Allergen::Milk.find_in("100 g unsalted butter"),
+                Some("butter")
+            );
+            assert_eq!(Allergen::Molluscs.find_in("500 g mussels"), Some("mussel"));
+        }
+
+        #[test]
+        fn find_german() {
+            assert_eq!(
+                Allergen::Gluten.find_in("500 g Weizenmehl"),
+                Some("weizenmehl")
+            );
+            assert_eq!(Allergen::Eggs.find_in("2 Eier"), Some("eier"));
+            assert_eq!(Allergen::Milk.find_in("200 ml Sahne"), Some("sahne"));
+            assert_eq!(
+                Allergen::Nuts.find_in("50 g gehackte Haselnüsse"),
+                Some("haselnüsse")
+            );
+        }
+
+        #[test]
+        fn exclusions() {
+            assert_eq!(Allergen::Milk.find_in("2 tbsp peanut butter"), None);
+            assert_eq!(
+                Allergen::Peanuts.find_in("2 tbsp peanut butter"),
+                Some("peanut")
+            );
+            assert_eq!(Allergen::Milk.find_in("1 can coconut milk"), None);
+            assert_eq!(Allergen::Gluten.find_in("100 g almond flour"), None);
+            assert_eq!(Allergen::Molluscs.find_in("200 g oyster mushrooms"), None);
+        }
+
+        #[test]
+        fn free_from_labels() {
+            assert_eq!(Allergen::Gluten.find_in("200 g gluten-free flour"), None);
+            assert_eq!(Allergen::Gluten.find_in("1 loaf gluten-free bread"), None);
+            assert_eq!(Allergen::Gluten.find_in("200 g glutenfreies Mehl"), None);
+            assert_eq!(Allergen::Milk.find_in("100 g dairy-free butter"), None);
+            assert_eq!(Allergen::Milk.find_in("100 g milchfreie Butter"), None);
+            assert_eq!(Allergen::Eggs.find_in("2 tbsp egg-free mayonnaise"), None);
+            assert_eq!(Allergen::Nuts.find_in("100 g nut free praline"), None);
+        }
+
+        #[test]
+        fn free_from_label_only_covers_its_allergen() {
+            assert_eq!(
+                Allergen::Milk.find_in("1 loaf gluten-free bread with butter"),
+                Some("butter")
+            );
+            assert_eq!(
+                Allergen::Nuts.find_in("2 peanut-free walnuts"),
+                Some("walnut")
+            );
+            assert_eq!(
+                Allergen::Eggs.find_in("2 eggs, freshly beaten"),
+                Some("egg")
+            );
+        }
+
+        #[test]
+        fn exclusion_only_covers_its_own_words() {
+            assert_eq!(
+                Allergen::Milk.find_in("coconut milk and whole milk"),
+                Some("milk")
+            );
+        }
+
+        #[test]
+        fn whole_words_only() {
+            assert_eq!(Allergen::Eggs.find_in("1 eggplant"), None);
+            assert_eq!(Allergen::Nuts.find_in("1 tsp nutmeg"), None);
+            assert_eq!(Allergen::Nuts.find_in("1 coconut"), None);
+            assert_eq!(Allergen::Nuts.find_in("1 butternut squash"), None);
+        }
+    }
+
+    mod recipe {
+        use super::*;
+
+        #[test]
+        fn find_allergens_in_recipe() {
+            let recipe = Recipe::from_lines(
+                &[
+                    "200 g flour",
+                    "2 eggs",
+                    "100 ml milk",
+                    "1 pinch salt",
+                    "1 egg yolk",
+                ],
+                &[],
+            );
+            let result: Vec<_> = recipe
+                .allergens()
+                .iter()
+                .map(|m| (m.allergen, m.ingredient.as_str()))
+                .collect();
+            assert_eq!(
+                result,
+                vec![
+                    (Allergen::Gluten, "200 g flour"),
+                    (Allergen::Eggs, "2 eggs"),
+                    (Allergen::Eggs, "1 egg yolk"),
+                    (Allergen::Milk, "100 ml milk"),
+                ]
+            );
+        }
+
+        #[test]
+        fn ignore_free_from_products() {
+            let recipe = Recipe::from_lines(
+                &[
+                    "200 g gluten-free flour",
+                    "1 loaf gluten-free bread",
+                    "100 g dairy-free butter",
+                    "2 tbsp egg-free mayonnaise",
+                ],
+                &[],
+            );
+            assert!(recipe.allergens().is_empty());
+        }
+
+        #[test]
+        fn no_allergens() {
+            let recipe = Recipe::default();
+            assert!(recipe.allergens().is_empty());
+        }
+    }
+}
@@ -2,12 +2,44 @@ pub struct LdFields;
 
 /// Constants for JSON-LD fields used in the recipe schema
 impl LdFields {
+    pub const CONTEXT: &'static str = "@context";
     pub const TYPE: &'static str = "@type";
+    pub const ID: &'static str = "@id";
     pub const NAME: &'static str = "name";
     pub const TEXT: &'static str = "text";
     pub const AUTHOR: &'static str = "author";
     pub const DESCRIPTION: &'static str = "description";
     pub const RECIPE_INGREDIENT: &'static str = "recipeIngredient";
+    pub const INGREDIENTS: &'static str = "ingredients";
     pub const RECIPE_INSTRUCTIONS: &'static str = "recipeInstructions";
     pub const ITEM_LIST_ELEMENT: &'static str = "itemListElement";
+    pub const SUITABLE_FOR_DIET: &'static str = "suitableForDiet";
+    pub const RECIPE_YIELD: &'static str = "recipeYield";
+    pub const PREP_TIME: &'static str = "prepTime";
+    pub const COOK_TIME: &'static str = "cookTime";
+    pub const TOTAL_TIME: &'static str = "totalTime";
+    pub const TOOL: &'static str = "tool";
+    pub const NUTRITION: &'static str = "nutrition";
+    pub const SERVING_SIZE: &'static str = "servingSize";
+    pub const CALORIES: &'static str = "calories";
+    pub const FAT_CONTENT: &'static str = "fatContent";
+    pub const SATURATED_FAT_CONTENT: &'static str = "saturatedFatContent";
+    pub const CARBOHYDRATE_CONTENT: &'static str = "carbohydrateContent";
+    pub const SUGAR_CONTENT: &'static str = "sugarContent";
+    pub const FIBER_CONTENT: &'static str = "fiberContent";
+    pub const PROTEIN_CONTENT: &'static str = "proteinContent";
+    pub const SODIUM_CONTENT: &'static str = "sodiumContent";
+    pub const IMAGE: &'static str = "image";
+    pub const URL: &'static str = "url";
+    pub const DATE_PUBLISHED: &'static str = "datePublished";
+    pub const KEYWORDS: &'static str = "keywords";
+    pub const RECIPE_CATEGORY: &'static str = "recipeCategory";
+    pub const RECIPE_CUISINE: &'static str = "recipeCuisine";
+    pub const AGGREGATE_RATING: &'static str = "aggregateRating";
+    pub const RATING_VALUE: &'static str = "ratingValue";
+    pub const RATING_COUNT: &'static str = "ratingCount";
+    pub const REVIEW_COUNT: &'static str = "reviewCount";
+    pub const VIDEO: &'static str = "video";
+    pub const THUMBNAIL_URL: &'static str = "thumbnailUrl";
+    pub const UPLOAD_DATE: &'static str = "uploadDate";
 }
@@ -0,0 +1,644 @@
+use crate::ingredient::{parse_amount, parse_ingredient};
+use crate::units::{UnitSystem, format_decimal, format_fraction};
+use crate::{HowToSection, IngredientSection, Recipe};
+
+/// A converter to rewrite the quantities of a recipe to another [unit system](UnitSystem)
+///
+/// Ingredient quantities are converted like [Quantity::convert](crate::Quantity::convert),
+/// teaspoons and tablespoons can be kept with [with_spoons](UnitConverter::with_spoons).
+/// In the text of the steps, temperatures (°F, °C, gas mark), lengths (inch, cm, mm) and
+/// pan sizes (9x13-inch) are converted.
+///
+/// ## Example
+///
+/// ```
+/// use reget::{UnitConverter, UnitSystem};
+///
+/// let converter = UnitConverter::new(UnitSystem::Metric).with_original();
+/// assert_eq!(
+///     converter.convert_step("Bake at 350°F in a 9x13-inch pan."),
+///     "Bake at 180°C (350°F) in a 23x33 cm (9x13-inch) pan."
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct UnitConverter {
+    /// The system everything is converted to
+    system: UnitSystem,
+    /// Whether the original text is kept in parentheses after the converted text
+    keep_original: bool,
+    /// Whether teaspoons and tablespoons are kept instead of converted
+    keep_spoons: bool,
+}
+
+impl UnitConverter {
+    /// Constructs a new UnitConverter for the given [unit system](UnitSystem)
+    pub fn new(system: UnitSystem) -> Self {
+        UnitConverter {
+            system,
+            keep_original: false,
+            keep_spoons: false,
+        }
+    }
+
+    /// Keeps the original quantity in parentheses after the converted one, e.g.
+    /// "180°C (350°F)"
+    pub fn with_original(mut self) -> Self {
+        self.keep_original = true;
+        self
+    }
+
+    /// Keeps teaspoons and tablespoons, which are common in metric recipes too, instead of
+    /// converting them to milliliters or grams
+    pub fn with_spoons(mut self) -> Self {
+        self.keep_spoons = true;
+        self
+    }
+
+    /// Returns a copy of the recipe with its ingredients and steps converted
+    pub fn convert(&self, recipe: &Recipe) -> Recipe {
+        Recipe {
+            ingredient_sections: recipe
+                .ingredient_sections
+                .iter()
+                .map(|section| IngredientSection {
+                    name: section.name.clone(),
+                    ingredients: section
+                        .ingredients
+                        .iter()
+                        .map(|ingredient| self.convert_ingredient(ingredient))
+                        .collect(),
+                })
+                .collect(),
+            how_to_sections: recipe
+                .how_to_sections
+                .iter()
+                .map(|section| HowToSection {
+                    name: section.name.clone(),
+                    steps: section
+                        .steps
+                        .iter()
+                        .map(|step| self.convert_step(step))
+                        .collect(),
+                })
+                .collect(),
+            ..recipe.clone()
+        }
+    }
+
+    /// Converts a single ingredient line
+    ///
+    /// Lines without a recognized [quantity](crate::Quantity) are returned unchanged.
+    pub fn convert_ingredient(&self, line: &str) -> String {
+        let parsed = parse_ingredient(line);
+        let Some(quantity) = parsed.quantity() else {
+            return line.to_string();
+        };
+        if self.keep_spoons && quantity.unit.system().is_none() {
+            return line.to_string();
+        }
+        let converted = quantity.convert(self.system, &parsed.name);
+        if converted == quantity {
+            return line.to_string();
+        }
+        if self.keep_original {
+            format!(
+                "{} ({}) {}",
+                converted.format(),
+                quantity.format(),
+                parsed.name
+            )
+        } else {
+            format!("{} {}", converted.format(), parsed.name)
+        }
+    }
+
+    /// Converts temperatures, lengths and pan sizes in the text of a step
+    pub fn convert_step(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        let mut previous: Option<char> = None;
+
+        while let Some(c) = rest.chars().next() {
+            let at_word_start = !previous.is_some_and(char::is_alphanumeric);
+            if at_word_start && let Some((measurement, after)) = parse_measurement(rest) {
+                let original = &rest[..rest.len() - after.len()];
+                match measurement.convert(self.system) {
+                    Some(converted) if self.keep_original => {
+                        result.push_str(&format!("{converted} ({original})"))
+                    }
+                    Some(converted) => result.push_str(&converted),
+                    None => result.push_str(original),
+                }
+                previous = original.chars().next_back();
+                rest = after;
+                continue;
+            }
+            result.push(c);
+            previous = Some(c);
+            rest = &rest[c.len_utf8()..];
+        }
+
+        result
+    }
+}
+
+/// The units that are recognized in the text of a step
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StepUnit {
+    Fahrenheit,
+    Celsius,
+    GasMark,
+    Inch,
+    Centimeter,
+    Millimeter,
+}
+
+/// A temperature, length or pan size found in the text of a step
+#[derive(Debug, Clone, PartialEq)]
+struct Measurement {
+    /// A single value, the bounds of a range or the dimensions of a pan
+    values: Vec<f64>,
+    /// Whether the values are dimensions (9x13) rather than a range (350-375)
+    dimensions: bool,
+    unit: StepUnit,
+    /// Whether the unit was attached with a hyphen, e.g. "9-inch"
+    hyphen: bool,
+}
+
+/// Temperatures in °C for gas marks 1 to 9.
+const GAS_MARKS_CELSIUS: [f64; 9] = [
+    140.0, 150.0, 170.0, 180.0, 190.0, 200.0, 220.0, 230.0, 240.0,
+];
+/// Temperatures in °F for gas marks 1 to 9.
+const GAS_MARKS_FAHRENHEIT: [f64; 9] = [
+    275.0, 300.0, 325.0, 350.0, 375.0, 400.0, 425.0, 450.0, 475.0,
+];
+
+impl Measurement {
+    /// Converts the measurement to the given system, returns `None` if it already belongs to it
+    fn convert(&self, system: UnitSystem) -> Option<String> {
+        let (values, unit): (Vec<f64>, StepUnit) = match (self.unit, system) {
+            (StepUnit::Fahrenheit, UnitSystem::Metric) => (
+                self.values
+                    .iter()
+                    .map(|f| round_celsius((f - 32.0) * 5.0 / 9.0))
+                    .collect(),
+                StepUnit::Celsius,
+            ),
+            (StepUnit::Celsius, UnitSystem::UsCustomary) => (
+                self.values
+                    .iter()
+                    .map(|c| round_fahrenheit(c * 9.0 / 5.0 + 32.0))
+                    .collect(),
+                StepUnit::Fahrenheit,
+            ),
+            (StepUnit::GasMark, _) => {
+                let (table, unit) = match system {
+                    UnitSystem::Metric => (GAS_MARKS_CELSIUS, StepUnit::Celsius),
+                    UnitSystem::UsCustomary => (GAS_MARKS_FAHRENHEIT, StepUnit::Fahrenheit),
+                };
+                let values = self
+                    .values
+                    .iter()
+                    .map(|mark| table.get((*mark as usize).checked_sub(1)?).copied())
+                    .collect::<Option<_>>()?;
+                (values, unit)
+            }
+            (StepUnit::Inch, UnitSystem::Metric) => (
+                self.values
+                    .iter()
+                    .map(|i| round_centimeters(i * 2.54))
+                    .collect(),
+                StepUnit::Centimeter,
+            ),
+            (StepUnit::Centimeter, UnitSystem::UsCustomary) => (
+                self.values
+                    .iter()
+                    .map(|cm| round_inches(cm / 2.54))
+                    .collect(),
+                StepUnit::Inch,
+            ),
+            (StepUnit::Millimeter, UnitSystem::UsCustomary) => (
+                self.values
+                    .iter()
+                    .map(|mm| round_inches(mm / 25.4))
+                    .collect(),
+                StepUnit::Inch,
+            ),
+            _ => return None,
+        };
+
+        let separator = if self.dimensions { "x" } else { "-" };
+        let formatted = values
+            .iter()
+            .map(|v| match unit {
+                StepUnit::Inch => format_fraction(*v),
+                _ => format_decimal(*v),
+            })
+            .collect::<Vec<_>>()
+            .join(separator);
+        let last = values.last().copied().unwrap_or_default();
+
+        Some(match unit {
+            StepUnit::Celsius => format!("{formatted}°C"),
+            StepUnit::Fahrenheit => format!("{formatted}°F"),
+            StepUnit::Inch if self.hyphen || self.dimensions => format!("{formatted}-inch"),
+            StepUnit::Inch if last > 1.0 => format!("{formatted} inches"),
+            StepUnit::Inch => format!("{formatted} inch"),
+            _ => format!("{formatted} cm"),
+        })
+    }
+}
+
+/// Rounds oven temperatures to steps of 10°C, lower temperatures to whole degrees.
+fn round_celsius(celsius: f64) -> f64 {
+    if celsius >= 100.0 {
+        (celsius / 10.0).round() * 10.0
+    } else {
+        celsius.round()
+    }
+}
+
+/// Rounds oven temperatures to steps of 25°F, lower temperatures to steps of 5°F.
+fn round_fahrenheit(fahrenheit: f64) -> f64 {
+    if fahrenheit >= 200.0 {
+        (fahrenheit / 25.0).round() * 25.0
+    } else {
+        (fahrenheit / 5.0).round() * 5.0
+    }
+}
+
+/// Rounds larger lengths to whole centimeters, smaller ones to half centimeters.
+fn round_centimeters(centimeters: f64) -> f64 {
+    if centimeters >= 4.0 {
+        centimeters.round()
+    } else {
+        ((centimeters * 2.0).round() / 2.0).max(0.5)
+    }
+}
+
+/// Rounds larger lengths to whole inches, smaller ones are rounded to eighths when formatting.
+fn round_inches(inches: f64) -> f64 {
+    if inches >= 4.0 {
+        inches.round()
+    } else {
+        inches
+    }
+}
+
+/// Tries to parse a measurement at the start of the text.
+///
+/// It deals with:
+///     - "350°F", "350 °F", "350F", "350 degrees F", "180 Grad", "350-375°F"
+///     - "gas mark 4"
+///     - "2 inches", "2-inch", "2\"", "5 cm", "10 mm"
+///     - "9x13-inch", "9 x 13 inch", "9-by-13-inch", "20 x 30 cm"
+fn parse_measurement(text: &str) -> Option<(Measurement, &str)> {
+    if let Some(after) = text
+        .get(..8)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("gas mark"))
+        .map(|_| text[8..].trim_start())
+    {
+        let (mark, rest) = parse_amount(after)?;
+        let measurement = Measurement {
+            values: vec![mark],
+            dimensions: false,
+            unit: StepUnit::GasMark,
+            hyphen: false,
+        };
+        return Some((measurement, rest));
+    }
+
+    let (first, mut rest) = parse_amount(text)?;
+    let mut values = vec![first];
+
+    while let Some((value, after)) = strip_dimension_separator(rest).and_then(parse_amount) {
+        values.push(value);
+        rest = after;
+    }
+    let dimensions = values.len() > 1;
+
+    if !dimensions && let Some((value, after)) = strip_range_separator(rest).and_then(parse_amount)
+    {
+        values.push(value);
+        rest = after;
+    }
+
+    if !dimensions && let Some((unit, after)) = parse_temperature_unit(rest) {
+        let measurement = Measurement {
+            values,
+            dimensions,
+            unit,
+            hyphen: false,
+        };
+        return Some((measurement, after));
+    }
+
+    let (unit, hyphen, after) = parse_length_unit(rest)?;
+    let measurement = Measurement {
+        values,
+        dimensions,
+        unit,
+        hyphen,
+    };
+    Some((measurement, after))
+}
+
+/// Strips a separator between dimensions like "x", " x ", "×" or "-by-".
+fn strip_dimension_separator(text: &str) -> Option<&str> {
+    let text = text.trim_start_matches([' ', '-']);
+    let text = text
+        .strip_prefix(['x', 'X', '×'])
+        .or_else(|| text.strip_prefix("by"))?;
+    Some(text.trim_start_matches([' ', '-']))
+}
+
+/// Strips a separator between the bounds of a range like "-", "–" or " to ".
+fn strip_range_separator(text: &str) -> Option<&str> {
+    let text = text.trim_start();
+    let text = text
+        .strip_prefix(['-', '–'])
+        .or_else(|| text.strip_prefix("to "))?;
+    Some(text.trim_start())
+}
+
+/// Parses a temperature unit like "°F", " °C", "F", " degrees Fahrenheit" or " Grad".
+fn parse_temperature_unit(text: &str) -> Option<(StepUnit, &str)> {
+    // Directly attached, e.g. "350F"
+    if let Some(unit) = text.chars().next().and_then(temperature_scale) {
+        let rest = &text[1..];
+        return is_word_end(rest).then_some((unit, rest));
+    }
+
+    let trimmed = text.trim_start();
+    let after_symbol = trimmed
+        .strip_prefix(['°', 'º'])
+        .or_else(|| strip_prefix_ignore_case(trimmed, "degrees"));
+    if let Some(after) = after_symbol {
+        let after = after.trim_start();
+        for (word, unit) in [
+            ("fahrenheit", StepUnit::Fahrenheit),
+            ("celsius", StepUnit::Celsius),
+            ("f", StepUnit::Fahrenheit),
+            ("c", StepUnit::Celsius),
+        ] {
+            if let Some(rest) = strip_prefix_ignore_case(after, word)
+                && is_word_end(rest)
+            {
+                return Some((unit, rest));
+            }
+        }
+        return None;
+    }
+
+    // German recipes give oven temperatures in "Grad", which is always Celsius
+    let rest = strip_prefix_ignore_case(trimmed, "grad")?;
+    is_word_end(rest).then_some((StepUnit::Celsius, rest))
+}
+
+/// Returns the temperature scale for a single letter "F" or "C".
+fn temperature_scale(c: char) -> Option<StepUnit> {
+    match c {
+        'F' => Some(StepUnit::Fahrenheit),
+        'C' => Some(StepUnit::Celsius),
+        _ => None,
+    }
+}
+
+/// Parses a length unit like " inches", "-inch", "\"", " cm" or "mm".
+///
+/// Returns the unit, whether it was attached with a hyphen and the remaining text.
+fn parse_length_unit(text: &str) -> Option<(StepUnit, bool, &str)> {
+    if let Some(rest) = text.strip_prefix(['"', '″']) {
+        return Some((StepUnit::Inch, false, rest));
+    }
+
+    let hyphen = text.starts_with('-');
+    let trimmed = text.strip_prefix('-').unwrap_or(text).trim_start();
+    for (word, unit) in [
+        ("inches", StepUnit::Inch),
+        ("inch", StepUnit::Inch),
+        ("centimeters", StepUnit::Centimeter),
+        ("centimetres", StepUnit::Centimeter),
+        ("cm", StepUnit::Centimeter),
+        ("mm", StepUnit::Millimeter),
+    ] {
+        if let Some(rest) = strip_prefix_ignore_case(trimmed, word)
+            && is_word_end(rest)
+        {
+            return Some((unit, hyphen, rest));
+        }
+    }
+    None
+}
+
+/// Strips an ASCII prefix ignoring its case.
+fn strip_prefix_ignore_case<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    text.get(..prefix.len())
+        .filter(|start| start.eq_ignore_ascii_case(prefix))
+        .map(|_| &text[prefix.len()..])
+}
+
+/// Checks that the text does not continue the current word.
+fn is_word_end(text: &str) -> bool {
+    !text.starts_with(char::is_alphabetic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod ingredients {
+        use super::*;
+
+        #[test]
+        fn convert_to_metric() {
+            let converter = UnitConverter::new(UnitSystem::Metric);
+            let result = converter.convert_ingredient("1 1/2 cups milk");
+            assert_eq!(result, "355 ml milk");
+        }
+
+        #[test]
+        fn convert_to_us() {
+            let converter = UnitConverter::new(UnitSystem::UsCustomary);
+            let result = converter.convert_ingredient("250 g butter");
+            assert_eq!(result, "1 1/8 cups butter");
+        }
+
+        #[test]
+        fn convert_with_original() {
+            let converter = UnitConverter::new(UnitSystem::Metric).with_original();
+            let result = converter.convert_ingredient("1 lb beef");
+            assert_eq!(result, "454 g (1 lb) beef");
+        }
+
+        #[test]
+        fn convert_unchanged() {
+            let converter = UnitConverter::new(UnitSystem::Metric);
+            assert_eq!(converter.convert_ingredient("3 eggs"), "3 eggs");
+            assert_eq!(converter.convert_ingredient("salt"), "salt");
+        }
+
+        #[test]
+        fn convert_spoons() {
+            let converter = UnitConverter::new(UnitSystem::Metric);
+            assert_eq!(converter.convert_ingredient("1 tsp salt"), "6 g salt");
+            assert_eq!(
+                converter.convert_ingredient("2 tbsp olive oil"),
+                "30 ml olive oil"
+            );
+            let converter = converter.with_spoons();
+            assert_eq!(converter.convert_ingredient("1 tsp salt"), "1 tsp salt");
+        }
+    }
+
+    mod temperatures {
+        use super::*;
+
+        #[test]
+        fn convert_fahrenheit() {
+            let converter = UnitConverter::new(UnitSystem::Metric);
+            assert_eq!(converter.convert_step("Bake at 350°F."), "Bake at 180°C.");
+            assert_eq!(converter.convert_step("Bake at 350 °F."), "Bake at 180°C.");
+            assert_eq!(converter.convert_step("Bake at 350F."), "Bake at 180°C.");
+            assert_eq!(
+                converter.convert_step("Bake at 425 degrees Fahrenheit."),
+                "Bake at 220°C."
+            );
+        }
+
+        #[test]
+        fn convert_celsius() {
+            let converter = UnitConverter::new(UnitSystem::UsCustomary);
+            assert_eq!(
+                converter.convert_step("Bei 180 °C backen."),
+                "Bei 350°F backen."
+            );
+            assert_eq!(
+                converter.convert_step("Bei 200 Grad backen."),
+                "Bei 400°F backen."
+            );
+        }
+
+        #[test]
+        fn convert_range() {
+            let converter = UnitConverter::new(UnitSystem::Metric);
+            assert_eq!(
+                converter.convert_step("Heat to 350-375°F."),
+                "Heat to 180-190°C."
+            );
+        }
+
+        #[test]
+        fn convert_gas_mark() {
+            let converter = UnitConverter::new(UnitSystem::Metric);
+            assert_eq!(
+                converter.convert_step("Bake at gas mark 4."),
+                "Bake at 180°C."
+            );
+            let converter = UnitConverter::new(UnitSystem::UsCustomary);
+            assert_eq!(
+                converter.convert_step("Bake at Gas Mark 6."),
+                "Bake at 400°F."
+            );
+        }
+
+        #[test]
+        fn keep_target_system() {
+            let converter = UnitConverter::new(UnitSystem::Metric);
+            assert_eq!(converter.convert_step("Bake at 180°C."), "Bake at 180°C.");
+        }
+    }
+
+    mod lengths {
+        use super::*;
+
+        #[test]
+        fn convert_inches() {
+            let converter = UnitConverter::new(UnitSystem::Metric);
+            assert_eq!(
+                converter.convert_step("Cut into 2-inch pieces."),
+                "Cut into 5 cm pieces."
+            );
+            assert_eq!(
+                converter.convert_step("Roll out 1/4 inch thick."),
+                "Roll out 0.5 cm thick."
+            );
+        }
+
+        #[test]
+        fn convert_centimeters() {
+            let converter = UnitConverter::new(UnitSystem::UsCustomary);
+            assert_eq!(
+                converter.convert_step("Slice into 1 cm strips."),
+                "Slice into 3/8 inch strips."
+            );
+        }
+
+        #[test]
+        fn convert_pan_sizes() {
+            let converter = UnitConverter::new(UnitSystem::Metric);
+            assert_eq!(
+                converter.convert_step("Use a 9x13-inch pan."),
+                "Use a 23x33 cm pan."
+            );
+            assert_eq!(
+                converter.convert_step("Use a 9-by-13-inch pan."),
+                "Use a 23x33 cm pan."
+            );
+            let converter = UnitConverter::new(UnitSystem::UsCustomary);
+            assert_eq!(
+                converter.convert_step("Use a 20 x 30 cm pan."),
+                "Use a 8x12-inch pan."
+            );
+        }
+    }
+
+    mod steps {
+        use super::*;
+
+        #[test]
+        fn ignore_other_numbers() {
+            let converter = UnitConverter::new(UnitSystem::Metric);
+            let step = "Bake for 25 minutes, then let 2 x 3 pieces rest.";
+            assert_eq!(converter.convert_step(step), step);
+        }
+
+        #[test]
+        fn ignore_numbers_inside_words() {
+            let converter = UnitConverter::new(UnitSystem::Metric);
+            let step = "Use the A4F setting.";
+            assert_eq!(converter.convert_step(step), step);
+        }
+
+        #[test]
+        fn keep_original() {
+            let converter = UnitConverter::new(UnitSystem::Metric).with_original();
+            assert_eq!(
+                converter.convert_step("Bake at 350°F for 25 minutes in a 9x13-inch pan."),
+                "Bake at 180°C (350°F) for 25 minutes in a 23x33 cm (9x13-inch) pan."
+            );
+        }
+
+        #[test]
+        fn convert_recipe() {
+            let recipe = Recipe {
+                ingredient_sections: vec![IngredientSection {
+                    name: None,
+                    ingredients: vec!["1 cup milk".into()],
+                }],
+                how_to_sections: vec![HowToSection {
+                    name: None,
+                    steps: vec!["Bake at 350°F.".into()],
+                }],
+                ..Default::default()
+            };
+            let result = UnitConverter::new(UnitSystem::Metric).convert(&recipe);
+            assert_eq!(
+                result.ingredient_sections[0].ingredients,
+                vec!["237 ml milk"]
+            );
+            assert_eq!(result.how_to_sections[0].steps, vec!["Bake at 180°C."]);
+        }
+    }
+}
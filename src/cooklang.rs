@@ -0,0 +1,1000 @@
+use crate::ingredient::{COUNT_NOUNS, parse_ingredient};
+use crate::json_ld::iso_duration;
+use crate::steps::{find_mentions, match_terms};
+use crate::text::{find_normalized, normalize_text, precedes_object, starts_clause};
+use crate::timers::{format_duration, parse_time};
+use crate::units::format_decimal;
+use crate::{
+    EquipmentVocabulary, HowToSection, HowToStep, Ingredient, IngredientSection, Recipe,
+    find_durations,
+};
+
+use std::fmt::Write;
+use std::ops::Range;
+use std::time::Duration;
+
+/// A builder to convert a recipe to a [Cooklang](https://cooklang.org) string
+///
+/// Ingredients are marked in the first step that mentions them, together with their
+/// quantity and under their own name, e.g. `@all-purpose flour{2%cups}` for "Stir in the
+/// flour". Ingredients that are not mentioned in any step are marked in a step of their
+/// own at the beginning. Equipment of the [vocabulary](EquipmentVocabulary) is marked as
+/// cookware and durations as timers.
+///
+/// ## Example
+///
+/// ```
+/// let recipe = reget::Recipe::default();
+/// recipe
+///     .to_cooklang()
+///     .with_url("https://example.org/recipe")
+///     .with_timers(false)
+///     .convert();
+/// ```
+///
+/// <details>
+/// <summary>Example Output</summary>
+///
+/// ```text
+/// >> title: Pancakes
+/// >> author: ...
+/// >> source: https://example.org/recipe
+/// >> servings: 4
+///
+/// Whisk @eggs{2} and @milk{250%ml} in a #bowl{}.
+///
+/// == Frying ==
+///
+/// Fry in a #pan{} for ~{3%minutes}.
+/// ```
+/// </details>
+///
+pub struct CooklangBuilder<'a> {
+    /// The recipe that is being converted
+    recipe: &'a Recipe,
+    /// The URL where the recipe stems from
+    url: Option<&'a str>,
+    /// The vocabulary used to find cookware in the steps
+    vocabulary: &'a EquipmentVocabulary,
+    /// Whether durations in the steps are marked as timers
+    timers: bool,
+    /// The output string being built
+    result: String,
+}
+
+/// An ingredient of the recipe while it is being marked in the steps
+struct Marker {
+    /// The normalized terms the ingredient is referred to by
+    terms: Vec<String>,
+    /// The name of the ingredient without its preparation notes, e.g. "coconut milk"
+    name: String,
+    /// The quantity in Cooklang notation, e.g. "2%cups"
+    quantity: String,
+    /// Whether the ingredient was already marked in a step
+    used: bool,
+}
+
+impl<'a> CooklangBuilder<'a> {
+    const METADATA_MARKER: &'static str = ">>";
+
+    /// Constructs a new CooklangBuilder for a [recipe](Recipe)
+    pub fn from(recipe: &'a Recipe) -> Self {
+        CooklangBuilder {
+            recipe,
+            url: None,
+            vocabulary: EquipmentVocabulary::builtin(),
+            timers: true,
+            result: String::new(),
+        }
+    }
+
+    /// Adds an optional URL to be written as the source of the recipe
+    pub fn with_url(mut self, url: &'a str) -> Self {
+        self.url = Some(url);
+        self
+    }
+
+    /// Uses the vocabulary to find cookware, default is the
+    /// [built-in vocabulary](EquipmentVocabulary::builtin)
+    pub fn with_vocabulary(mut self, vocabulary: &'a EquipmentVocabulary) -> Self {
+        self.vocabulary = vocabulary;
+        self
+    }
+
+    /// Sets whether durations in the steps are marked as timers, default is true
+    pub fn with_timers(mut self, timers: bool) -> Self {
+        self.timers = timers;
+        self
+    }
+
+    /// Performs the conversion
+    pub fn convert(mut self) -> String {
+        self.put_metadata();
+        self.put_sections();
+        self.result
+    }
+
+    /// Writes the metadata to the output string, if there is any
+    fn put_metadata(&mut self) {
+        let recipe = self.recipe;
+        let servings = recipe
+            .servings()
+            .map(format_decimal)
+            .or_else(|| recipe.recipe_yield.clone());
+        let times = [
+            ("prep time", &recipe.prep_time),
+            ("cook time", &recipe.cook_time),
+            ("time required", &recipe.total_time),
+        ]
+        .map(|(key, time)| (key, time.as_deref().map(format_time)));
+
+        let metadata = [
+            ("title", recipe.name.clone()),
+            ("description", recipe.description.clone()),
+            ("author", recipe.author.clone()),
+            ("source", self.url.map(str::to_string)),
+            ("servings", servings),
+        ]
+        .into_iter()
+        .chain(times);
+
+        let mut written = false;
+        for (key, value) in metadata {
+            if let Some(value) = value {
+                let value = single_line(&value);
+                writeln!(self.result, "{} {key}: {value}", Self::METADATA_MARKER).unwrap();
+                written = true;
+            }
+        }
+        if written {
+            writeln!(self.result).unwrap();
+        }
+    }
+
+    /// Writes the steps with their markers, named sections get a `== Name ==` heading
+    fn put_sections(&mut self) {
+        let ingredients: Vec<&str> = self.recipe.ingredients().map(String::as_str).collect();
+        let mut markers: Vec<Marker> = ingredients
+            .iter()
+            .zip(match_terms(&ingredients))
+            .map(|(ingredient, terms)| marker(ingredient, terms))
+            .collect();
+        let sections: Vec<Vec<String>> = self
+            .recipe
+            .how_to_sections
+            .iter()
+            .map(|section| {
+                section
+                    .steps
+                    .iter()
+                    .map(|step| self.annotate(step, &mut markers))
+                    .collect()
+            })
+            .collect();
+
+        let unused: Vec<String> = markers
+            .iter()
+            .filter(|marker| !marker.used && !marker.name.is_empty())
+            .map(|marker| format!("@{}{{{}}}", marker.name, marker.quantity))
+            .collect();
+        if !unused.is_empty() {
+            writeln!(self.result, "{}", unused.join(", ")).unwrap();
+            writeln!(self.result).unwrap();
+        }
+
+        for (i, (section, steps)) in self.recipe.how_to_sections.iter().zip(sections).enumerate() {
+            match &section.name {
+                Some(name) => writeln!(self.result, "== {} ==", single_line(name)).unwrap(),
+                None if i > 0 => writeln!(self.result, "==").unwrap(),
+                None => {}
+            }
+            if i > 0 || section.name.is_some() {
+                writeln!(self.result).unwrap();
+            }
+            for step in steps {
+                writeln!(self.result, "{step}").unwrap();
+                writeln!(self.result).unwrap();
+            }
+        }
+
+        let trimmed = self.result.trim_end().len();
+        self.result.truncate(trimmed);
+        if !self.result.is_empty() {
+            self.result.push('\n');
+        }
+    }
+
+    /// Returns the step with markers for ingredients, cookware and timers
+    ///
+    /// Where mentions overlap the longer one is kept, ingredients take precedence over
+    /// cookware and cookware over timers.
+    fn annotate(&self, step: &str, markers: &mut [Marker]) -> String {
+        let mut accepted: Vec<(Range<usize>, String)> = Vec::new();
+        let overlaps = |accepted: &[(Range<usize>, String)], range: &Range<usize>| {
+            accepted.iter().any(|(other, _)| overlap(range, other))
+        };
+
+        let mut candidates =
+            find_mentions(step, markers.iter().map(|marker| marker.terms.as_slice()));
+        candidates.sort_by_key(|(range, _)| (std::cmp::Reverse(range.len()), range.start));
+        let mut ingredients: Vec<(Range<usize>, usize)> = Vec::new();
+        for (range, i) in candidates {
+            if !ingredients.iter().any(|(_, other)| *other == i)
+                && !ingredients.iter().any(|(other, _)| overlap(&range, other))
+            {
+                ingredients.push((range, i));
+            }
+        }
+        ingredients.sort_by_key(|(range, _)| range.start);
+        for (range, i) in ingredients {
+            let marker = &mut markers[i];
+            let quantity = if marker.used { "" } else { &marker.quantity };
+            let name = match marker.name.is_empty() {
+                true => sanitize(&step[range.clone()]),
+                false => marker.name.clone(),
+            };
+            accepted.push((range, format!("@{name}{{{quantity}}}")));
+            marker.used = true;
+        }
+
+        let mut cookware: Vec<(Range<usize>, usize)> = self
+            .vocabulary
+            .equipment()
+            .iter()
+            .enumerate()
+            .flat_map(|(i, equipment)| {
+                equipment
+                    .names
+                    .iter()
+                    .flat_map(|name| find_normalized(step, name))
+                    .filter(|range| !starts_clause(step, range) && !precedes_object(step, range))
+                    .map(move |range| (range, i))
+            })
+            .collect();
+        cookware.sort_by_key(|(range, _)| (std::cmp::Reverse(range.len()), range.start));
+        let mut marked = Vec::new();
+        for (range, i) in cookware {
+            if !marked.contains(&i) && !overlaps(&accepted, &range) {
+                let name = sanitize(&step[range.clone()]);
+                accepted.push((range, format!("#{name}{{}}")));
+                marked.push(i);
+            }
+        }
+
+        if self.timers {
+            for mention in find_durations(step) {
+                if !overlaps(&accepted, &mention.span) {
+                    accepted.push((
+                        mention.span,
+                        format!("~{{{}}}", timer(mention.min, mention.max)),
+                    ));
+                }
+            }
+        }
+
+        accepted.sort_by_key(|(range, _)| range.start);
+        let mut result = String::with_capacity(step.len());
+        let mut position = 0;
+        for (range, marker) in accepted {
+            result.push_str(&escape(&step[position..range.start]));
+            result.push_str(&marker);
+            position = range.end;
+        }
+        result.push_str(&escape(&step[position..]));
+        let result = single_line(&result);
+        // A leading ">" or "=" would make the step a note or a section heading
+        if result.starts_with(['>', '=']) {
+            format!("\\{result}")
+        } else {
+            result
+        }
+    }
+}
+
+/// Creates the marker of an ingredient from its [match terms](match_terms)
+///
+/// A leading count noun of the name is written as the unit, e.g. "1 can coconut milk" as
+/// `@coconut milk{1%can}`.
+fn marker(ingredient: &str, terms: Vec<String>) -> Marker {
+    let parsed = parse_ingredient(ingredient);
+    let name = parsed
+        .name
+        .split([',', '('])
+        .next()
+        .unwrap_or_default()
+        .trim();
+    let (count_noun, name) = match name.split_once(' ') {
+        Some((first, rest))
+            if parsed.unit.is_none() && COUNT_NOUNS.contains(&normalize_text(first).as_str()) =>
+        {
+            (Some(first), rest)
+        }
+        _ => (None, name),
+    };
+    let quantity = match (parsed.amount, parsed.unit, count_noun) {
+        (Some(amount), Some(unit), _) => {
+            format!("{}%{}", format_decimal(amount), unit.symbol(amount))
+        }
+        (Some(amount), None, Some(noun)) => {
+            format!("{}%{}", format_decimal(amount), sanitize(noun))
+        }
+        (Some(amount), None, None) => format_decimal(amount),
+        _ => String::new(),
+    };
+    Marker {
+        terms,
+        name: sanitize(name).trim().to_string(),
+        quantity,
+        used: false,
+    }
+}
+
+/// Formats the duration of a timer in the largest unit both bounds can be written in,
+/// e.g. "90%minutes" or "10-15%minutes"
+fn timer(min: Duration, max: Duration) -> String {
+    let (min, max) = (min.as_secs(), max.as_secs());
+    let (factor, unit) = if min % 3600 == 0 && max % 3600 == 0 {
+        (3600, "hours")
+    } else if min % 60 == 0 && max % 60 == 0 {
+        (60, "minutes")
+    } else {
+        (1, "seconds")
+    };
+    if min == max {
+        format!("{}%{unit}", max / factor)
+    } else {
+        format!("{}-{}%{unit}", min / factor, max / factor)
+    }
+}
+
+/// Formats a time of the recipe for the metadata, e.g. "PT1H30M" as "1 hour 30 minutes"
+///
+/// Times that are not durations are kept as they are.
+fn format_time(time: &str) -> String {
+    parse_time(time).map_or_else(|| time.to_string(), format_duration)
+}
+
+fn overlap(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+/// Escapes the characters that start a marker in Cooklang
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '@' | '#' | '~') {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Removes the characters that would end the name of a marker
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '@' | '#' | '~' | '{' | '}' | '%'))
+        .collect()
+}
+
+/// Joins the lines of a text, as every line break would end a step or metadata entry, and
+/// breaks up `--` and `[-` with a space, as they would start a comment
+fn single_line(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c == '-' && result.ends_with(['-', '[']) {
+            result.push(' ');
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Parses a [Cooklang](https://cooklang.org) recipe, e.g. the contents of a `.cook` file
+///
+/// It deals with:
+///     - metadata as `>> key: value` lines or a `---` front matter (title, description,
+///       author, servings, prep time, cook time, time required and diet)
+///     - sections like `== Dough ==` or `= Dough`
+///     - ingredients like `@salt`, `@ground pepper{}` or `@flour{2%cups}(sifted)`
+///     - cookware like `#pot` or `#frying pan{}`, which become the tools of the recipe
+///     - timers like `~{10%minutes}` or `~rest{1%hour}`
+///     - line comments (`-- ...`), block comments (`[- ... -]`) and notes (`> ...`), which
+///       are skipped
+///     - escaped characters like `\@` or `\>`, which are kept as text
+///
+/// The markers are replaced with their names in the steps, e.g. "Add @flour{2%cups}."
+/// becomes "Add flour.". Ingredients are listed per section with their quantity, like
+/// "2 cups flour". A step consisting only of ingredients is not kept as a step. Times are
+/// converted to ISO 8601 durations if possible.
+///
+/// ## Example
+///
+/// ```
+/// use reget::parse_cooklang;
+///
+/// let recipe = parse_cooklang(
+///     ">> title: Pancakes\n\
+///      >> servings: 4\n\n\
+///      Whisk @eggs{2} and @milk{250%ml} in a #bowl.\n\n\
+///      Fry for ~{3%minutes}.",
+/// );
+/// assert_eq!(recipe.name.as_deref(), Some("Pancakes"));
+/// assert_eq!(recipe.ingredient_sections[0].ingredients, vec!["2 eggs", "250 ml milk"]);
+/// assert_eq!(recipe.how_to_sections[0].steps[1], "Fry for 3 minutes.");
+/// assert_eq!(recipe.tools, vec!["bowl"]);
+/// ```
+pub fn parse_cooklang(text: &str) -> Recipe {
+    let mut recipe = Recipe::default();
+    let mut sections = vec![ParsedSection::default()];
+    let mut step = String::new();
+
+    // The front matter is read first, as its "---" would start a comment
+    let mut text = text.trim_start();
+    if let Some(rest) = text.strip_prefix("---")
+        && let Some((front_matter, body)) = rest.split_once("\n---")
+    {
+        front_matter
+            .lines()
+            .for_each(|line| put_metadata(&mut recipe, line));
+        text = body.split_once('\n').map_or("", |(_, body)| body);
+    }
+
+    for line in strip_comments(text).lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('>') || line.starts_with('=') {
+            finish_step(&mut recipe, &mut sections, &mut step);
+        }
+        if let Some(metadata) = line.strip_prefix(">>") {
+            put_metadata(&mut recipe, metadata);
+        } else if line.starts_with('=') {
+            let name = line.trim_matches(|c: char| c == '=' || c.is_whitespace());
+            let name = (!name.is_empty()).then(|| name.to_string());
+            match sections.last_mut() {
+                Some(section) if section.is_empty() => section.name = name,
+                _ => sections.push(ParsedSection {
+                    name,
+                    ..Default::default()
+                }),
+            }
+        } else if !line.is_empty() && !line.starts_with('>') {
+            if !step.is_empty() {
+                step.push(' ');
+            }
+            step.push_str(line);
+        }
+    }
+    finish_step(&mut recipe, &mut sections, &mut step);
+
+    for section in sections {
+        if !section.ingredients.is_empty() {
+            recipe.ingredient_sections.push(IngredientSection {
+                name: section.name.clone(),
+                ingredients: section.ingredients,
+            });
+        }
+        if !section.steps.is_empty() {
+            recipe.how_to_sections.push(HowToSection {
+                name: section.name,
+                steps: section.steps,
+            });
+        }
+    }
+    recipe
+}
+
+/// A section of a Cooklang recipe while it is being parsed
+#[derive(Default)]
+struct ParsedSection {
+    name: Option<String>,
+    steps: Vec<HowToStep>,
+    ingredients: Vec<Ingredient>,
+}
+
+impl ParsedSection {
+    fn is_empty(&self) -> bool {
+        self.steps.is_empty() && self.ingredients.is_empty()
+    }
+}
+
+/// An ingredient, cookware or timer marker of a step
+struct Component<'a> {
+    name: &'a str,
+    quantity: Option<&'a str>,
+    unit: Option<&'a str>,
+    note: Option<&'a str>,
+    /// The length of the marker after its `@`, `#` or `~`
+    len: usize,
+}
+
+impl Component<'_> {
+    /// Returns the amount and unit followed by the name, e.g. "2 cups flour"
+    fn text(&self) -> String {
+        let mut parts: Vec<&str> = [self.quantity, self.unit].into_iter().flatten().collect();
+        if !self.name.is_empty() {
+            parts.push(self.name);
+        }
+        let text = parts.join(" ");
+        match self.note {
+            Some(note) => format!("{text} ({note})"),
+            None => text,
+        }
+    }
+}
+
+/// Adds the collected step to the last section and clears it
+fn finish_step(recipe: &mut Recipe, sections: &mut [ParsedSection], step: &mut String) {
+    if step.is_empty() {
+        return;
+    }
+    let known: Vec<String> = sections
+        .iter()
+        .flat_map(|section| &section.ingredients)
+        .map(|ingredient| parse_ingredient(ingredient).name)
+        .collect();
+    let section = sections.last_mut().expect("there is always a section");
+
+    let mut text = String::with_capacity(step.len());
+    let mut only_ingredients = true;
+    let mut rest = step.as_str();
+    while let Some(c) = rest.chars().next() {
+        let after = &rest[c.len_utf8()..];
+        let component = match c {
+            '@' | '#' | '~' => parse_component(after, c),
+            _ => None,
+        };
+        let Some(component) = component else {
+            match (c, after.chars().next()) {
+                ('\\', Some(escaped @ ('@' | '#' | '~' | '>' | '='))) => {
+                    text.push(escaped);
+                    rest = &after[1..];
+                }
+                _ => {
+                    text.push(c);
+                    rest = after;
+                }
+            }
+            only_ingredients &= c.is_whitespace() || c == ',';
+            continue;
+        };
+        rest = &after[component.len..];
+
+        match c {
+            '@' => {
+                text.push_str(component.name);
+                let ingredient = component.text();
+                if component.quantity.is_some() || !known.iter().any(|n| n == component.name) {
+                    section.ingredients.push(ingredient);
+                }
+            }
+            '#' => {
+                text.push_str(component.name);
+                only_ingredients = false;
+                if !recipe.tools.iter().any(|tool| tool == component.name) {
+                    recipe.tools.push(component.name.to_string());
+                }
+            }
+            _ => {
+                text.push_str(&component.text());
+                only_ingredients = false;
+            }
+        }
+    }
+
+    if !only_ingredients {
+        section.steps.push(text.trim().to_string());
+    }
+    step.clear();
+}
+
+/// Parses the marker after its `@`, `#` or `~`, either a single word or a name of
+/// multiple words followed by braces
+fn parse_component(text: &str, kind: char) -> Option<Component<'_>> {
+    let modifiers = match kind {
+        '@' => text.len() - text.trim_start_matches(['@', '&', '?', '+', '-']).len(),
+        _ => 0,
+    };
+    let text = &text[modifiers..];
+
+    let braced = text.find('{').and_then(|open| {
+        let name = &text[..open];
+        let close = open + text[open..].find('}')?;
+        let valid = !name.contains(['@', '#', '~', '}', '.', ',', ';', ':', '!', '?', '(', ')']);
+        valid.then_some((name, &text[open + 1..close], close + 1))
+    });
+    let (name, amount, mut len) = match braced {
+        Some((name, amount, len)) => (name.trim(), Some(amount), len),
+        None => {
+            let len = text
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .unwrap_or(text.len());
+            (&text[..len], None, len)
+        }
+    };
+    if name.is_empty() && kind != '~' {
+        return None;
+    }
+
+    let amount = amount.map(|amount| amount.trim().trim_start_matches('=').trim_end_matches('*'));
+    let (quantity, unit) = match amount.map(|amount| amount.split_once('%').unwrap_or((amount, "")))
+    {
+        Some((quantity, unit)) => (quantity.trim(), unit.trim()),
+        None => ("", ""),
+    };
+    let note = match &text[len..] {
+        rest if kind == '@' && rest.starts_with('(') => rest.find(')').map(|close| {
+            let note = &rest[1..close];
+            len += close + 1;
+            note.trim()
+        }),
+        _ => None,
+    };
+    if kind == '~' && quantity.is_empty() {
+        return None;
+    }
+
+    Some(Component {
+        name,
+        quantity: (!quantity.is_empty()).then_some(quantity),
+        unit: (!unit.is_empty()).then_some(unit),
+        note: note.filter(|note| !note.is_empty()),
+        len: modifiers + len,
+    })
+}
+
+/// Sets the field of the recipe for a `key: value` metadata entry
+fn put_metadata(recipe: &mut Recipe, entry: &str) {
+    let Some((key, value)) = entry.split_once(':') else {
+        return;
+    };
+    let key = key.trim().to_lowercase().replace(['_', '-'], " ");
+    let value = value.trim().trim_matches('"').to_string();
+    if value.is_empty() {
+        return;
+    }
+    let time = |value: String| Some(iso_duration(&value).unwrap_or(value));
+    match key.as_str() {
+        "title" | "name" => recipe.name = Some(value),
+        "description" => recipe.description = Some(value),
+        "author" => recipe.author = Some(value),
+        "servings" | "serves" | "yield" => recipe.recipe_yield = Some(value),
+        "prep time" => recipe.prep_time = time(value),
+        "cook time" => recipe.cook_time = time(value),
+        "time required" | "total time" | "time" | "duration" => recipe.total_time = time(value),
+        "diet" => {
+            recipe.suitable_for_diet = value.split(',').map(|d| d.trim().to_string()).collect()
+        }
+        _ => {}
+    }
+}
+
+/// Removes `-- ...` line comments and `[- ... -]` block comments
+fn strip_comments(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    loop {
+        let line = rest.find("--");
+        let block = rest.find("[-");
+        match (line, block) {
+            (Some(start), block) if block.is_none_or(|block| start < block) => {
+                result.push_str(&rest[..start]);
+                rest = &rest[start..];
+                rest = &rest[rest.find('\n').unwrap_or(rest.len())..];
+            }
+            (_, Some(start)) => {
+                result.push_str(&rest[..start]);
+                rest = rest[start..]
+                    .find("-]")
+                    .map_or("", |end| &rest[start + end + 2..]);
+            }
+            _ => {
+                result.push_str(rest);
+                return result;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod builder {
+        use super::*;
+
+        #[test]
+        fn write_metadata() {
+            let recipe = Recipe {
+                name: Some("Pancakes".into()),
+                author: Some("Jane".into()),
+                recipe_yield: Some("4 servings".into()),
+                prep_time: Some("PT1H30M".into()),
+                cook_time: Some("20 minutes".into()),
+                total_time: Some("overnight".into()),
+                ..Default::default()
+            };
+            assert_eq!(
+                recipe
+                    .to_cooklang()
+                    .with_url("https://example.org")
+                    .convert(),
+                ">> title: Pancakes\n\
+                 >> author: Jane\n\
+                 >> source: https://example.org\n\
+                 >> servings: 4\n\
+                 >> prep time: 1 hour 30 minutes\n\
+                 >> cook time: 20 minutes\n\
+                 >> time required: overnight\n"
+            );
+        }
+
+        #[test]
+        fn mark_ingredients() {
+            let recipe = Recipe::from_lines(
+                &["2 cups all-purpose flour", "2 large eggs", "1/2 tsp salt"],
+                &["Beat the eggs with the flour.", "Add more flour."],
+            );
+            assert_eq!(
+                recipe.to_cooklang().convert(),
+                "@salt{0.5%tsp}\n\n\
+                 Beat the @large eggs{2} with the @all-purpose flour{2%cups}.\n\n\
+                 Add more @all-purpose flour{}.\n"
+            );
+        }
+
+        #[test]
+        fn mark_ingredient_names() {
+            let recipe = Recipe::from_lines(
+                &["1 red bell pepper", "1 can coconut milk", "salt and pepper"],
+                &[
+                    "Slice the bell pepper.",
+                    "Add the milk.",
+                    "Season with salt and pepper.",
+                ],
+            );
+            let cooklang = recipe.to_cooklang().convert();
+            assert_eq!(
+                cooklang,
+                "Slice the @red bell pepper{1}.\n\n\
+                 Add the @coconut milk{1%can}.\n\n\
+                 Season with @salt and pepper{}.\n"
+            );
+            assert_eq!(
+                parse_cooklang(&cooklang).ingredients().collect::<Vec<_>>(),
+                vec!["1 red bell pepper", "1 can coconut milk", "salt and pepper"]
+            );
+        }
+
+        #[test]
+        fn prefer_longest_mention() {
+            let recipe = Recipe::from_lines(
+                &["1 tbsp butter", "2 tbsp peanut butter"],
+                &["Melt the peanut butter and the butter."],
+            );
+            assert_eq!(
+                recipe.to_cooklang().convert(),
+                "Melt the @peanut butter{2%tbsp} and the @butter{1%tbsp}.\n"
+            );
+        }
+
+        #[test]
+        fn mark_cookware_and_timers() {
+            let recipe = Recipe::from_lines(
+                &["1 onion"],
+                &["Fry the onion in a Dutch oven for 10-15 minutes, then rest 1 hour #2."],
+            );
+            assert_eq!(
+                recipe.to_cooklang().convert(),
+                "Fry the @onion{1} in a #Dutch oven{} for ~{10-15%minutes}, then rest ~{1%hours} \\#2.\n"
+            );
+            assert_eq!(
+                recipe.to_cooklang().with_timers(false).convert(),
+                "Fry the @onion{1} in a #Dutch oven{} for 10-15 minutes, then rest 1 hour \\#2.\n"
+            );
+        }
+
+        #[test]
+        fn write_sections() {
+            let recipe = Recipe {
+                how_to_sections: vec![
+                    HowToSection {
+                        name: None,
+                        steps: vec!["Mix.".into()],
+                    },
+                    HowToSection {
+                        name: Some("Frying".into()),
+                        steps: vec!["Fry.\nServe.".into(), "Eat.".into()],
+                    },
+                    HowToSection {
+                        name: None,
+                        steps: vec!["Clean.".into()],
+                    },
+                ],
+                ..Default::default()
+            };
+            assert_eq!(
+                recipe.to_cooklang().convert(),
+                "Mix.\n\n== Frying ==\n\nFry. Serve.\n\nEat.\n\n==\n\nClean.\n"
+            );
+        }
+
+        #[test]
+        fn timer_units() {
+            let minutes = |min: u64| Duration::from_secs(min * 60);
+            assert_eq!(timer(minutes(90), minutes(90)), "90%minutes");
+            assert_eq!(timer(minutes(60), minutes(120)), "1-2%hours");
+            assert_eq!(
+                timer(Duration::from_secs(30), Duration::from_secs(30)),
+                "30%seconds"
+            );
+        }
+    }
+
+    mod parser {
+        use super::*;
+
+        #[test]
+        fn parse_metadata() {
+            let recipe = parse_cooklang(
+                "---\n\
+                 title: Pancakes\n\
+                 prep_time: 1 hour 30 minutes\n\
+                 ---\n\
+                 >> author: Jane\n\
+                 >> servings: 4\n\
+                 >> cook time: soon\n\
+                 >> diet: VeganDiet, LowSaltDiet\n\
+                 >> source: https://example.org\n",
+            );
+            assert_eq!(
+                recipe,
+                Recipe {
+                    name: Some("Pancakes".into()),
+                    author: Some("Jane".into()),
+                    recipe_yield: Some("4".into()),
+                    prep_time: Some("PT1H30M".into()),
+                    cook_time: Some("soon".into()),
+                    suitable_for_diet: vec!["VeganDiet".into(), "LowSaltDiet".into()],
+                    ..Default::default()
+                }
+            );
+        }
+
+        #[test]
+        fn parse_sections() {
+            let recipe = parse_cooklang(
+                "== Dough ==\n\
+                 Mix @flour{500%g} -- or spelt\n\
+                 with @water{300%ml}.\n\n\
+                 > Tip: use cold water.\n\n\
+                 [- resting\n is optional -]Rest.\n\n\
+                 = Topping\n\n\
+                 @cheese{100%g}, @basil\n\n\
+                 Spread the @cheese.\n\n\
+                 ==\n\n\
+                 Bake.",
+            );
+            assert_eq!(
+                recipe.ingredient_sections,
+                vec![
+                    IngredientSection {
+                        name: Some("Dough".into()),
+                        ingredients: vec!["500 g flour".into(), "300 ml water".into()],
+                    },
+                    IngredientSection {
+                        name: Some("Topping".into()),
+                        ingredients: vec!["100 g cheese".into(), "basil".into()],
+                    },
+                ]
+            );
+            assert_eq!(
+                recipe.how_to_sections,
+                vec![
+                    HowToSection {
+                        name: Some("Dough".into()),
+                        steps: vec!["Mix flour with water.".into(), "Rest.".into()],
+                    },
+                    HowToSection {
+                        name: Some("Topping".into()),
+                        steps: vec!["Spread the cheese.".into()],
+                    },
+                    HowToSection {
+                        name: None,
+                        steps: vec!["Bake.".into()],
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn parse_markers() {
+            let recipe = parse_cooklang(
+                "Add @?ground pepper{1/2%tsp}(freshly ground), @salt and @eggs{2} to a #large bowl{}. \
+                 Beat with a #whisk for ~{2-3%minutes}, then rest ~dough{1%hour}. \
+                 Serve with #1 \\@home style ~ @ {} #.",
+            );
+            assert_eq!(
+                recipe.ingredient_sections[0].ingredients,
+                vec!["1/2 tsp ground pepper (freshly ground)", "salt", "2 eggs"]
+            );
+            assert_eq!(recipe.tools, vec!["large bowl", "whisk", "1"]);
+            assert_eq!(
+                recipe.how_to_sections[0].steps,
+                vec![
+                    "Add ground pepper, salt and eggs to a large bowl. Beat with a whisk for \
+                     2-3 minutes, then rest 1 hour dough. Serve with 1 @home style ~ @ {} #."
+                ]
+            );
+        }
+
+        #[test]
+        fn round_trip_builder() {
+            let recipe = Recipe {
+                name: Some("Omelette".into()),
+                recipe_yield: Some("2".into()),
+                cook_time: Some("PT10M".into()),
+                ingredient_sections: vec![IngredientSection {
+                    name: None,
+                    ingredients: vec!["3 eggs".into(), "1 tbsp butter".into(), "salt".into()],
+                }],
+                how_to_sections: vec![
+                    HowToSection {
+                        name: None,
+                        steps: vec!["Beat the eggs.".into()],
+                    },
+                    HowToSection {
+                        name: Some("Frying".into()),
+                        steps: vec!["Melt the butter in a pan, then fry for 5 minutes.".into()],
+                    },
+                ],
+                tools: vec!["pan".into()],
+                ..Default::default()
+            };
+            let parsed = parse_cooklang(&recipe.to_cooklang().convert());
+            assert_eq!(parsed.how_to_sections, recipe.how_to_sections);
+            assert_eq!(
+                parsed.ingredients().collect::<Vec<_>>(),
+                vec!["salt", "3 eggs", "1 tbsp butter"]
+            );
+            assert_eq!(
+                Recipe {
+                    ingredient_sections: recipe.ingredient_sections.clone(),
+                    ..parsed
+                },
+                recipe
+            );
+        }
+
+        #[test]
+        fn round_trip_comments_notes_and_sections() {
+            let recipe = Recipe {
+                name: Some("Pasta -- the best".into()),
+                how_to_sections: vec![HowToSection {
+                    name: Some("Sauce [-thick-]".into()),
+                    steps: vec![
+                        "Season with salt -- to taste.".into(),
+                        "> 5 cm pieces are fine.".into(),
+                        "= Serve.".into(),
+                        "Stir [-gently-] for a while.".into(),
+                    ],
+                }],
+                ..Default::default()
+            };
+            let parsed = parse_cooklang(&recipe.to_cooklang().with_timers(false).convert());
+            assert_eq!(parsed.name.as_deref(), Some("Pasta - - the best"));
+            assert_eq!(
+                parsed.how_to_sections,
+                vec![HowToSection {
+                    name: Some("Sauce [ -thick-]".into()),
+                    steps: vec![
+                        "Season with salt - - to taste.".into(),
+                        "> 5 cm pieces are fine.".into(),
+                        "= Serve.".into(),
+                        "Stir [ -gently-] for a while.".into(),
+                    ],
+                }]
+            );
+        }
+    }
+}
@@ -0,0 +1,435 @@
+use crate::allergens::Allergen;
+use crate::food::FoodCategory;
+use crate::ingredient::{ParsedIngredient, parse_ingredient};
+use crate::text::{contains_phrase, normalize_text};
+use crate::units::{Dimension, Unit};
+use crate::{Ingredient, Recipe};
+
+/// A diet a recipe can be [classified](Recipe::diets) for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Diet {
+    Vegetarian,
+    Vegan,
+    Pescatarian,
+    GlutenFree,
+    DairyFree,
+    LowCarb,
+}
+
+impl Diet {
+    /// All diets that are classified
+    pub const ALL: [Diet; 6] = [
+        Diet::Vegetarian,
+        Diet::Vegan,
+        Diet::Pescatarian,
+        Diet::GlutenFree,
+        Diet::DairyFree,
+        Diet::LowCarb,
+    ];
+
+    /// Returns the name of the diet for display, e.g. "Gluten-free"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Diet::Vegetarian => "Vegetarian",
+            Diet::Vegan => "Vegan",
+            Diet::Pescatarian => "Pescatarian",
+            Diet::GlutenFree => "Gluten-free",
+            Diet::DairyFree => "Dairy-free",
+            Diet::LowCarb => "Low-carb",
+        }
+    }
+
+    /// Checks whether a declared schema.org diet (e.g. "VeganDiet") implies this diet
+    fn declared_by(&self, schema_diet: &str) -> bool {
+        let implied: &[Diet] = match schema_diet {
+            "VeganDiet" => &[
+                Diet::Vegan,
+                Diet::Vegetarian,
+                Diet::Pescatarian,
+                Diet::DairyFree,
+            ],
+            "VegetarianDiet" => &[Diet::Vegetarian, Diet::Pescatarian],
+            "GlutenFreeDiet" => &[Diet::GlutenFree],
+            _ => &[],
+        };
+        implied.contains(self)
+    }
+
+    /// Checks whether the ingredient rules out this diet
+    fn is_ruled_out_by(&self, ingredient: &str, parsed: &ParsedIngredient) -> bool {
+        let text = normalize_text(ingredient);
+        let meat_free_product = contains_any(&text, MEAT_FREE_MARKERS);
+        let poultry_eggs = contains_any(&text, POULTRY_EGGS);
+        let meat = !meat_free_product
+            && !poultry_eggs
+            && (parsed.category == FoodCategory::Meat || contains_any(&text, MEAT_KEYWORDS));
+        let fish = !meat_free_product
+            && (parsed.category == FoodCategory::Seafood || contains_any(&text, FISH_KEYWORDS));
+
+        match self {
+            Diet::Vegetarian => meat || fish,
+            Diet::Pescatarian => meat,
+            Diet::Vegan => {
+                meat || fish
+                    || (!contains_any(&text, VEGAN_MARKERS)
+                        && (Allergen::Eggs.find_in(ingredient).is_some()
+                            || Allergen::Milk.find_in(ingredient).is_some()
+                            || contains_any(&text, ANIMAL_PRODUCT_KEYWORDS)))
+            }
+            Diet::GlutenFree => Allergen::Gluten.find_in(ingredient).is_some(),
+            Diet::DairyFree => Allergen::Milk.find_in(ingredient).is_some(),
+            Diet::LowCarb => {
+                parsed
+                    .food
+                    .as_deref()
+                    .is_some_and(|food| HIGH_CARB_FOODS.contains(&food))
+                    && !is_small_amount(parsed)
+            }
+        }
+    }
+}
+
+/// How certain a [diet assessment](DietAssessment) is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Confidence {
+    /// Less than 70% of the ingredients were recognized.
+    Low,
+    /// Most ingredients were recognized, or the declared diets contradict the ingredients.
+    Medium,
+    /// All ingredients were recognized, an ingredient rules out the diet or the diet is
+    /// declared and not contradicted.
+    High,
+}
+
+/// Whether a recipe is suitable for a [diet](Diet)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DietAssessment<'a> {
+    /// The diet that was assessed.
+    pub diet: Diet,
+    /// Whether the recipe is suitable for the diet.
+    ///
+    /// Diets declared in [suitable_for_diet](Recipe::suitable_for_diet) are suitable unless
+    /// an ingredient contradicts them.
+    pub suitable: bool,
+    /// How certain the assessment is.
+    pub confidence: Confidence,
+    /// Whether the diet is declared in [suitable_for_diet](Recipe::suitable_for_diet).
+    pub declared: bool,
+    /// The ingredients that rule out the diet, also if the diet is declared.
+    pub offending: Vec<&'a Ingredient>,
+}
+
+/// Classifies the recipe for every [diet](Diet)
+///
+/// A diet is ruled out with high confidence if any ingredient contradicts it, or with
+/// medium confidence if the diet is declared nonetheless. Otherwise the confidence
+/// depends on how many ingredients were recognized by the
+/// [food dictionary](crate::FoodDictionary). Low-carb is never inferred with high
+/// confidence, as the amounts are not taken into account beyond spoon measures.
+pub(crate) fn assess_diets(recipe: &Recipe) -> Vec<DietAssessment<'_>> {
+    let ingredients: Vec<(&Ingredient, ParsedIngredient)> = recipe
+        .ingredients()
+        .map(|ingredient| (ingredient, parse_ingredient(ingredient)))
+        .collect();
+    let recognized = ingredients
+        .iter()
+        .filter(|(_, parsed)| parsed.food.is_some())
+        .count();
+    let inferred_confidence = match ingredients.len() {
+        0 => Confidence::Low,
+        total if recognized == total => Confidence::High,
+        total if recognized * 10 >= total * 7 => Confidence::Medium,
+        _ => Confidence::Low,
+    };
+
+    Diet::ALL
+        .iter()
+        .map(|&diet| {
+            let declared = recipe
+                .suitable_for_diet
+                .iter()
+                .any(|schema_diet| diet.declared_by(schema_diet));
+            let offending: Vec<&Ingredient> = ingredients
+                .iter()
+                .filter(|(ingredient, parsed)| diet.is_ruled_out_by(ingredient, parsed))
+                .map(|(ingredient, _)| *ingredient)
+                .collect();
+
+            let confidence = match (declared, offending.is_empty()) {
+                (true, true) => Confidence::High,
+                (true, false) => Confidence::Medium,
+                (false, false) => Confidence::High,
+                (false, true) if diet == Diet::LowCarb => {
+                    inferred_confidence.min(Confidence::Medium)
+                }
+                (false, true) => inferred_confidence,
+            };
+
+            DietAssessment {
+                diet,
+                suitable: offending.is_empty(),
+                confidence,
+                declared,
+                offending,
+            }
+        })
+        .collect()
+}
+
+/// Checks whether the text contains any of the keywords as whole words
+fn contains_any(text: &str, keywords: &[&str]) -> bool {
+    keywords
+        .iter()
+        .any(|keyword| contains_phrase(text, &normalize_text(keyword)))
+}
+
+/// Checks whether the ingredient is only used in spoon measures or up to 15 g
+fn is_small_amount(parsed: &ParsedIngredient) -> bool {
+    match parsed.quantity() {
+        Some(quantity) if matches!(quantity.unit, Unit::Teaspoon | Unit::Tablespoon) => true,
+        Some(quantity) if quantity.unit.dimension() == Dimension::Mass => {
+            quantity.base_value() <= 15.0
+        }
+        _ => false,
+    }
+}
+
+/// Markers of meat substitutes, e.g. "vegan sausage"
+const MEAT_FREE_MARKERS: &[&str] = &[
+    "vegan",
+    "vegetarian",
+    "plant-based",
+    "meatless",
+    "veggie",
+    "vegetarisch",
+];
+
+/// Markers of substitutes for eggs, dairy and other animal products, e.g. "vegan cheese"
+const VEGAN_MARKERS: &[&str] = &["vegan", "plant-based", "vegane", "veganer"];
+
+/// Eggs of poultry, which are not meat despite the name of the bird, e.g. "duck eggs"
+const POULTRY_EGGS: &[&str] = &[
+    "duck egg",
+    "goose egg",
+    "quail egg",
+    "chicken egg",
+    "hen egg",
+];
+
+/// Meat products not covered by the meat category of the food dictionary
+const MEAT_KEYWORDS: &[&str] = &[
+    "chicken stock",
+    "chicken broth",
+    "beef stock",
+    "beef broth",
+    "bone broth",
+    "gelatin",
+    "gelatine",
+    "lard",
+    "suet",
+    "chorizo",
+    "prosciutto",
+    "salami",
+    "pepperoni",
+    "veal",
+    "duck",
+    "venison",
+    "hühnerbrühe",
+    "rinderbrühe",
+    "schmalz",
+    "kalbfleisch",
+    "ente",
+];
+
+/// Fish products not covered by the seafood category of the food dictionary
+const FISH_KEYWORDS: &[&str] = &[
+    "fish sauce",
+    "worcestershire",
+    "dashi",
+    "bonito",
+    "fischsauce",
+];
+
+/// Animal products that are neither meat, fish, eggs nor dairy
+const ANIMAL_PRODUCT_KEYWORDS: &[&str] = &["honey", "honig", "beeswax"];
+
+/// Canonical foods that are mostly carbohydrates
+const HIGH_CARB_FOODS: &[&str] = &[
+    "flour",
+    "sugar",
+    "brown_sugar",
+    "powdered_sugar",
+    "honey",
+    "rice",
+    "pasta",
+    "potato",
+    "sweet_potato",
+    "bread",
+    "baguette",
+    "tortilla",
+    "bun",
+    "oats",
+    "semolina",
+    "breadcrumbs",
+    "beans",
+    "lentils",
+    "chickpeas",
+    "banana",
+    "juice",
+    "beer",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assessment<'a>(recipe: &'a Recipe, diet: Diet) -> DietAssessment<'a> {
+        recipe.diets().into_iter().find(|a| a.diet == diet).unwrap()
+    }
+
+    #[test]
+    fn vegan_recipe() {
+        let recipe = Recipe::from_lines(
+            &[
+                "200 g rice",
+                "1 onion",
+                "2 tbsp olive oil",
+                "1 can coconut milk",
+            ],
+            &[],
+        );
+        for diet in [
+            Diet::Vegan,
+            Diet::Vegetarian,
+            Diet::DairyFree,
+            Diet::GlutenFree,
+        ] {
+            let result = assessment(&recipe, diet);
+            assert!(result.suitable, "{diet:?}");
+            assert_eq!(result.confidence, Confidence::High);
+        }
+    }
+
+    #[test]
+    fn rule_out_with_offending_ingredients() {
+        let recipe = Recipe::from_lines(&["500 g chicken breast", "100 ml cream", "1 onion"], &[]);
+        let vegetarian = assessment(&recipe, Diet::Vegetarian);
+        assert!(!vegetarian.suitable);
+        assert_eq!(vegetarian.confidence, Confidence::High);
+        assert_eq!(vegetarian.offending, vec!["500 g chicken breast"]);
+
+        let vegan = assessment(&recipe, Diet::Vegan);
+        assert_eq!(
+            vegan.offending,
+            vec!["500 g chicken breast", "100 ml cream"]
+        );
+    }
+
+    #[test]
+    fn pescatarian() {
+        let recipe = Recipe::from_lines(&["200 g salmon", "1 lemon"], &[]);
+        assert!(assessment(&recipe, Diet::Pescatarian).suitable);
+        assert!(!assessment(&recipe, Diet::Vegetarian).suitable);
+    }
+
+    #[test]
+    fn hidden_animal_products() {
+        let recipe = Recipe::from_lines(
+            &["500 ml chicken stock", "1 tbsp fish sauce", "2 tbsp honey"],
+            &[],
+        );
+        assert_eq!(
+            assessment(&recipe, Diet::Pescatarian).offending,
+            vec!["500 ml chicken stock"]
+        );
+        assert_eq!(assessment(&recipe, Diet::Vegan).offending.len(), 3);
+    }
+
+    #[test]
+    fn meat_substitutes() {
+        let recipe = Recipe::from_lines(&["4 vegan sausages"], &[]);
+        assert!(assessment(&recipe, Diet::Vegan).suitable);
+    }
+
+    #[test]
+    fn low_carb_ignores_small_amounts() {
+        let recipe = Recipe::from_lines(&["500 g beef", "1 tbsp flour", "10 g sugar"], &[]);
+        let result = assessment(&recipe, Diet::LowCarb);
+        assert!(result.suitable);
+        assert_eq!(result.confidence, Confidence::Medium);
+
+        let recipe = Recipe::from_lines(&["500 g potatoes"], &[]);
+        assert!(!assessment(&recipe, Diet::LowCarb).suitable);
+    }
+
+    #[test]
+    fn unknown_ingredients_lower_confidence() {
+        let recipe = Recipe::from_lines(&["1 onion", "2 quinces", "1 unobtainium"], &[]);
+        assert_eq!(assessment(&recipe, Diet::Vegan).confidence, Confidence::Low);
+    }
+
+    #[test]
+    fn declared_diets() {
+        let mut recipe = Recipe::from_lines(&["200 g tofu", "2 quinces", "1 unobtainium"], &[]);
+        recipe.suitable_for_diet = vec!["VeganDiet".into()];
+        let vegan = assessment(&recipe, Diet::Vegan);
+        assert!(vegan.declared);
+        assert_eq!(vegan.confidence, Confidence::High);
+        assert!(assessment(&recipe, Diet::Vegetarian).declared);
+        assert!(!assessment(&recipe, Diet::GlutenFree).declared);
+    }
+
+    #[test]
+    fn declared_diet_contradicted() {
+        let mut recipe = Recipe::from_lines(&["2 eggs"], &[]);
+        recipe.suitable_for_diet = vec!["VeganDiet".into()];
+        let vegan = assessment(&recipe, Diet::Vegan);
+        assert!(vegan.declared);
+        assert!(!vegan.suitable);
+        assert_eq!(vegan.confidence, Confidence::Medium);
+        assert_eq!(vegan.offending, vec!["2 eggs"]);
+    }
+
+    #[test]
+    fn vegetarian_dairy_is_not_vegan() {
+        let recipe = Recipe::from_lines(&["100 g vegetarian parmesan", "1 cup veggie cheese"], &[]);
+        assert!(assessment(&recipe, Diet::Vegetarian).suitable);
+        let vegan = assessment(&recipe, Diet::Vegan);
+        assert!(!vegan.suitable);
+        assert_eq!(vegan.offending.len(), 2);
+    }
+
+    #[test]
+    fn free_from_products() {
+        let recipe = Recipe::from_lines(
+            &[
+                "200 g gluten-free flour",
+                "1 loaf gluten-free bread",
+                "100 g dairy-free butter",
+                "2 tbsp egg-free mayonnaise",
+            ],
+            &[],
+        );
+        for diet in [Diet::GlutenFree, Diet::DairyFree, Diet::Vegan] {
+            let assessment = assessment(&recipe, diet);
+            assert!(assessment.suitable, "{diet:?}");
+            assert!(assessment.offending.is_empty(), "{diet:?}");
+        }
+    }
+
+    #[test]
+    fn free_from_label_only_covers_its_allergen() {
+        let recipe = Recipe::from_lines(&["1 loaf gluten-free bread with butter"], &[]);
+        assert!(assessment(&recipe, Diet::GlutenFree).suitable);
+        assert!(!assessment(&recipe, Diet::DairyFree).suitable);
+    }
+
+    #[test]
+    fn poultry_eggs_are_not_meat() {
+        let recipe = Recipe::from_lines(&["2 duck eggs", "6 quail eggs", "2 chicken eggs"], &[]);
+        assert!(assessment(&recipe, Diet::Vegetarian).suitable);
+        assert_eq!(assessment(&recipe, Diet::Vegan).offending.len(), 3);
+    }
+}
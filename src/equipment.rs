@@ -0,0 +1,344 @@
+use crate::Recipe;
+use crate::text::{find_normalized, normalize_text, precedes_object, starts_clause};
+
+use std::ops::Range;
+use std::sync::LazyLock;
+
+/// A piece of cooking equipment of an [EquipmentVocabulary]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Equipment {
+    /// The name of the equipment for display, e.g. "Dutch oven".
+    pub name: String,
+    /// All names the equipment is known by, normalized to lower case and singular
+    /// (e.g. "dutch oven", "cocotte", "bräter").
+    pub names: Vec<String>,
+}
+
+/// A vocabulary to recognize cooking equipment mentioned in the steps of a recipe
+///
+/// Names are matched as whole words, ignoring case and plural forms. Longer names take
+/// precedence over the names they contain, so "sheet pan" is not also reported as "pan".
+/// Names at the start of a sentence or followed by an article are taken as verbs and
+/// skipped, e.g. "Whisk the eggs" or "Grill for 5 minutes".
+///
+/// ## Example
+///
+/// ```
+/// use reget::EquipmentVocabulary;
+///
+/// let vocabulary = EquipmentVocabulary::builtin();
+/// let found = vocabulary.find_in("Heat the oil in a Dutch oven, then blend with a food processor.");
+/// let names: Vec<&str> = found.iter().map(|e| e.name.as_str()).collect();
+/// assert_eq!(names, vec!["Dutch oven", "Food processor"]);
+///
+/// let vocabulary = EquipmentVocabulary::builtin()
+///     .clone()
+///     .with_equipment("Tagine", &["tagine", "tajine"]);
+/// assert_eq!(vocabulary.find_in("Cover the tajine")[0].name, "Tagine");
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct EquipmentVocabulary {
+    equipment: Vec<Equipment>,
+}
+
+static BUILTIN: LazyLock<EquipmentVocabulary> = LazyLock::new(|| {
+    BUILTIN_EQUIPMENT
+        .iter()
+        .fold(EquipmentVocabulary::new(), |vocabulary, (name, names)| {
+            vocabulary.with_equipment(name, names)
+        })
+});
+
+impl EquipmentVocabulary {
+    /// Constructs an empty vocabulary
+    pub fn new() -> Self {
+        EquipmentVocabulary::default()
+    }
+
+    /// Returns the built-in vocabulary with common equipment in English and German
+    pub fn builtin() -> &'static EquipmentVocabulary {
+        &BUILTIN
+    }
+
+    /// Adds equipment with the given names, or adds the names to the equipment if it
+    /// already exists
+    pub fn with_equipment(mut self, name: &str, names: &[&str]) -> Self {
+        let names = names.iter().map(|name| normalize_text(name));
+        match self.equipment.iter_mut().find(|e| e.name == name) {
+            Some(equipment) => equipment.names.extend(names),
+            None => self.equipment.push(Equipment {
+                name: name.to_string(),
+                names: names.collect(),
+            }),
+        }
+        self
+    }
+
+    /// Returns all equipment of the vocabulary
+    pub fn equipment(&self) -> &[Equipment] {
+        &self.equipment
+    }
+
+    /// Returns the equipment mentioned in the text, in order of first appearance and
+    /// without duplicates
+    pub fn find_in(&self, text: &str) -> Vec<&Equipment> {
+        let mut matches: Vec<(Range<usize>, &Equipment)> = self
+            .equipment
+            .iter()
+            .flat_map(|equipment| {
+                equipment
+                    .names
+                    .iter()
+                    .flat_map(|name| find_normalized(text, name))
+                    .filter(|range| !starts_clause(text, range) && !precedes_object(text, range))
+                    .map(move |range| (range, equipment))
+            })
+            .collect();
+
+        // Keep the longest names where matches overlap, e.g. "dutch oven" over "oven"
+        matches.sort_by_key(|(range, _)| std::cmp::Reverse(range.len()));
+        let mut accepted: Vec<(Range<usize>, &Equipment)> = Vec::new();
+        for (range, equipment) in matches {
+            if !accepted
+                .iter()
+                .any(|(other, _)| range.start < other.end && other.start < range.end)
+            {
+                accepted.push((range, equipment));
+            }
+        }
+
+        accepted.sort_by_key(|(range, _)| range.start);
+        let mut result: Vec<&Equipment> = Vec::new();
+        for (_, equipment) in accepted {
+            if !result.contains(&equipment) {
+                result.push(equipment);
+            }
+        }
+        result
+    }
+
+    /// Returns the equipment mentioned in all steps of the recipe, in order of first
+    /// appearance and without duplicates
+    pub fn find_in_recipe(&self, recipe: &Recipe) -> Vec<&Equipment> {
+        let mut result: Vec<&Equipment> = Vec::new();
+        for step in recipe.steps() {
+            for equipment in self.find_in(step.text) {
+                if !result.contains(&equipment) {
+                    result.push(equipment);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// The equipment of the [built-in vocabulary](EquipmentVocabulary::builtin)
+const BUILTIN_EQUIPMENT: &[(&str, &[&str])] = &[
+    // Appliances
+    ("Oven", &["oven", "backofen", "ofen"]),
+    ("Microwave", &["microwave", "mikrowelle"]),
+    ("Grill", &["grill", "barbecue"]),
+    (
+        "Slow cooker",
+        &["slow cooker", "crock pot", "crockpot", "schongarer"],
+    ),
+    (
+        "Pressure cooker",
+        &["pressure cooker", "instant pot", "schnellkochtopf"],
+    ),
+    ("Air fryer", &["air fryer", "heißluftfritteuse"]),
+    ("Food processor", &["food processor", "zerkleinerer"]),
+    ("Blender", &["blender", "standmixer"]),
+    (
+        "Immersion blender",
+        &[
+            "immersion blender",
+            "stick blender",
+            "hand blender",
+            "pürierstab",
+            "stabmixer",
+        ],
+    ),
+    ("Stand mixer", &["stand mixer", "küchenmaschine"]),
+    (
+        "Hand mixer",
+        &["hand mixer", "electric mixer", "handmixer", "handrührgerät"],
+    ),
+    // Pots and pans
+    (
+        "Dutch oven",
+        &["dutch oven", "cocotte", "bräter", "schmortopf"],
+    ),
+    ("Pot", &["pot", "stockpot", "topf", "kochtopf"]),
+    ("Saucepan", &["saucepan", "sauce pan", "stieltopf"]),
+    (
+        "Skillet",
+        &["skillet", "frying pan", "fry pan", "bratpfanne"],
+    ),
+    ("Pan", &["pan", "pfanne"]),
+    ("Wok", &["wok"]),
+    // Bakeware
+    (
+        "Sheet pan",
+        &[
+            "sheet pan",
+            "baking sheet",
+            "baking tray",
+            "cookie sheet",
+            "backblech",
+            "blech",
+        ],
+    ),
+    (
+        "Baking dish",
+        &["baking dish", "casserole dish", "auflaufform"],
+    ),
+    (
+        "Cake pan",
+        &[
+            "cake pan",
+            "cake tin",
+            "springform pan",
+            "springform",
+            "backform",
+        ],
+    ),
+    ("Loaf pan", &["loaf pan", "loaf tin", "kastenform"]),
+    ("Muffin tin", &["muffin tin", "muffin pan", "muffinform"]),
+    (
+        "Pie dish",
+        &["pie dish", "pie pan", "tart pan", "tarteform"],
+    ),
+    ("Ramekin", &["ramekin", "förmchen"]),
+    (
+        "Pizza stone",
+        &["pizza stone", "baking stone", "pizzastein"],
+    ),
+    ("Wire rack", &["wire rack", "cooling rack", "kuchengitter"]),
+    // Tools
+    (
+        "Mixing bowl",
+        &["mixing bowl", "bowl", "schüssel", "rührschüssel"],
+    ),
+    ("Whisk", &["whisk", "schneebesen"]),
+    ("Spatula", &["spatula", "teigschaber", "pfannenwender"]),
+    ("Rolling pin", &["rolling pin", "nudelholz", "teigrolle"]),
+    (
+        "Cutting board",
+        &["cutting board", "chopping board", "schneidebrett"],
+    ),
+    ("Colander", &["colander", "strainer", "sieve", "sieb"]),
+    ("Grater", &["grater", "microplane", "reibe"]),
+    ("Thermometer", &["thermometer"]),
+    (
+        "Mortar and pestle",
+        &["mortar and pestle", "mortar", "mörser"],
+    ),
+    ("Piping bag", &["piping bag", "spritzbeutel"]),
+    ("Kitchen scale", &["kitchen scale", "küchenwaage"]),
+    // Supplies
+    (
+        "Parchment paper",
+        &["parchment paper", "parchment", "baking paper", "backpapier"],
+    ),
+    (
+        "Aluminum foil",
+        &[
+            "aluminum foil",
+            "aluminium foil",
+            "tin foil",
+            "foil",
+            "alufolie",
+        ],
+    ),
+    (
+        "Plastic wrap",
+        &["plastic wrap", "cling film", "frischhaltefolie"],
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HowToSection;
+
+    fn names(equipment: Vec<&Equipment>) -> Vec<&str> {
+        equipment.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn find_plural_and_case() {
+        let vocabulary = EquipmentVocabulary::builtin();
+        assert_eq!(
+            names(vocabulary.find_in("Line two Baking Sheets with parchment paper.")),
+            vec!["Sheet pan", "Parchment paper"]
+        );
+    }
+
+    #[test]
+    fn prefer_longest_name() {
+        let vocabulary = EquipmentVocabulary::builtin();
+        assert_eq!(
+            names(vocabulary.find_in("Preheat the oven. Brown the meat in a Dutch oven.")),
+            vec!["Oven", "Dutch oven"]
+        );
+        assert_eq!(
+            names(vocabulary.find_in("Spread on a sheet pan")),
+            vec!["Sheet pan"]
+        );
+    }
+
+    #[test]
+    fn find_german() {
+        let vocabulary = EquipmentVocabulary::builtin();
+        assert_eq!(
+            names(vocabulary.find_in("Den Backofen vorheizen und die Auflaufform einfetten.")),
+            vec!["Oven", "Baking dish"]
+        );
+    }
+
+    #[test]
+    fn ignore_partial_words() {
+        let vocabulary = EquipmentVocabulary::builtin();
+        assert!(
+            vocabulary
+                .find_in("Add the pancetta and potatoes")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn find_in_recipe_deduplicates() {
+        let recipe = Recipe {
+            how_to_sections: vec![HowToSection {
+                name: None,
+                steps: vec![
+                    "Whisk the eggs in a bowl.".into(),
+                    "Pour into a skillet.".into(),
+                    "Return to the bowl.".into(),
+                ],
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            names(EquipmentVocabulary::builtin().find_in_recipe(&recipe)),
+            vec!["Mixing bowl", "Skillet"]
+        );
+    }
+
+    #[test]
+    fn ignore_verbs() {
+        let vocabulary = EquipmentVocabulary::builtin();
+        for step in [
+            "Whisk the eggs.",
+            "Grill for 5 minutes.",
+            "Sieve the flour, then grill the chicken.",
+        ] {
+            assert!(vocabulary.find_in(step).is_empty(), "{step}");
+        }
+        assert_eq!(
+            names(vocabulary.find_in("Beat with a whisk, then heat the grill.")),
+            vec!["Whisk", "Grill"]
+        );
+    }
+}
@@ -0,0 +1,922 @@
+use crate::ingredient::{ParsedIngredient, parse_ingredient};
+use crate::text::{contains_phrase, find_phrase, is_verb, singularize};
+
+use std::sync::LazyLock;
+
+/// The category of a food, used to group a [shopping list](crate::ShoppingList) by store aisle.
+///
+/// Categories are listed in the order they are usually found in a store.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FoodCategory {
+    Produce,
+    Bakery,
+    Meat,
+    Seafood,
+    Dairy,
+    Pantry,
+    Spices,
+    Frozen,
+    Beverages,
+    #[default]
+    Other,
+}
+
+impl FoodCategory {
+    /// Returns the name of the category for display, e.g. "Produce"
+    pub fn name(&self) -> &'static str {
+        match self {
+            FoodCategory::Produce => "Produce",
+            FoodCategory::Bakery => "Bakery",
+            FoodCategory::Meat => "Meat",
+            FoodCategory::Seafood => "Seafood",
+            FoodCategory::Dairy => "Dairy & Eggs",
+            FoodCategory::Pantry => "Pantry",
+            FoodCategory::Spices => "Spices",
+            FoodCategory::Frozen => "Frozen",
+            FoodCategory::Beverages => "Beverages",
+            FoodCategory::Other => "Other",
+        }
+    }
+
+    /// Looks up the category of an ingredient in the [built-in dictionary](FoodDictionary::builtin)
+    pub fn of(name: &str) -> FoodCategory {
+        FoodDictionary::builtin()
+            .lookup(name)
+            .map(|food| food.category)
+            .unwrap_or_default()
+    }
+}
+
+/// A canonical food of a [FoodDictionary]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Food {
+    /// The canonical id of the food, e.g. "egg".
+    pub id: String,
+    /// The category of the food.
+    pub category: FoodCategory,
+    /// All names the food is known by, in singular and lower case (e.g. "egg", "ei", "eier").
+    pub names: Vec<String>,
+}
+
+/// A dictionary mapping ingredient names to canonical [foods](Food)
+///
+/// Names are matched as whole words, ignoring case, plural forms and preparation notes
+/// after a comma or in parentheses. If multiple names match, the one ending last wins, as
+/// it is the head noun ("orange juice" is juice), then the longest one, so "peanut butter"
+/// is not mistaken for "butter". Names used as a verb at the start of a text, like "Roll
+/// out the dough", are not matched.
+///
+/// ## Example
+///
+/// ```
+/// use reget::{FoodCategory, FoodDictionary};
+///
+/// let dictionary = FoodDictionary::builtin();
+/// for name in ["large eggs", "egg", "Eier", "eggs, beaten"] {
+///     assert_eq!(dictionary.lookup(name).unwrap().id, "egg");
+/// }
+///
+/// let dictionary = FoodDictionary::builtin()
+///     .clone()
+///     .with_food("gochujang", FoodCategory::Pantry, &["gochujang", "korean chili paste"]);
+/// assert_eq!(dictionary.lookup("2 tbsp Gochujang").unwrap().id, "gochujang");
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct FoodDictionary {
+    foods: Vec<Food>,
+    /// The names of the foods that contain a list word, like "half and half"
+    compounds: Vec<String>,
+}
+
+static BUILTIN: LazyLock<FoodDictionary> = LazyLock::new(|| {
+    BUILTIN_FOODS.iter().fold(
+        FoodDictionary::new(),
+        |dictionary, (id, category, names)| dictionary.with_food(id, *category, names),
+    )
+});
+
+impl FoodDictionary {
+    /// Constructs an empty dictionary
+    pub fn new() -> Self {
+        FoodDictionary::default()
+    }
+
+    /// Returns the built-in dictionary with common foods in English and German
+    pub fn builtin() -> &'static FoodDictionary {
+        &BUILTIN
+    }
+
+    /// Adds a food with the given names, or adds the names to the food and changes its
+    /// category if it already exists
+    pub fn with_food(mut self, id: &str, category: FoodCategory, names: &[&str]) -> Self {
+        let names: Vec<String> = names.iter().map(|name| normalize_name(name)).collect();
+        self.compounds.extend(
+            names
+                .iter()
+                .filter(|name| split_list(name).len() > 1)
+                .cloned(),
+        );
+        match self.foods.iter_mut().find(|food| food.id == id) {
+            Some(food) => {
+                food.names.extend(names);
+                food.category = category;
+            }
+            None => self.foods.push(Food {
+                id: id.to_string(),
+                category,
+                names,
+            }),
+        }
+        self
+    }
+
+    /// Returns all foods of the dictionary
+    pub fn foods(&self) -> &[Food] {
+        &self.foods
+    }
+
+    /// Looks up the canonical food for an ingredient name
+    ///
+    /// For names listing several foods, e.g. "salt and pepper", the first food is returned.
+    pub fn lookup(&self, name: &str) -> Option<&Food> {
+        self.lookup_all(name).into_iter().next()
+    }
+
+    /// Looks up the canonical foods for an ingredient name listing several foods, e.g. salt
+    /// and black pepper for "salt and pepper"
+    ///
+    /// Names of the dictionary containing "and", like "half and half", are kept together.
+    pub fn lookup_all(&self, name: &str) -> Vec<&Food> {
+        let name = normalize_name(name);
+        let compound = self.compounds.iter().any(|n| contains_phrase(&name, n));
+        let parts = if compound {
+            vec![name]
+        } else {
+            split_list(&name)
+        };
+
+        let mut foods: Vec<&Food> = Vec::new();
+        for food in parts.iter().filter_map(|part| self.find_head(part)) {
+            if !foods.contains(&food) {
+                foods.push(food);
+            }
+        }
+        foods
+    }
+
+    /// Returns the food whose name ends last in the text, the longest one if several do
+    fn find_head(&self, text: &str) -> Option<&Food> {
+        self.foods
+            .iter()
+            .flat_map(|food| food.names.iter().map(move |n| (food, n)))
+            .flat_map(|(food, n)| {
+                find_phrase(text, n)
+                    .filter(|range| !is_verb(text, range))
+                    .map(move |range| (food, range))
+            })
+            .max_by_key(|(_, range)| (range.end, range.len()))
+            .map(|(food, _)| food)
+    }
+
+    /// Parses the ingredient like [parse_ingredient] and annotates it with the food of
+    /// this dictionary
+    pub fn parse_ingredient(&self, line: &str) -> ParsedIngredient {
+        let mut parsed = parse_ingredient(line);
+        self.annotate(&mut parsed);
+        parsed
+    }
+
+    /// Sets the food id and category of the parsed ingredient
+    pub(crate) fn annotate(&self, parsed: &mut ParsedIngredient) {
+        let food = self.lookup(&parsed.name);
+        parsed.food = food.map(|food| food.id.clone());
+        parsed.category = food.map(|food| food.category).unwrap_or_default();
+    }
+}
+
+/// Splits a list of foods at "and", "or" and "&", e.g. "salt and pepper"
+fn split_list(text: &str) -> Vec<String> {
+    text.split(' ')
+        .collect::<Vec<_>>()
+        .split(|word| LIST_WORDS.contains(word))
+        .filter(|words| !words.is_empty())
+        .map(|words| words.join(" "))
+        .collect()
+}
+
+/// Words joining foods in a list, in English and German
+const LIST_WORDS: &[&str] = &["and", "or", "&", "und", "oder"];
+
+/// Normalizes an ingredient name for lookups, e.g. "Large Eggs, beaten" -> "large egg"
+pub(crate) fn normalize_name(name: &str) -> String {
+    let name = name.split([',', '(']).next().unwrap_or_default();
+    name.to_lowercase()
+        .split_whitespace()
+        .map(singularize)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The foods of the [built-in dictionary](FoodDictionary::builtin)
+const BUILTIN_FOODS: &[(&str, FoodCategory, &[&str])] = &[
+    // Dairy & Eggs
+    ("egg", FoodCategory::Dairy, &["egg", "ei", "eier"]),
+    (
+        "egg_yolk",
+        FoodCategory::Dairy,
+        &["egg yolk", "yolk", "eigelb", "eidotter"],
+    ),
+    (
+        "egg_white",
+        FoodCategory::Dairy,
+        &["egg white", "eiweiß", "eiweiss", "eiklar"],
+    ),
+    ("butter", FoodCategory::Dairy, &["butter"]),
+    (
+        "milk",
+        FoodCategory::Dairy,
+        &["milk", "whole milk", "milch", "vollmilch"],
+    ),
+    (
+        "buttermilk",
+        FoodCategory::Dairy,
+        &["buttermilk", "buttermilch"],
+    ),
+    (
+        "cream",
+        FoodCategory::Dairy,
+        &[
+            "cream",
+            "heavy cream",
+            "whipping cream",
+            "double cream",
+            "sahne",
+            "schlagsahne",
+        ],
+    ),
+    (
+        "sour_cream",
+        FoodCategory::Dairy,
+        &["sour cream", "saure sahne", "schmand"],
+    ),
+    (
+        "creme_fraiche",
+        FoodCategory::Dairy,
+        &["crème fraîche", "creme fraiche"],
+    ),
+    (
+        "yogurt",
+        FoodCategory::Dairy,
+        &["yogurt", "yoghurt", "joghurt"],
+    ),
+    ("cheese", FoodCategory::Dairy, &["cheese", "käse"]),
+    (
+        "parmesan",
+        FoodCategory::Dairy,
+        &["parmesan", "parmigiano", "parmigiano reggiano"],
+    ),
+    ("mozzarella", FoodCategory::Dairy, &["mozzarella"]),
+    ("cheddar", FoodCategory::Dairy, &["cheddar"]),
+    ("feta", FoodCategory::Dairy, &["feta"]),
+    ("ricotta", FoodCategory::Dairy, &["ricotta"]),
+    (
+        "cream_cheese",
+        FoodCategory::Dairy,
+        &["cream cheese", "frischkäse"],
+    ),
+    ("quark", FoodCategory::Dairy, &["quark", "topfen"]),
+    // Meat
+    (
+        "chicken",
+        FoodCategory::Meat,
+        &[
+            "chicken",
+            "chicken breast",
+            "chicken thigh",
+            "hähnchen",
+            "hähnchenbrust",
+            "huhn",
+        ],
+    ),
+    (
+        "beef",
+        FoodCategory::Meat,
+        &["beef", "steak", "rindfleisch", "rind"],
+    ),
+    (
+        "ground_beef",
+        FoodCategory::Meat,
+        &[
+            "ground beef",
+            "minced beef",
+            "rinderhackfleisch",
+            "hackfleisch",
+        ],
+    ),
+    ("pork", FoodCategory::Meat, &["pork", "schweinefleisch"]),
+    ("bacon", FoodCategory::Meat, &["bacon", "speck", "pancetta"]),
+    (
+        "sausage",
+        FoodCategory::Meat,
+        &["sausage", "wurst", "bratwurst"],
+    ),
+    ("ham", FoodCategory::Meat, &["ham", "schinken"]),
+    ("lamb", FoodCategory::Meat, &["lamb", "lammfleisch", "lamm"]),
+    (
+        "turkey",
+        FoodCategory::Meat,
+        &["turkey", "pute", "truthahn"],
+    ),
+    // Seafood
+    ("salmon", FoodCategory::Seafood, &["salmon", "lachs"]),
+    ("tuna", FoodCategory::Seafood, &["tuna", "thunfisch"]),
+    ("cod", FoodCategory::Seafood, &["cod", "kabeljau"]),
+    (
+        "fish",
+        FoodCategory::Seafood,
+        &["fish", "fisch", "fish fillet"],
+    ),
+    (
+        "shrimp",
+        FoodCategory::Seafood,
+        &["shrimp", "prawn", "garnele", "garnelen", "krabben"],
+    ),
+    (
+        "anchovy",
+        FoodCategory::Seafood,
+        &["anchovy", "anchovies", "sardelle", "sardellen"],
+    ),
+    (
+        "mussel",
+        FoodCategory::Seafood,
+        &["mussel", "muschel", "muscheln", "miesmuscheln"],
+    ),
+    // Bakery
+    ("bread", FoodCategory::Bakery, &["bread", "brot", "toast"]),
+    ("baguette", FoodCategory::Bakery, &["baguette"]),
+    ("tortilla", FoodCategory::Bakery, &["tortilla", "wrap"]),
+    ("bun", FoodCategory::Bakery, &["bun", "roll", "brötchen"]),
+    // Produce
+    (
+        "onion",
+        FoodCategory::Produce,
+        &["onion", "zwiebel", "zwiebeln"],
+    ),
+    (
+        "red_onion",
+        FoodCategory::Produce,
+        &["red onion", "rote zwiebel", "rote zwiebeln"],
+    ),
+    (
+        "spring_onion",
+        FoodCategory::Produce,
+        &[
+            "spring onion",
+            "green onion",
+            "scallion",
+            "frühlingszwiebel",
+            "frühlingszwiebeln",
+            "lauchzwiebel",
+        ],
+    ),
+    (
+        "shallot",
+        FoodCategory::Produce,
+        &["shallot", "schalotte", "schalotten"],
+    ),
+    (
+        "garlic",
+        FoodCategory::Produce,
+        &[
+            "garlic",
+            "garlic clove",
+            "knoblauch",
+            "knoblauchzehe",
+            "knoblauchzehen",
+        ],
+    ),
+    (
+        "tomato",
+        FoodCategory::Produce,
+        &["tomato", "tomate", "tomaten"],
+    ),
+    (
+        "potato",
+        FoodCategory::Produce,
+        &["potato", "kartoffel", "kartoffeln"],
+    ),
+    (
+        "sweet_potato",
+        FoodCategory::Produce,
+        &["sweet potato", "süßkartoffel", "süßkartoffeln"],
+    ),
+    (
+        "carrot",
+        FoodCategory::Produce,
+        &["carrot", "karotte", "karotten", "möhre", "möhren"],
+    ),
+    (
+        "celery",
+        FoodCategory::Produce,
+        &["celery", "celery stalk", "sellerie", "staudensellerie"],
+    ),
+    (
+        "bell_pepper",
+        FoodCategory::Produce,
+        &["bell pepper", "paprikaschote", "paprikaschoten"],
+    ),
+    (
+        "chili",
+        FoodCategory::Produce,
+        &[
+            "chili",
+            "chile",
+            "chilli",
+            "chilischote",
+            "jalapeño",
+            "jalapeno",
+        ],
+    ),
+    (
+        "zucchini",
+        FoodCategory::Produce,
+        &["zucchini", "courgette"],
+    ),
+    (
+        "eggplant",
+        FoodCategory::Produce,
+        &["eggplant", "aubergine", "auberginen"],
+    ),
+    (
+        "cucumber",
+        FoodCategory::Produce,
+        &["cucumber", "gurke", "gurken"],
+    ),
+    (
+        "mushroom",
+        FoodCategory::Produce,
+        &["mushroom", "champignon", "champignons", "pilze"],
+    ),
+    ("spinach", FoodCategory::Produce, &["spinach", "spinat"]),
+    (
+        "lettuce",
+        FoodCategory::Produce,
+        &["lettuce", "salat", "kopfsalat"],
+    ),
+    (
+        "cabbage",
+        FoodCategory::Produce,
+        &["cabbage", "kohl", "weißkohl"],
+    ),
+    ("broccoli", FoodCategory::Produce, &["broccoli", "brokkoli"]),
+    ("leek", FoodCategory::Produce, &["leek", "lauch", "porree"]),
+    (
+        "lemon",
+        FoodCategory::Produce,
+        &["lemon", "zitrone", "zitronen"],
+    ),
+    (
+        "lime",
+        FoodCategory::Produce,
+        &["lime", "limette", "limetten"],
+    ),
+    ("orange", FoodCategory::Produce, &["orange", "orangen"]),
+    ("apple", FoodCategory::Produce, &["apple", "apfel", "äpfel"]),
+    (
+        "banana",
+        FoodCategory::Produce,
+        &["banana", "banane", "bananen"],
+    ),
+    (
+        "berries",
+        FoodCategory::Produce,
+        &[
+            "berry",
+            "strawberry",
+            "blueberry",
+            "raspberry",
+            "beeren",
+            "erdbeeren",
+            "himbeeren",
+            "heidelbeeren",
+        ],
+    ),
+    ("avocado", FoodCategory::Produce, &["avocado"]),
+    ("ginger", FoodCategory::Produce, &["ginger", "ingwer"]),
+    ("parsley", FoodCategory::Produce, &["parsley", "petersilie"]),
+    ("basil", FoodCategory::Produce, &["basil", "basilikum"]),
+    (
+        "cilantro",
+        FoodCategory::Produce,
+        &["cilantro", "coriander leaves", "koriandergrün"],
+    ),
+    ("chives", FoodCategory::Produce, &["chive", "schnittlauch"]),
+    ("dill", FoodCategory::Produce, &["dill"]),
+    ("mint", FoodCategory::Produce, &["mint", "minze"]),
+    ("rosemary", FoodCategory::Produce, &["rosemary", "rosmarin"]),
+    ("thyme", FoodCategory::Produce, &["thyme", "thymian"]),
+    // Pantry
+    (
+        "flour",
+        FoodCategory::Pantry,
+        &[
+            "flour",
+            "all-purpose flour",
+            "plain flour",
+            "mehl",
+            "weizenmehl",
+        ],
+    ),
+    (
+        "sugar",
+        FoodCategory::Pantry,
+        &["sugar", "granulated sugar", "caster sugar", "zucker"],
+    ),
+    (
+        "brown_sugar",
+        FoodCategory::Pantry,
+        &["brown sugar", "brauner zucker"],
+    ),
+    (
+        "powdered_sugar",
+        FoodCategory::Pantry,
+        &[
+            "powdered sugar",
+            "icing sugar",
+            "confectioners sugar",
+            "puderzucker",
+        ],
+    ),
+    ("honey", FoodCategory::Pantry, &["honey", "honig"]),
+    ("rice", FoodCategory::Pantry, &["rice", "reis"]),
+    (
+        "pasta",
+        FoodCategory::Pantry,
+        &["pasta", "spaghetti", "penne", "noodle", "nudeln"],
+    ),
+    (
+        "oats",
+        FoodCategory::Pantry,
+        &["oat", "rolled oat", "haferflocken"],
+    ),
+    (
+        "semolina",
+        FoodCategory::Pantry,
+        &["semolina", "semola", "grieß", "griess"],
+    ),
+    (
+        "olive_oil",
+        FoodCategory::Pantry,
+        &["olive oil", "olivenöl"],
+    ),
+    (
+        "oil",
+        FoodCategory::Pantry,
+        &[
+            "oil",
+            "vegetable oil",
+            "sunflower oil",
+            "öl",
+            "rapsöl",
+            "sonnenblumenöl",
+        ],
+    ),
+    ("vinegar", FoodCategory::Pantry, &["vinegar", "essig"]),
+    (
+        "soy_sauce",
+        FoodCategory::Pantry,
+        &["soy sauce", "sojasauce", "sojasoße"],
+    ),
+    (
+        "mustard",
+        FoodCategory::Pantry,
+        &["mustard", "dijon mustard", "senf"],
+    ),
+    (
+        "baking_powder",
+        FoodCategory::Pantry,
+        &["baking powder", "backpulver"],
+    ),
+    (
+        "baking_soda",
+        FoodCategory::Pantry,
+        &["baking soda", "bicarbonate of soda", "natron"],
+    ),
+    (
+        "yeast",
+        FoodCategory::Pantry,
+        &["yeast", "hefe", "trockenhefe"],
+    ),
+    (
+        "cornstarch",
+        FoodCategory::Pantry,
+        &["cornstarch", "cornflour", "speisestärke", "maisstärke"],
+    ),
+    (
+        "stock",
+        FoodCategory::Pantry,
+        &[
+            "stock",
+            "broth",
+            "chicken stock",
+            "chicken broth",
+            "beef stock",
+            "vegetable stock",
+            "vegetable broth",
+            "brühe",
+            "gemüsebrühe",
+            "hühnerbrühe",
+            "fond",
+        ],
+    ),
+    (
+        "canned_tomatoes",
+        FoodCategory::Pantry,
+        &[
+            "canned tomato",
+            "crushed tomato",
+            "diced tomato",
+            "dosentomaten",
+            "passata",
+            "tomato sauce",
+        ],
+    ),
+    (
+        "tomato_paste",
+        FoodCategory::Pantry,
+        &["tomato paste", "tomatenmark"],
+    ),
+    (
+        "chickpeas",
+        FoodCategory::Pantry,
+        &["chickpea", "kichererbse", "kichererbsen"],
+    ),
+    (
+        "lentils",
+        FoodCategory::Pantry,
+        &["lentil", "linse", "linsen"],
+    ),
+    (
+        "beans",
+        FoodCategory::Pantry,
+        &["bean", "black bean", "kidney bean", "bohne", "bohnen"],
+    ),
+    (
+        "coconut_milk",
+        FoodCategory::Pantry,
+        &["coconut milk", "kokosmilch"],
+    ),
+    (
+        "peanut_butter",
+        FoodCategory::Pantry,
+        &["peanut butter", "erdnussbutter"],
+    ),
+    (
+        "chocolate",
+        FoodCategory::Pantry,
+        &["chocolate", "chocolate chip", "schokolade"],
+    ),
+    (
+        "cocoa",
+        FoodCategory::Pantry,
+        &["cocoa", "cocoa powder", "kakao", "kakaopulver"],
+    ),
+    (
+        "nuts",
+        FoodCategory::Pantry,
+        &[
+            "nut",
+            "almond",
+            "walnut",
+            "hazelnut",
+            "cashew",
+            "pecan",
+            "nüsse",
+            "mandeln",
+            "walnüsse",
+            "haselnüsse",
+        ],
+    ),
+    (
+        "peanuts",
+        FoodCategory::Pantry,
+        &["peanut", "erdnuss", "erdnüsse"],
+    ),
+    (
+        "breadcrumbs",
+        FoodCategory::Pantry,
+        &["breadcrumb", "panko", "paniermehl", "semmelbrösel"],
+    ),
+    (
+        "sesame",
+        FoodCategory::Pantry,
+        &["sesame", "sesame seed", "sesame oil", "sesam", "tahini"],
+    ),
+    // Spices
+    (
+        "salt",
+        FoodCategory::Spices,
+        &["salt", "sea salt", "kosher salt", "salz"],
+    ),
+    (
+        "black_pepper",
+        FoodCategory::Spices,
+        &["pepper", "black pepper", "pfeffer"],
+    ),
+    (
+        "paprika",
+        FoodCategory::Spices,
+        &["paprika", "smoked paprika", "paprikapulver"],
+    ),
+    ("cinnamon", FoodCategory::Spices, &["cinnamon", "zimt"]),
+    ("cumin", FoodCategory::Spices, &["cumin", "kreuzkümmel"]),
+    ("oregano", FoodCategory::Spices, &["oregano"]),
+    (
+        "nutmeg",
+        FoodCategory::Spices,
+        &["nutmeg", "muskat", "muskatnuss"],
+    ),
+    (
+        "vanilla",
+        FoodCategory::Spices,
+        &["vanilla", "vanilla extract", "vanille", "vanillezucker"],
+    ),
+    (
+        "chili_flakes",
+        FoodCategory::Spices,
+        &["chili flake", "red pepper flake", "chiliflocken"],
+    ),
+    (
+        "curry_powder",
+        FoodCategory::Spices,
+        &["curry powder", "currypulver"],
+    ),
+    (
+        "bay_leaf",
+        FoodCategory::Spices,
+        &["bay leaf", "bay leave", "lorbeerblatt", "lorbeerblätter"],
+    ),
+    // Frozen
+    (
+        "frozen_peas",
+        FoodCategory::Frozen,
+        &["frozen pea", "tiefkühlerbsen"],
+    ),
+    (
+        "ice_cream",
+        FoodCategory::Frozen,
+        &["ice cream", "eiscreme"],
+    ),
+    // Beverages
+    ("water", FoodCategory::Beverages, &["water", "wasser"]),
+    (
+        "wine",
+        FoodCategory::Beverages,
+        &[
+            "wine",
+            "white wine",
+            "red wine",
+            "wein",
+            "weißwein",
+            "rotwein",
+        ],
+    ),
+    ("beer", FoodCategory::Beverages, &["beer", "bier"]),
+    ("juice", FoodCategory::Beverages, &["juice", "saft"]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod lookup {
+        use super::*;
+
+        #[test]
+        fn lookup_plural_and_synonyms() {
+            let dictionary = FoodDictionary::builtin();
+            for name in [
+                "large eggs",
+                "egg",
+                "Eier",
+                "eggs, beaten",
+                "2 Eier (Größe M)",
+            ] {
+                assert_eq!(dictionary.lookup(name).unwrap().id, "egg", "{name}");
+            }
+        }
+
+        #[test]
+        fn lookup_longest_match() {
+            let dictionary = FoodDictionary::builtin();
+            assert_eq!(
+                dictionary.lookup("peanut butter").unwrap().id,
+                "peanut_butter"
+            );
+            assert_eq!(dictionary.lookup("egg yolks").unwrap().id, "egg_yolk");
+            assert_eq!(dictionary.lookup("unsalted butter").unwrap().id, "butter");
+        }
+
+        #[test]
+        fn lookup_whole_words() {
+            let dictionary = FoodDictionary::builtin();
+            assert_eq!(dictionary.lookup("buttermilk").unwrap().id, "buttermilk");
+            assert_eq!(dictionary.lookup("eggplant").unwrap().id, "eggplant");
+        }
+
+        #[test]
+        fn lookup_head_noun() {
+            let dictionary = FoodDictionary::builtin();
+            assert_eq!(dictionary.lookup("orange juice").unwrap().id, "juice");
+            assert_eq!(dictionary.lookup("2 chicken eggs").unwrap().id, "egg");
+            assert_eq!(FoodCategory::of("orange juice"), FoodCategory::Beverages);
+        }
+
+        #[test]
+        fn lookup_lists() {
+            let dictionary = FoodDictionary::builtin();
+            assert_eq!(dictionary.lookup("salt and pepper").unwrap().id, "salt");
+            let ids: Vec<&str> = dictionary
+                .lookup_all("Salt & pepper")
+                .iter()
+                .map(|food| food.id.as_str())
+                .collect();
+            assert_eq!(ids, vec!["salt", "black_pepper"]);
+
+            let dictionary = FoodDictionary::new()
+                .with_food("half_and_half", FoodCategory::Dairy, &["half and half"])
+                .with_food("milk", FoodCategory::Dairy, &["milk"]);
+            assert_eq!(
+                dictionary.lookup("half and half").unwrap().id,
+                "half_and_half"
+            );
+        }
+
+        #[test]
+        fn lookup_ignores_verbs() {
+            let dictionary = FoodDictionary::builtin();
+            assert_eq!(dictionary.lookup("Roll out the dough"), None);
+            assert_eq!(dictionary.lookup("4 bread rolls").unwrap().id, "bun");
+            assert_eq!(dictionary.lookup("rolls").unwrap().id, "bun");
+        }
+
+        #[test]
+        fn lookup_unknown() {
+            assert_eq!(FoodDictionary::builtin().lookup("unobtainium"), None);
+        }
+
+        #[test]
+        fn category() {
+            assert_eq!(FoodCategory::of("unsalted butter"), FoodCategory::Dairy);
+            assert_eq!(FoodCategory::of("peanut butter"), FoodCategory::Pantry);
+            assert_eq!(FoodCategory::of("red onion"), FoodCategory::Produce);
+            assert_eq!(FoodCategory::of("unobtainium"), FoodCategory::Other);
+        }
+    }
+
+    mod extension {
+        use super::*;
+
+        #[test]
+        fn add_food() {
+            let dictionary = FoodDictionary::new().with_food(
+                "gochujang",
+                FoodCategory::Pantry,
+                &["Gochujang", "korean chili pastes"],
+            );
+            let food = dictionary.lookup("korean chili paste").unwrap();
+            assert_eq!(food.id, "gochujang");
+            assert_eq!(food.names, vec!["gochujang", "korean chili paste"]);
+        }
+
+        #[test]
+        fn add_names_to_existing_food() {
+            let dictionary =
+                FoodDictionary::builtin()
+                    .clone()
+                    .with_food("egg", FoodCategory::Dairy, &["huevo"]);
+            assert_eq!(dictionary.lookup("huevos").unwrap().id, "egg");
+        }
+
+        #[test]
+        fn change_category_of_existing_food() {
+            let dictionary =
+                FoodDictionary::builtin()
+                    .clone()
+                    .with_food("egg", FoodCategory::Other, &[]);
+            assert_eq!(
+                dictionary.lookup("eggs").unwrap().category,
+                FoodCategory::Other
+            );
+        }
+
+        #[test]
+        fn annotate_ingredient() {
+            let parsed = FoodDictionary::builtin().parse_ingredient("3 large eggs, beaten");
+            assert_eq!(parsed.food, Some("egg".into()));
+            assert_eq!(parsed.category, FoodCategory::Dairy);
+            assert_eq!(parsed.name, "large eggs, beaten");
+        }
+    }
+}
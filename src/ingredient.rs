@@ -0,0 +1,379 @@
+use crate::food::{FoodCategory, FoodDictionary};
+use crate::units::{Quantity, Unit};
+
+/// An [ingredient](crate::Ingredient) split into its amount, unit and name.
+///
+/// Created using [parse_ingredient].
+#[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParsedIngredient {
+    /// The amount of the ingredient, if one was given (e.g. `1.5` for "1 1/2 cups flour").
+    pub amount: Option<f64>,
+    /// The unit of the amount, if one was recognized.
+    pub unit: Option<Unit>,
+    /// The remaining text, usually the name of the ingredient (e.g. "flour").
+    pub name: String,
+    /// The canonical id of the [food](crate::Food), if the name was found in the dictionary.
+    pub food: Option<String>,
+    /// The category of the food, [other](FoodCategory::Other) if it is unknown.
+    pub category: FoodCategory,
+}
+
+impl ParsedIngredient {
+    /// Returns the [quantity](Quantity) if both amount and unit are known.
+    pub fn quantity(&self) -> Option<Quantity> {
+        Some(Quantity::new(self.amount?, self.unit?))
+    }
+}
+
+/// Splits an ingredient line into amount, unit and name.
+///
+/// The ingredient is annotated with its canonical food from the
+/// [built-in dictionary](FoodDictionary::builtin).
+///
+/// It deals with:
+///     - "2 cups flour", "200g butter", "2 eggs"
+///     - "1 1/2 tbsp sugar", "1½ tbsp sugar", "0,5 l milk"
+///     - "salt" (no amount or unit)
+///
+/// ## Example
+///
+/// ```
+/// use reget::{parse_ingredient, Unit};
+///
+/// let ingredient = parse_ingredient("1 1/2 cups flour");
+/// assert_eq!(ingredient.amount, Some(1.5));
+/// assert_eq!(ingredient.unit, Some(Unit::Cup));
+/// assert_eq!(ingredient.name, "flour");
+/// assert_eq!(ingredient.food.as_deref(), Some("flour"));
+/// ```
+pub fn parse_ingredient(line: &str) -> ParsedIngredient {
+    let line = line.trim();
+    let (amount, rest) = match parse_amount(line) {
+        Some((amount, rest)) => (Some(amount), rest),
+        None => (None, line),
+    };
+
+    let (unit, rest) = match amount.and(parse_unit(rest)) {
+        Some((unit, rest)) => (Some(unit), rest),
+        None => (None, rest),
+    };
+
+    let name = rest.trim();
+    let name = name.strip_prefix("of ").unwrap_or(name).trim();
+
+    let mut parsed = ParsedIngredient {
+        amount,
+        unit,
+        name: name.to_string(),
+        ..Default::default()
+    };
+    FoodDictionary::builtin().annotate(&mut parsed);
+    parsed
+}
+
+/// Returns the name of the group if the line is a heading rather than an ingredient.
+///
+/// It deals with:
+///     - "For the dough:", "Sauce:"
+///     - "--- Sauce ---", "== Sauce ==", "*Sauce*", "# Sauce"
+///     - "FILLING"
+pub(crate) fn heading_name(line: &str) -> Option<String> {
+    let line = line.trim();
+    let stripped =
+        line.trim_matches(|c: char| HEADING_DECORATION.contains(&c) || c.is_whitespace());
+    let has_letters = stripped.chars().any(char::is_alphabetic);
+    let has_digits = stripped
+        .chars()
+        .any(|c| c.is_ascii_digit() || vulgar_fraction(c).is_some());
+    if !has_letters || has_digits {
+        return None;
+    }
+
+    let decorated = stripped.len() != line.len();
+    let colon = stripped.ends_with(':');
+    let upper_case = stripped.chars().filter(|c| c.is_alphabetic()).count() >= 3
+        && !stripped.chars().any(char::is_lowercase);
+    let short = stripped.split_whitespace().count() <= 5;
+
+    if short && (decorated || colon || upper_case) {
+        let name = stripped.trim_end_matches(':').trim();
+        Some(name.to_string())
+    } else {
+        None
+    }
+}
+
+/// Words for the pieces or packages of a food, in singular, e.g. "clove" in "2 cloves
+/// garlic"
+pub(crate) const COUNT_NOUNS: &[&str] = &[
+    "clove", "bulb", "head", "sprig", "stalk", "stick", "bunch", "slice", "piece", "can", "jar",
+    "package", "packet", "bag", "bottle", "box", "leaf", "knob", "fillet", "zehe", "knolle",
+    "dose", "packung", "bund", "scheibe", "stück",
+];
+
+/// Characters used to decorate headings inside ingredient lists
+const HEADING_DECORATION: &[char] = &['-', '=', '*', '#', '_', '–', '—'];
+
+/// Parses a leading amount such as "2", "1.5", "0,5", "1/2", "½", "1 ½" or "1 1/2".
+///
+/// Returns the amount and the remaining text.
+pub(crate) fn parse_amount(text: &str) -> Option<(f64, &str)> {
+    let (mut amount, mut rest) = parse_number(text)?;
+
+    // A mixed number like "1 1/2" or "1 ½"
+    if amount.fract() == 0.0
+        && let Some((fraction, after)) = parse_number(rest.trim_start())
+        && fraction < 1.0
+    {
+        amount += fraction;
+        rest = after;
+    }
+
+    Some((amount, rest))
+}
+
+/// Parses a single number, fraction or vulgar fraction at the start of the text.
+fn parse_number(text: &str) -> Option<(f64, &str)> {
+    if let Some(c) = text.chars().next()
+        && let Some(value) = vulgar_fraction(c)
+    {
+        return Some((value, &text[c.len_utf8()..]));
+    }
+
+    let digits_end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    if digits_end == 0 {
+        return None;
+    }
+    let whole = &text[..digits_end];
+    let rest = &text[digits_end..];
+
+    // Directly attached vulgar fraction, e.g. "1½"
+    if let Some(c) = rest.chars().next()
+        && let Some(value) = vulgar_fraction(c)
+    {
+        return Some((whole.parse::<f64>().ok()? + value, &rest[c.len_utf8()..]));
+    }
+
+    // Fraction, e.g. "1/2"
+    if let Some(after) = rest.strip_prefix('/') {
+        let end = after
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(after.len());
+        let numerator = whole.parse::<f64>().ok()?;
+        let denominator = after[..end].parse::<f64>().ok()?;
+        if denominator == 0.0 {
+            return None;
+        }
+        return Some((numerator / denominator, &after[end..]));
+    }
+
+    // Decimal, e.g. "1.5" or "0,5"
+    if let Some(after) = rest.strip_prefix(['.', ',']) {
+        let end = after
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(after.len());
+        if end > 0 {
+            let value = format!("{whole}.{}", &after[..end]).parse::<f64>().ok()?;
+            return Some((value, &after[end..]));
+        }
+    }
+
+    Some((whole.parse::<f64>().ok()?, rest))
+}
+
+/// Returns the value of a unicode vulgar fraction like "½".
+fn vulgar_fraction(c: char) -> Option<f64> {
+    match c {
+        '¼' => Some(0.25),
+        '½' => Some(0.5),
+        '¾' => Some(0.75),
+        '⅓' => Some(1.0 / 3.0),
+        '⅔' => Some(2.0 / 3.0),
+        '⅕' => Some(0.2),
+        '⅛' => Some(0.125),
+        '⅜' => Some(0.375),
+        '⅝' => Some(0.625),
+        '⅞' => Some(0.875),
+        _ => None,
+    }
+}
+
+/// Parses a leading unit such as "cups", "g" or "fl oz".
+///
+/// Returns the unit and the remaining text.
+pub(crate) fn parse_unit(text: &str) -> Option<(Unit, &str)> {
+    let text = text.trim_start();
+
+    // Units made of two words
+    let lower = text.to_lowercase();
+    for prefix in ["fl oz", "fl. oz", "fluid ounces", "fluid ounce"] {
+        if lower.starts_with(prefix) && is_word_end(&text[prefix.len()..]) {
+            return Some((Unit::FluidOunce, &text[prefix.len()..]));
+        }
+    }
+
+    let end = text
+        .find(|c: char| !c.is_alphabetic())
+        .unwrap_or(text.len());
+    let word = &text[..end];
+    let unit = Unit::parse(word)?;
+    let rest = &text[end..];
+    Some((unit, rest.strip_prefix('.').unwrap_or(rest)))
+}
+
+/// Checks that the text does not continue the current word.
+fn is_word_end(text: &str) -> bool {
+    !text.starts_with(char::is_alphabetic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod amount {
+        use super::*;
+
+        #[test]
+        fn parse_integer() {
+            assert_eq!(parse_amount("2 eggs"), Some((2.0, " eggs")));
+        }
+
+        #[test]
+        fn parse_decimal() {
+            assert_eq!(parse_amount("1.5 cups"), Some((1.5, " cups")));
+            assert_eq!(parse_amount("0,5 l"), Some((0.5, " l")));
+        }
+
+        #[test]
+        fn parse_fraction() {
+            assert_eq!(parse_amount("1/2 cup"), Some((0.5, " cup")));
+        }
+
+        #[test]
+        fn parse_mixed_number() {
+            assert_eq!(parse_amount("1 1/2 cup"), Some((1.5, " cup")));
+            assert_eq!(parse_amount("1 ½ cup"), Some((1.5, " cup")));
+            assert_eq!(parse_amount("1½ cup"), Some((1.5, " cup")));
+        }
+
+        #[test]
+        fn parse_vulgar_fraction() {
+            assert_eq!(parse_amount("¾ cup"), Some((0.75, " cup")));
+        }
+
+        #[test]
+        fn parse_no_amount() {
+            assert_eq!(parse_amount("salt"), None);
+        }
+
+        #[test]
+        fn parse_zero_denominator() {
+            assert_eq!(parse_amount("1/0 cup"), None);
+        }
+    }
+
+    mod heading {
+        use super::*;
+
+        #[test]
+        fn colon_heading() {
+            assert_eq!(heading_name("For the dough:"), Some("For the dough".into()));
+        }
+
+        #[test]
+        fn decorated_heading() {
+            assert_eq!(heading_name("--- Sauce ---"), Some("Sauce".into()));
+            assert_eq!(heading_name("== Sauce =="), Some("Sauce".into()));
+            assert_eq!(heading_name("**Sauce:**"), Some("Sauce".into()));
+        }
+
+        #[test]
+        fn upper_case_heading() {
+            assert_eq!(heading_name("FILLING"), Some("FILLING".into()));
+        }
+
+        #[test]
+        fn ingredients_are_not_headings() {
+            assert_eq!(heading_name("2 cups flour"), None);
+            assert_eq!(heading_name("salt"), None);
+            assert_eq!(heading_name("Salt"), None);
+            assert_eq!(heading_name("1 tbsp. OIL"), None);
+            assert_eq!(heading_name("½ tsp salt:"), None);
+            assert_eq!(heading_name("---"), None);
+        }
+
+        #[test]
+        fn long_lines_are_not_headings() {
+            assert_eq!(
+                heading_name("Salt and pepper to taste, plus more for the table:"),
+                None
+            );
+        }
+    }
+
+    mod ingredient {
+        use super::*;
+
+        #[test]
+        fn parse_amount_unit_name() {
+            let result = parse_ingredient("2 cups flour");
+            assert_eq!(result.amount, Some(2.0));
+            assert_eq!(result.unit, Some(Unit::Cup));
+            assert_eq!(result.name, "flour");
+        }
+
+        #[test]
+        fn parse_attached_unit() {
+            let result = parse_ingredient("200g butter");
+            assert_eq!(result.amount, Some(200.0));
+            assert_eq!(result.unit, Some(Unit::Gram));
+            assert_eq!(result.name, "butter");
+        }
+
+        #[test]
+        fn parse_two_word_unit() {
+            let result = parse_ingredient("4 fl oz milk");
+            assert_eq!(result.unit, Some(Unit::FluidOunce));
+            assert_eq!(result.name, "milk");
+        }
+
+        #[test]
+        fn parse_without_unit() {
+            let result = parse_ingredient("3 eggs");
+            assert_eq!(result.amount, Some(3.0));
+            assert_eq!(result.unit, None);
+            assert_eq!(result.name, "eggs");
+        }
+
+        #[test]
+        fn parse_without_amount() {
+            let result = parse_ingredient("salt to taste");
+            assert_eq!(result.amount, None);
+            assert_eq!(result.unit, None);
+            assert_eq!(result.name, "salt to taste");
+        }
+
+        #[test]
+        fn parse_unit_word_without_amount() {
+            let result = parse_ingredient("cup of tea");
+            assert_eq!(result.unit, None);
+            assert_eq!(result.name, "cup of tea");
+        }
+
+        #[test]
+        fn parse_strips_of() {
+            let result = parse_ingredient("2 cups of flour");
+            assert_eq!(result.name, "flour");
+        }
+
+        #[test]
+        fn parse_does_not_take_unit_prefix() {
+            let result = parse_ingredient("2 large eggs");
+            assert_eq!(result.unit, None);
+            assert_eq!(result.name, "large eggs");
+        }
+    }
+}
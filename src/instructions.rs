@@ -0,0 +1,253 @@
+//! Splits instructions given as a single block of text into individual steps.
+
+use crate::HowToStep;
+
+use scraper::{Html, Selector};
+
+use std::sync::LazyLock;
+
+/// The selectors for list items and paragraphs, in the order they are tried
+static STEP_SELECTORS: LazyLock<[Selector; 2]> = LazyLock::new(|| {
+    [
+        Selector::parse("li").unwrap(),
+        Selector::parse("p").unwrap(),
+    ]
+});
+
+/// Splits run-on instruction text into individual steps
+///
+/// It deals with:
+///     - "1. Preheat the oven. 2. Mix. 3. Bake." and "1) ...", "Step 1: ...", "Schritt 1: ..."
+///     - steps separated by newlines or `<br>`, with or without numbers or bullets
+///     - `<ol><li>...</li></ol>` and `<p>...</p>` fragments
+///
+/// Numbered steps are only split where the numbers count up from one, so
+/// "Bake at 180. 2 eggs are enough." stays a single step.
+pub(crate) fn split_instructions(text: &str) -> Vec<HowToStep> {
+    if text.contains('<')
+        && let Some(steps) = html_steps(text)
+    {
+        return steps;
+    }
+
+    let text = replace_line_breaks(text);
+    text.lines()
+        .flat_map(split_numbered)
+        .map(strip_marker)
+        .filter(|step| !step.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Returns the inner HTML of the `<li>` elements, or the `<p>` elements if there are no
+/// list items. The remaining tags are removed when the text is cleaned.
+fn html_steps(text: &str) -> Option<Vec<HowToStep>> {
+    let fragment = Html::parse_fragment(text);
+    STEP_SELECTORS.iter().find_map(|selector| {
+        let steps: Vec<HowToStep> = fragment
+            .select(selector)
+            .map(|element| {
+                let html = element.inner_html();
+                strip_marker(&collapse_whitespace(&html)).to_string()
+            })
+            .filter(|step| !step.is_empty())
+            .collect();
+        (!steps.is_empty()).then_some(steps)
+    })
+}
+
+/// Replaces `<br>`, `<br/>` and `<br />` with newlines
+fn replace_line_breaks(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        result.push_str(&rest[..start]);
+        let tag = &rest[start..];
+        let end = tag.find('>').map(|end| end + 1);
+        match end {
+            Some(end) if is_line_break(&tag[..end]) => {
+                result.push('\n');
+                rest = &tag[end..];
+            }
+            _ => {
+                result.push('<');
+                rest = &tag[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+fn is_line_break(tag: &str) -> bool {
+    let name = tag
+        .trim_start_matches('<')
+        .trim_end_matches('>')
+        .trim_end_matches('/')
+        .trim();
+    name.eq_ignore_ascii_case("br")
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Splits a line at numbered markers that count up from one, e.g. "1. ... 2. ... 3. ..."
+fn split_numbered(line: &str) -> Vec<&str> {
+    let mut starts = Vec::new();
+    let mut expected = 1;
+
+    for (position, _) in line.char_indices() {
+        let at_word_start = line[..position]
+            .chars()
+            .next_back()
+            .is_none_or(char::is_whitespace);
+        if at_word_start
+            && let Some((number, _)) = parse_marker(&line[position..], &line[..position])
+            && number == expected
+        {
+            starts.push(position);
+            expected += 1;
+        }
+    }
+
+    if starts.len() < 2 {
+        return vec![line];
+    }
+    let mut result = vec![&line[..starts[0]]];
+    for (i, start) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).copied().unwrap_or(line.len());
+        result.push(&line[*start..end]);
+    }
+    result
+}
+
+/// Removes a leading step number or bullet, e.g. "2. Mix" -> "Mix"
+fn strip_marker(step: &str) -> &str {
+    let step = step.trim();
+    let step = match parse_marker(step, "") {
+        Some((_, rest)) => rest,
+        None => step,
+    };
+    step.trim_start()
+        .trim_start_matches(['-', '•', '*', '–'])
+        .trim()
+}
+
+/// Parses a step marker like "1.", "1)", "Step 1:" or "Schritt 1" at the start of the text.
+///
+/// Plain numbers are only markers at the start of the line or after the end of a sentence.
+/// Returns the number and the remaining text.
+fn parse_marker<'a>(text: &'a str, before: &str) -> Option<(u32, &'a str)> {
+    for word in ["step", "schritt"] {
+        if let Some(head) = text.get(..word.len())
+            && head.eq_ignore_ascii_case(word)
+        {
+            let rest = text[word.len()..].trim_start();
+            let (number, rest) = parse_digits(rest)?;
+            return match rest.strip_prefix([':', '.', ')', '-']) {
+                Some(rest) => Some((number, rest)),
+                None if rest.is_empty() || rest.starts_with(char::is_whitespace) => {
+                    Some((number, rest))
+                }
+                None => None,
+            };
+        }
+    }
+
+    let sentence_end = before
+        .trim_end()
+        .chars()
+        .next_back()
+        .is_none_or(|c| ".!?:)".contains(c));
+    if !sentence_end {
+        return None;
+    }
+    let (number, rest) = parse_digits(text)?;
+    let rest = rest.strip_prefix(['.', ')'])?;
+    rest.starts_with(char::is_whitespace)
+        .then_some((number, rest))
+}
+
+fn parse_digits(text: &str) -> Option<(u32, &str)> {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    if end == 0 || end > 2 {
+        return None;
+    }
+    Some((text[..end].parse().ok()?, &text[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_numbered_list() {
+        assert_eq!(
+            split_instructions("1. Preheat the oven to 180°C. 2. Mix the flour. 3) Bake."),
+            vec!["Preheat the oven to 180°C.", "Mix the flour.", "Bake."]
+        );
+    }
+
+    #[test]
+    fn split_step_prefixes() {
+        assert_eq!(
+            split_instructions("Step 1: Chop the onions. Step 2: Fry them. Schritt 3 - Serve."),
+            vec!["Chop the onions.", "Fry them.", "Serve."]
+        );
+    }
+
+    #[test]
+    fn split_newlines() {
+        assert_eq!(
+            split_instructions("Preheat the oven.\n\n- Mix the flour.\r\n2. Bake for 1.5 hours."),
+            vec!["Preheat the oven.", "Mix the flour.", "Bake for 1.5 hours."]
+        );
+    }
+
+    #[test]
+    fn split_line_breaks() {
+        assert_eq!(
+            split_instructions("Preheat the oven.<br/>Mix the flour.<BR>Bake."),
+            vec!["Preheat the oven.", "Mix the flour.", "Bake."]
+        );
+    }
+
+    #[test]
+    fn split_html_list() {
+        assert_eq!(
+            split_instructions("<ol><li>Preheat the <b>oven</b>.</li>\n<li>Bake.</li></ol>"),
+            vec!["Preheat the <b>oven</b>.", "Bake."]
+        );
+    }
+
+    #[test]
+    fn split_html_paragraphs() {
+        assert_eq!(
+            split_instructions("<p>1. Preheat the oven.</p><p>2. Bake.</p>"),
+            vec!["Preheat the oven.", "Bake."]
+        );
+    }
+
+    #[test]
+    fn keep_numbers_that_are_not_markers() {
+        assert_eq!(
+            split_instructions("Bake at 180. 2 eggs are enough. Serve 4 people."),
+            vec!["Bake at 180. 2 eggs are enough. Serve 4 people."]
+        );
+        assert_eq!(
+            split_instructions("Add 2. Then add 1. Done"),
+            vec!["Add 2. Then add 1. Done"]
+        );
+    }
+
+    #[test]
+    fn keep_text_before_first_marker() {
+        assert_eq!(
+            split_instructions("Before you start: 1. Mix. 2. Bake."),
+            vec!["Before you start:", "Mix.", "Bake."]
+        );
+    }
+}
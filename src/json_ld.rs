@@ -0,0 +1,399 @@
+//! Converts a recipe back to schema.org JSON-LD.
+
+use crate::constants::LdFields;
+use crate::timers::{parse_iso_duration, parse_time};
+use crate::{HowToSection, NutritionInformation, Recipe};
+
+use serde_json::{Map, Value, json};
+use std::time::Duration;
+
+/// Converts the recipe to a schema.org Recipe object
+///
+/// It writes:
+///     - the author as a Person
+///     - the ingredients as a single list, with headings like "Dough:" for named sections
+///     - the steps as HowToSteps, named sections as HowToSections
+///     - times as ISO 8601 durations, converting e.g. "30 minutes" to "PT30M"
+pub(crate) fn to_json_ld(recipe: &Recipe) -> Value {
+    let mut json = Map::new();
+    json.insert(LdFields::CONTEXT.into(), "https://schema.org".into());
+    json.insert(LdFields::TYPE.into(), "Recipe".into());
+
+    if let Some(name) = &recipe.name {
+        json.insert(LdFields::NAME.into(), name.as_str().into());
+    }
+    if let Some(author) = &recipe.author {
+        json.insert(
+            LdFields::AUTHOR.into(),
+            json!({ "@type": "Person", "name": author }),
+        );
+    }
+    if let Some(description) = &recipe.description {
+        json.insert(LdFields::DESCRIPTION.into(), description.as_str().into());
+    }
+    for (key, time) in [
+        (LdFields::PREP_TIME, &recipe.prep_time),
+        (LdFields::COOK_TIME, &recipe.cook_time),
+        (LdFields::TOTAL_TIME, &recipe.total_time),
+    ] {
+        if let Some(duration) = time.as_deref().and_then(iso_duration) {
+            json.insert(key.into(), duration.into());
+        }
+    }
+    if let Some(recipe_yield) = &recipe.recipe_yield {
+        json.insert(LdFields::RECIPE_YIELD.into(), recipe_yield.as_str().into());
+    }
+    if !recipe.suitable_for_diet.is_empty() {
+        let diets: Vec<Value> = recipe
+            .suitable_for_diet
+            .iter()
+            .map(|diet| format!("https://schema.org/{diet}").into())
+            .collect();
+        json.insert(LdFields::SUITABLE_FOR_DIET.into(), diets.into());
+    }
+    if !recipe.tools.is_empty() {
+        let tools: Vec<Value> = recipe
+            .tools
+            .iter()
+            .map(|tool| json!({ "@type": "HowToTool", "name": tool }))
+            .collect();
+        json.insert(LdFields::TOOL.into(), tools.into());
+    }
+    if let Some(nutrition) = recipe.nutrition.as_ref().and_then(nutrition_to_json) {
+        json.insert(LdFields::NUTRITION.into(), nutrition);
+    }
+    if !recipe.ingredient_sections.is_empty() {
+        json.insert(
+            LdFields::RECIPE_INGREDIENT.into(),
+            ingredient_lines(recipe).into(),
+        );
+    }
+    if !recipe.how_to_sections.is_empty() {
+        json.insert(
+            LdFields::RECIPE_INSTRUCTIONS.into(),
+            instructions_to_json(&recipe.how_to_sections),
+        );
+    }
+
+    Value::Object(json)
+}
+
+/// Returns the ingredients as a single list, with headings like "Dough:" for named sections
+///
+/// Sections without ingredients are left out, as a heading without ingredients is read back
+/// as an ingredient.
+pub(crate) fn ingredient_lines(recipe: &Recipe) -> Vec<String> {
+    recipe
+        .ingredient_sections
+        .iter()
+        .filter(|section| !section.ingredients.is_empty())
+        .flat_map(|section| {
+            let heading = section.name.as_ref().map(|name| format!("{name}:"));
+            heading
+                .into_iter()
+                .chain(section.ingredients.iter().cloned())
+        })
+        .collect()
+}
+
+/// Writes the steps of a first section without a name directly, all other sections as
+/// HowToSections
+fn instructions_to_json(sections: &[HowToSection]) -> Value {
+    let steps = |section: &HowToSection| -> Vec<Value> {
+        section
+            .steps
+            .iter()
+            .map(|step| json!({ "@type": "HowToStep", "text": step }))
+            .collect()
+    };
+
+    let mut result = Vec::new();
+    for (i, section) in sections.iter().enumerate() {
+        if i == 0 && section.name.is_none() {
+            result.extend(steps(section));
+            continue;
+        }
+        let mut obj = Map::new();
+        obj.insert(LdFields::TYPE.into(), crate::HOW_TO_SECTION_TYPE.into());
+        if let Some(name) = &section.name {
+            obj.insert(LdFields::NAME.into(), name.as_str().into());
+        }
+        obj.insert(LdFields::ITEM_LIST_ELEMENT.into(), steps(section).into());
+        result.push(Value::Object(obj));
+    }
+    result.into()
+}
+
+/// Returns None if the nutrition information has no values
+pub(crate) fn nutrition_to_json(nutrition: &NutritionInformation) -> Option<Value> {
+    let mut json = Map::new();
+    json.insert(LdFields::TYPE.into(), "NutritionInformation".into());
+    for (key, value) in [
+        (LdFields::SERVING_SIZE, &nutrition.serving_size),
+        (LdFields::CALORIES, &nutrition.calories),
+        (LdFields::FAT_CONTENT, &nutrition.fat_content),
+        (
+            LdFields::SATURATED_FAT_CONTENT,
+            &nutrition.saturated_fat_content,
+        ),
+        (
+            LdFields::CARBOHYDRATE_CONTENT,
+            &nutrition.carbohydrate_content,
+        ),
+        (LdFields::SUGAR_CONTENT, &nutrition.sugar_content),
+        (LdFields::FIBER_CONTENT, &nutrition.fiber_content),
+        (LdFields::PROTEIN_CONTENT, &nutrition.protein_content),
+        (LdFields::SODIUM_CONTENT, &nutrition.sodium_content),
+    ] {
+        if let Some(value) = value {
+            json.insert(key.into(), value.as_str().into());
+        }
+    }
+    (json.len() > 1).then_some(Value::Object(json))
+}
+
+/// Keeps ISO 8601 durations as they are and converts durations like "1 hour 30 minutes"
+pub(crate) fn iso_duration(time: &str) -> Option<String> {
+    if parse_iso_duration(time).is_some() {
+        return Some(time.to_string());
+    }
+    parse_time(time).map(format_iso_duration)
+}
+
+/// Formats a duration, e.g. "PT1H30M"
+fn format_iso_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    let mut result = String::from("PT");
+    for (value, designator) in [(hours, 'H'), (minutes, 'M'), (seconds, 'S')] {
+        if value > 0 {
+            result.push_str(&format!("{value}{designator}"));
+        }
+    }
+    if result.len() == 2 {
+        result.push_str("0S");
+    }
+    result
+}
+
+/// Wraps the JSON-LD in a script tag, escaping "</" so the JSON cannot end the script
+pub(crate) fn to_script(json: &Value) -> String {
+    format!(
+        "<script type=\"application/ld+json\">{}</script>",
+        json.to_string().replace("</", "<\\/")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{IngredientSection, parse_recipe};
+
+    fn round_trip(recipe: &Recipe) -> Recipe {
+        parse_recipe(&recipe.to_json_ld_script()).unwrap()
+    }
+
+    #[test]
+    fn write_structure() {
+        let recipe = Recipe {
+            name: Some("Pancakes".into()),
+            author: Some("Jane".into()),
+            ingredient_sections: vec![
+                IngredientSection {
+                    name: None,
+                    ingredients: vec!["2 eggs".into()],
+                },
+                IngredientSection {
+                    name: Some("Topping".into()),
+                    ingredients: vec!["Maple syrup".into()],
+                },
+            ],
+            how_to_sections: vec![HowToSection {
+                name: Some("Frying".into()),
+                steps: vec!["Fry.".into()],
+            }],
+            suitable_for_diet: vec!["VegetarianDiet".into()],
+            cook_time: Some("20 minutes".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            recipe.to_json_ld(),
+            json!({
+                "@context": "https://schema.org",
+                "@type": "Recipe",
+                "name": "Pancakes",
+                "author": {"@type": "Person", "name": "Jane"},
+                "cookTime": "PT20M",
+                "suitableForDiet": ["https://schema.org/VegetarianDiet"],
+                "recipeIngredient": ["2 eggs", "Topping:", "Maple syrup"],
+                "recipeInstructions": [{
+                    "@type": "HowToSection",
+                    "name": "Frying",
+                    "itemListElement": [{"@type": "HowToStep", "text": "Fry."}]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn round_trip_fixtures() {
+        for html in [
+            include_str!("../tests/fixtures/basic_1.html"),
+            include_str!("../tests/fixtures/basic_2.html"),
+            include_str!("../tests/fixtures/basic_3.html"),
+            include_str!("../tests/fixtures/basic_4.html"),
+            include_str!("../tests/fixtures/basic_5.html"),
+            include_str!("../tests/fixtures/basic_6.html"),
+        ] {
+            let recipe = parse_recipe(html).unwrap();
+            assert_eq!(round_trip(&recipe), recipe);
+        }
+    }
+
+    #[test]
+    fn round_trip_all_fields() {
+        let recipe = Recipe {
+            name: Some("Mac & Cheese".into()),
+            author: Some("Jane, John".into()),
+            description: Some("Creamy.".into()),
+            ingredient_sections: vec![
+                IngredientSection {
+                    name: None,
+                    ingredients: vec!["200 g pasta".into()],
+                },
+                IngredientSection {
+                    name: Some("For the sauce".into()),
+                    ingredients: vec!["100 g cheddar".into(), "1 cup milk".into()],
+                },
+            ],
+            how_to_sections: vec![
+                HowToSection {
+                    name: None,
+                    steps: vec!["1. Boil the pasta.".into()],
+                },
+                HowToSection {
+                    name: None,
+                    steps: vec!["Melt the cheese.".into()],
+                },
+                HowToSection {
+                    name: Some("Serving".into()),
+                    steps: vec!["Mix and serve.".into()],
+                },
+            ],
+            suitable_for_diet: vec!["VegetarianDiet".into()],
+            recipe_yield: Some("4 servings".into()),
+            nutrition: Some(NutritionInformation {
+                calories: Some("540 kcal".into()),
+                ..Default::default()
+            }),
+            prep_time: Some("PT10M".into()),
+            cook_time: Some("PT20M".into()),
+            total_time: Some("PT30M".into()),
+            tools: vec!["Pot".into()],
+        };
+        assert_eq!(round_trip(&recipe), recipe);
+    }
+
+    #[test]
+    fn round_trip_parsed_headings() {
+        let html = Recipe {
+            ingredient_sections: vec![IngredientSection {
+                name: None,
+                ingredients: vec![
+                    "--- Pasta ---".into(),
+                    "200 g pasta".into(),
+                    "SALT".into(),
+                    "PEPPER".into(),
+                    "For the topping:".into(),
+                ],
+            }],
+            ..Default::default()
+        }
+        .to_json_ld_script();
+        let recipe = parse_recipe(&html).unwrap();
+        assert_eq!(
+            recipe.ingredient_sections,
+            vec![IngredientSection {
+                name: Some("Pasta".into()),
+                ingredients: vec![
+                    "200 g pasta".into(),
+                    "SALT".into(),
+                    "PEPPER".into(),
+                    "For the topping:".into(),
+                ],
+            }]
+        );
+        assert_eq!(round_trip(&recipe), recipe);
+    }
+
+    #[test]
+    fn sections_without_round_trip() {
+        let section = |name: Option<&str>, ingredients: &[&str]| IngredientSection {
+            name: name.map(String::from),
+            ingredients: ingredients.iter().map(|i| i.to_string()).collect(),
+        };
+
+        // An unnamed section after a named one is merged into it
+        let recipe = Recipe {
+            ingredient_sections: vec![
+                section(Some("Dough"), &["500 g flour"]),
+                section(None, &["Salt"]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            round_trip(&recipe).ingredient_sections,
+            vec![section(Some("Dough"), &["500 g flour", "Salt"])]
+        );
+
+        // Ingredients that look like headings are read as headings
+        let recipe = Recipe {
+            ingredient_sections: vec![section(None, &["SALT", "200 g pasta"])],
+            ..Default::default()
+        };
+        assert_eq!(
+            round_trip(&recipe).ingredient_sections,
+            vec![section(Some("SALT"), &["200 g pasta"])]
+        );
+
+        // Sections without ingredients are left out
+        let recipe = Recipe {
+            ingredient_sections: vec![
+                section(None, &["200 g pasta"]),
+                section(Some("Topping"), &[]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            recipe.to_json_ld()["recipeIngredient"],
+            json!(["200 g pasta"])
+        );
+        assert_eq!(
+            round_trip(&recipe).ingredient_sections,
+            vec![section(None, &["200 g pasta"])]
+        );
+    }
+
+    #[test]
+    fn escape_script_end() {
+        let recipe = Recipe {
+            name: Some("</script><b>".into()),
+            ..Default::default()
+        };
+        let script = recipe.to_json_ld_script();
+        assert_eq!(script.matches("</script>").count(), 1);
+        assert!(script.contains(r#""name":"<\/script><b>""#));
+    }
+
+    #[test]
+    fn format_durations() {
+        assert_eq!(iso_duration("PT1H").as_deref(), Some("PT1H"));
+        assert_eq!(
+            iso_duration("1 hour 30 minutes").as_deref(),
+            Some("PT1H30M")
+        );
+        assert_eq!(iso_duration("about 10-15 min").as_deref(), Some("PT15M"));
+        assert_eq!(iso_duration("soon"), None);
+        assert_eq!(format_iso_duration(Duration::ZERO), "PT0S");
+    }
+}
@@ -1,9 +1,22 @@
 //! # Recipe Extraction from HTML documents
 //!
 //! `reget` provides a [single function](parse_recipe) to extract a [recipe](Recipe) from HTML documents
-//! using structured data (JSON-LD) embedded within.
+//! using the JSON-LD embedded within. A [RecipeParser] can be configured to also read microdata
+//! or RDFa, or to guess the recipe from the headings of the page.
 //!
-//! With the optional `markdown` feature, recipes can be [converted to a markdown string](MarkdownBuilder).
+//! With the optional `markdown` feature, recipes can be [converted to a markdown string](MarkdownBuilder)
+//! and [read back](MarkdownReader), or converted to [RecipeMD](RecipeMdBuilder) and [back](parse_recipe_md).
+//! With the optional `cooklang` feature, recipes can be [converted to Cooklang](CooklangBuilder)
+//! and [parsed from Cooklang](parse_cooklang).
+//! The optional `paprika`, `mealie`, `tandoor` and `nextcloud` features export recipes to the
+//! formats of these recipe managers, see e.g. [Recipe::to_mealie_json]. Paprika archives can be
+//! [imported](parse_paprika_archive) as well, and with the optional `mealmaster` feature, recipes
+//! can be [read from MealMaster files](parse_mealmaster).
+//! With the optional `serde` feature, recipes can be serialized and deserialized, see [Recipe].
+//!
+//! Ingredient quantities and temperatures or pan sizes in the steps can be
+//! [converted](Recipe::convert_units) between metric and US customary units, and the
+//! ingredients of multiple recipes can be combined into a [shopping list](ShoppingList).
 //!
 //! This library assumes the document follows the [schema.org recipe specification](https://schema.org/Recipe).
 //!
@@ -37,111 +50,260 @@
 //!     .convert();
 //! ```
 
+mod allergens;
 mod constants;
+mod converter;
+#[cfg(feature = "cooklang")]
+mod cooklang;
+mod diet;
+mod equipment;
+mod food;
+mod ingredient;
+mod instructions;
+mod json_ld;
 #[cfg(feature = "markdown")]
 mod markdown;
+#[cfg(feature = "mealie")]
+mod mealie;
+#[cfg(feature = "mealmaster")]
+mod mealmaster;
 mod model;
-
+#[cfg(feature = "nextcloud")]
+mod nextcloud;
+mod normalize;
+#[cfg(feature = "nutrition")]
+mod nutrition;
+#[cfg(feature = "paprika")]
+mod paprika;
+mod parser;
+#[cfg(feature = "markdown")]
+mod recipe_md;
+mod report;
+mod sanitize;
+mod shopping;
+mod steps;
+#[cfg(feature = "tandoor")]
+mod tandoor;
+mod text;
+mod timers;
+mod units;
+mod validate;
+
+pub use allergens::{Allergen, AllergenMatch};
 use constants::LdFields;
+pub use converter::UnitConverter;
+#[cfg(feature = "cooklang")]
+pub use cooklang::{CooklangBuilder, parse_cooklang};
+pub use diet::{Confidence, Diet, DietAssessment};
+pub use equipment::{Equipment, EquipmentVocabulary};
+pub use food::{Food, FoodCategory, FoodDictionary};
+pub use ingredient::{ParsedIngredient, parse_ingredient};
+#[cfg(feature = "markdown")]
+pub use markdown::{MarkdownBuilder, MarkdownReader};
+#[cfg(feature = "mealmaster")]
+pub use mealmaster::parse_mealmaster;
+pub use model::{
+    HowToSection, HowToStep, Ingredient, IngredientSection, NutritionInformation, Recipe,
+};
+pub use normalize::{QuoteStyle, TextNormalizer, UnicodeForm};
+#[cfg(feature = "nutrition")]
+pub use nutrition::NutritionEstimate;
+#[cfg(feature = "paprika")]
+pub use paprika::{parse_paprika_archive, to_paprika_archive};
+pub use parser::{Locale, RecipeParser, Strictness};
 #[cfg(feature = "markdown")]
-pub use markdown::MarkdownBuilder;
-pub use model::{HowToSection, HowToStep, Ingredient, Recipe};
+pub use recipe_md::{RecipeMdBuilder, parse_recipe_md};
+pub use report::ExtractionWarning;
+pub use sanitize::TextFormat;
+use sanitize::clean_text;
+pub use shopping::{ShoppingItem, ShoppingList};
+pub use steps::{IngredientRef, Step};
+pub use timers::{DurationMention, find_durations};
+pub use units::{Dimension, Quantity, Unit, UnitSystem};
+pub use validate::{Diagnostic, Severity};
 
-use scraper::{Html, Selector};
 use serde_json::{Map, Value};
+use std::sync::LazyLock;
 
-const JSON_LD_SELECTOR: &str = r#"script[type="application/ld+json"]"#;
-const RECIPE_TYPE: &str = "Recipe";
 const HOW_TO_SECTION_TYPE: &str = "HowToSection";
+const SCHEMA_ORG_PREFIXES: [&str; 4] = [
+    "https://schema.org/",
+    "http://schema.org/",
+    "schema.org/",
+    "schema:",
+];
+
+static DEFAULT_PARSER: LazyLock<RecipeParser> = LazyLock::new(RecipeParser::new);
+static MARKDOWN_PARSER: LazyLock<RecipeParser> =
+    LazyLock::new(|| RecipeParser::new().with_text_format(TextFormat::Markdown));
 
 /// Parses the [recipe](Recipe) from the given HTML document. Will return None if no
-/// linked data is found in the document.
+/// JSON-LD recipe is found in the document.
 ///
 /// This function will only extract the first recipe it finds and only if it follows
-/// [schema.org recipe specification](https://schema.org/Recipe).
+/// [schema.org recipe specification](https://schema.org/Recipe). Use a [RecipeParser]
+/// to change the settings.
 ///
 /// For an example see [here](crate).
 pub fn parse_recipe(html: &str) -> Option<Recipe> {
-    let json = extract_recipe_json(html)?;
-    Some(extract_recipe(&json))
+    DEFAULT_PARSER.parse(html)
+}
+
+/// Parses the [recipe](Recipe) like [parse_recipe], converting HTML inside the text
+/// fields to the given [format](TextFormat).
+///
+/// All text fields are normalized with the default [TextNormalizer].
+///
+/// ## Example
+///
+/// ```
+/// use reget::{parse_recipe_as, TextFormat};
+///
+/// let html = r#"
+/// <script type="application/ld+json">
+/// {
+///   "@type": "Recipe",
+///   "name": "Mac &amp;amp; Cheese",
+///   "recipeInstructions": "<p><b>Boil</b> the pasta.</p>"
+/// }
+/// </script>
+/// "#;
+///
+/// let recipe = parse_recipe_as(html, TextFormat::Markdown).unwrap();
+/// assert_eq!(recipe.name.as_deref(), Some("Mac & Cheese"));
+/// assert_eq!(recipe.how_to_sections[0].steps, vec!["**Boil** the pasta."]);
+/// ```
+pub fn parse_recipe_as(html: &str, format: TextFormat) -> Option<Recipe> {
+    match format {
+        TextFormat::Plain => DEFAULT_PARSER.parse(html),
+        TextFormat::Markdown => MARKDOWN_PARSER.parse(html),
+    }
+}
+
+/// Parses the [recipe](Recipe) like [parse_recipe] and reports the data that was ignored
+///
+/// It reports:
+///     - values of an unexpected type, e.g. ingredients that are objects
+///     - steps without a text and sections without steps
+///     - properties that are not part of the schema.org recipe specification
+///
+/// Each [warning](ExtractionWarning) has a JSON pointer relative to the recipe, the reason
+/// and an excerpt of the ignored value.
+///
+/// ## Example
+///
+/// ```
+/// use reget::parse_recipe_with_report;
+///
+/// let html = r#"
+/// <script type="application/ld+json">
+/// {
+///   "@type": "Recipe",
+///   "name": "Pancakes",
+///   "recipeIngredient": ["2 eggs", { "name": "flour" }]
+/// }
+/// </script>
+/// "#;
+///
+/// let (recipe, warnings) = parse_recipe_with_report(html).unwrap();
+/// assert_eq!(recipe.ingredients().count(), 1);
+/// assert_eq!(warnings[0].path, "/recipeIngredient/1");
+/// assert_eq!(warnings[0].excerpt, r#"{"name":"flour"}"#);
+/// ```
+pub fn parse_recipe_with_report(html: &str) -> Option<(Recipe, Vec<ExtractionWarning>)> {
+    DEFAULT_PARSER.parse_with_report(html)
+}
+
+/// Validates the JSON-LD of the first recipe in the HTML document against schema.org types
+/// and the required and recommended properties of Google's recipe rich results.
+///
+/// It checks e.g. that `image` and `name` exist, that `totalTime` is an ISO 8601 duration and
+/// that the instructions are HowToSteps with a text. Each [diagnostic](Diagnostic) has a
+/// [severity](Severity) and a JSON pointer to the offending value.
+///
+/// ## Example
+///
+/// ```
+/// use reget::{validate_recipe, Severity};
+///
+/// let html = r#"
+/// <script type="application/ld+json">
+/// {
+///   "@context": "https://schema.org",
+///   "@type": "Recipe",
+///   "name": "Pancakes",
+///   "totalTime": "30 minutes"
+/// }
+/// </script>
+/// "#;
+///
+/// let errors: Vec<_> = validate_recipe(html)
+///     .into_iter()
+///     .filter(|d| d.severity == Severity::Error)
+///     .map(|d| d.path)
+///     .collect();
+/// assert_eq!(errors, vec!["/image", "/totalTime"]);
+/// ```
+pub fn validate_recipe(html: &str) -> Vec<Diagnostic> {
+    DEFAULT_PARSER.validate(html)
 }
 
-fn extract_recipe(json: &Map<String, Value>) -> Recipe {
+/// Extracts the recipe from its JSON-LD object
+///
+/// All text fields are cleaned with [clean_text]. Authors, yields, tools and nutrition
+/// values are always cleaned to plain text, as they are names and amounts.
+fn extract_recipe(json: &Map<String, Value>, format: TextFormat, group_sections: bool) -> Recipe {
+    let clean = |text: &str| clean_text(text, format);
+    let plain = |text: String| clean_text(&text, TextFormat::Plain);
     Recipe {
-        name: json
-            .get(LdFields::NAME)
-            .and_then(Value::as_str)
-            .map(String::from),
-        author: json.get(LdFields::AUTHOR).and_then(extract_author),
+        name: json.get(LdFields::NAME).and_then(Value::as_str).map(clean),
+        author: json
+            .get(LdFields::AUTHOR)
+            .and_then(extract_author)
+            .map(plain),
         description: json
             .get(LdFields::DESCRIPTION)
             .and_then(Value::as_str)
-            .map(String::from),
-        ingredients: json
+            .map(clean),
+        ingredient_sections: json
             .get(LdFields::RECIPE_INGREDIENT)
             .map(extract_ingredients)
+            .map(|ingredients| ingredients.iter().map(|i| clean(i)).collect())
+            .map(|ingredients| {
+                if group_sections {
+                    group_ingredients(ingredients)
+                } else {
+                    single_section(ingredients)
+                }
+            })
             .unwrap_or_default(),
         how_to_sections: json
             .get(LdFields::RECIPE_INSTRUCTIONS)
             .map(extract_instructions)
+            .map(|sections| clean_sections(sections, format))
             .unwrap_or_default(),
-    }
-}
-
-/// Looks for `type="application/ld+json"` in the provided html with `"@type": Recipe`.
-fn extract_recipe_json(html: &str) -> Option<Map<String, Value>> {
-    let sel = Selector::parse(JSON_LD_SELECTOR).unwrap();
-    let document = Html::parse_document(html);
-
-    for e in document.select(&sel) {
-        let s = e.text().collect::<String>();
-
-        let value = match serde_json::from_str::<Value>(&s) {
-            Ok(val) => val,
-            Err(_) => continue, // parsing json failed
-        };
-
-        match find_recipe_in_value(value) {
-            Some(val) => return Some(val),
-            None => continue, // this is not the recipe
-        };
-    }
-    None
-}
-
-/// Tries to recursively find a recipe by looking for the tag `"@type": Recipe`.
-fn find_recipe_in_value(value: Value) -> Option<Map<String, Value>> {
-    match value {
-        Value::Object(obj) => {
-            if is_recipe_type(&obj) {
-                return Some(obj);
-            }
-            for (_, v) in obj {
-                if let Some(recipe) = find_recipe_in_value(v) {
-                    return Some(recipe);
-                }
-            }
-        }
-        Value::Array(arr) => {
-            for item in arr {
-                if let Some(recipe) = find_recipe_in_value(item) {
-                    return Some(recipe);
-                }
-            }
-        }
-        _ => {}
-    }
-    None
-}
-
-/// Verifies that the obj contains the tag `"@type": Recipe`.
-fn is_recipe_type(obj: &serde_json::Map<String, Value>) -> bool {
-    match obj.get(LdFields::TYPE) {
-        Some(Value::String(s)) => s == RECIPE_TYPE,
-        Some(Value::Array(arr)) => arr
-            .iter()
-            .any(|t| matches!(t, Value::String(type_str) if type_str == RECIPE_TYPE)),
-        _ => false,
+        suitable_for_diet: json
+            .get(LdFields::SUITABLE_FOR_DIET)
+            .map(extract_diets)
+            .unwrap_or_default(),
+        recipe_yield: json
+            .get(LdFields::RECIPE_YIELD)
+            .and_then(extract_yield)
+            .map(plain),
+        nutrition: json
+            .get(LdFields::NUTRITION)
+            .and_then(extract_nutrition)
+            .map(clean_nutrition),
+        prep_time: extract_string(json, LdFields::PREP_TIME),
+        cook_time: extract_string(json, LdFields::COOK_TIME),
+        total_time: extract_string(json, LdFields::TOTAL_TIME),
+        tools: json
+            .get(LdFields::TOOL)
+            .map(extract_tools)
+            .unwrap_or_default()
+            .into_iter()
+            .map(plain)
+            .collect(),
     }
 }
 
@@ -198,10 +360,167 @@ fn extract_ingredients(value: &serde_json::Value) -> Vec<Ingredient> {
     }
 }
 
+/// Extracts the diets
+///
+/// It deals with:
+///     - "suitableForDiet": "https://schema.org/VeganDiet"
+///     - "suitableForDiet": [ "VeganDiet", "https://schema.org/GlutenFreeDiet" ]
+///     - "suitableForDiet": [ { "@id": "https://schema.org/VeganDiet" } ]
+///
+/// The schema.org prefix is removed, returning e.g. "VeganDiet".
+fn extract_diets(value: &serde_json::Value) -> Vec<String> {
+    match value {
+        Value::Array(arr) => arr.iter().flat_map(extract_diets).collect(),
+        Value::String(s) => {
+            let diet = SCHEMA_ORG_PREFIXES
+                .iter()
+                .find_map(|prefix| s.strip_prefix(prefix))
+                .unwrap_or(s)
+                .trim();
+            if diet.is_empty() {
+                vec![]
+            } else {
+                vec![diet.to_string()]
+            }
+        }
+        Value::Object(obj) => obj.get(LdFields::ID).map(extract_diets).unwrap_or_default(),
+        _ => vec![],
+    }
+}
+
+/// Extracts the tools
+///
+/// It deals with:
+///     - "tool": "Dutch oven"
+///     - "tool": [ "Dutch oven", "Whisk" ]
+///     - "tool": [ { "@type": "HowToTool", "name": "Dutch oven" } ]
+fn extract_tools(value: &serde_json::Value) -> Vec<String> {
+    match value {
+        Value::Array(arr) => arr.iter().flat_map(extract_tools).collect(),
+        Value::String(s) if !s.trim().is_empty() => vec![s.trim().to_string()],
+        Value::Object(obj) => obj
+            .get(LdFields::NAME)
+            .map(extract_tools)
+            .unwrap_or_default(),
+        _ => vec![],
+    }
+}
+
+/// Extracts a trimmed, non-empty string field
+fn extract_string(json: &Map<String, Value>, key: &str) -> Option<String> {
+    let value = json.get(key)?.as_str()?.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Extracts the yield
+///
+/// It deals with:
+///     - "recipeYield": "4 servings"
+///     - "recipeYield": 4
+///     - "recipeYield": [ "4", "4 servings" ] (the most descriptive value is used)
+fn extract_yield(value: &serde_json::Value) -> Option<String> {
+    match value {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Array(arr) => arr.iter().filter_map(extract_yield).max_by_key(|s| s.len()),
+        _ => None,
+    }
+}
+
+/// Extracts the nutrition information
+///
+/// It deals with:
+///     - "nutrition": { "calories": "240 kcal", "fatContent": "12 g" }
+///     - "nutrition": { "calories": 240 } (numbers are kept without a unit)
+fn extract_nutrition(value: &serde_json::Value) -> Option<NutritionInformation> {
+    let obj = value.as_object()?;
+    let field = |key: &str| match obj.get(key)? {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    };
+    let nutrition = NutritionInformation {
+        serving_size: field(LdFields::SERVING_SIZE),
+        calories: field(LdFields::CALORIES),
+        fat_content: field(LdFields::FAT_CONTENT),
+        saturated_fat_content: field(LdFields::SATURATED_FAT_CONTENT),
+        carbohydrate_content: field(LdFields::CARBOHYDRATE_CONTENT),
+        sugar_content: field(LdFields::SUGAR_CONTENT),
+        fiber_content: field(LdFields::FIBER_CONTENT),
+        protein_content: field(LdFields::PROTEIN_CONTENT),
+        sodium_content: field(LdFields::SODIUM_CONTENT),
+    };
+    (nutrition != NutritionInformation::default()).then_some(nutrition)
+}
+
+/// Cleans every value of the nutrition information to plain text, see [clean_text]
+fn clean_nutrition(nutrition: NutritionInformation) -> NutritionInformation {
+    let clean = |value: Option<String>| value.map(|value| clean_text(&value, TextFormat::Plain));
+    NutritionInformation {
+        serving_size: clean(nutrition.serving_size),
+        calories: clean(nutrition.calories),
+        fat_content: clean(nutrition.fat_content),
+        saturated_fat_content: clean(nutrition.saturated_fat_content),
+        carbohydrate_content: clean(nutrition.carbohydrate_content),
+        sugar_content: clean(nutrition.sugar_content),
+        fiber_content: clean(nutrition.fiber_content),
+        protein_content: clean(nutrition.protein_content),
+        sodium_content: clean(nutrition.sodium_content),
+    }
+}
+
+/// Groups the ingredients into [IngredientSection]s
+///
+/// Headings like "For the dough:", "--- Sauce ---" or "FILLING" found in the list
+/// start a new section. Ingredients before the first heading are put in a section
+/// without a name.
+///
+/// A line only counts as a heading if an ingredient follows it, otherwise it is kept as
+/// an ingredient. This keeps lists like "SALT", "PEPPER" and trailing headings intact.
+fn group_ingredients(ingredients: Vec<Ingredient>) -> Vec<IngredientSection> {
+    let mut result = Vec::new();
+    let mut current_section = IngredientSection::default();
+    let mut ingredients = ingredients.into_iter().peekable();
+
+    while let Some(ingredient) = ingredients.next() {
+        let followed_by_ingredient = ingredients
+            .peek()
+            .is_some_and(|next| ingredient::heading_name(next).is_none());
+        match ingredient::heading_name(&ingredient).filter(|_| followed_by_ingredient) {
+            Some(name) => {
+                // Push the current section if it has ingredients before starting a new one
+                if !current_section.ingredients.is_empty() {
+                    result.push(std::mem::take(&mut current_section));
+                }
+                current_section.name = Some(name);
+            }
+            None => current_section.ingredients.push(ingredient),
+        }
+    }
+
+    if !current_section.ingredients.is_empty() {
+        result.push(current_section);
+    }
+
+    result
+}
+
+/// Puts all ingredients into a single [IngredientSection] without a name
+fn single_section(ingredients: Vec<Ingredient>) -> Vec<IngredientSection> {
+    if ingredients.is_empty() {
+        return vec![];
+    }
+    vec![IngredientSection {
+        name: None,
+        ingredients,
+    }]
+}
+
 /// Extracts the instructions
 ///
 /// It deals with:
 ///     - "recipeInstructions": "step text"
+///     - "recipeInstructions": "1. step1 2. step2" (see [instructions::split_instructions])
 ///     - "recipeInstructions": [ "step1", "step2" ]
 ///     - "recipeInstructions": [ { "text": "step1" }, { "text": "step2" } ]
 ///     - "recipeInstructions": [ { "text": "step1" }, { "@type": "HowToSection", "name": "...", "itemListElement": [...] } ]
@@ -238,14 +557,32 @@ fn extract_instructions(value: &serde_json::Value) -> Vec<HowToSection> {
         Value::Object(_) if is_how_to_section_obj(value) => {
             extract_section(value).into_iter().collect()
         }
-        // Single step or text
+        // Single step or text, which may contain all steps at once
         _ => vec![HowToSection {
             name: None,
-            steps: extract_step(value),
+            steps: extract_step(value)
+                .iter()
+                .flat_map(|text| instructions::split_instructions(text))
+                .collect(),
         }],
     }
 }
 
+/// Cleans the names and steps of the sections, see [sanitize::clean_text]
+fn clean_sections(sections: Vec<HowToSection>, format: TextFormat) -> Vec<HowToSection> {
+    sections
+        .into_iter()
+        .map(|section| HowToSection {
+            name: section.name.map(|name| clean_text(&name, format)),
+            steps: section
+                .steps
+                .iter()
+                .map(|step| clean_text(step, format))
+                .collect(),
+        })
+        .collect()
+}
+
 /// Extracts a [HowToSection]
 fn extract_section(value: &serde_json::Value) -> Option<HowToSection> {
     if let Value::Object(obj) = value {
@@ -303,11 +640,21 @@ mod tests {
                     name: Some("recipe_name".into()),
                     author: Some("author_name".into()),
                     description: Some("description".into()),
-                    ingredients: vec!["ingredient_1".into(), "ingredient_2".into()],
+                    ingredient_sections: vec![IngredientSection {
+                        name: None,
+                        ingredients: vec!["ingredient_1".into(), "ingredient_2".into()],
+                    }],
                     how_to_sections: vec![HowToSection {
                         name: None,
                         steps: vec!["instruction_1".into()],
-                    }]
+                    }],
+                    suitable_for_diet: vec![],
+                    recipe_yield: None,
+                    nutrition: None,
+                    prep_time: None,
+                    cook_time: None,
+                    total_time: None,
+                    tools: vec![],
                 }
             )
         }
@@ -322,11 +669,21 @@ mod tests {
                     name: Some("recipe_name".into()),
                     author: Some("author_name".into()),
                     description: Some("description".into()),
-                    ingredients: vec!["ingredient_1".into(), "ingredient_2".into()],
+                    ingredient_sections: vec![IngredientSection {
+                        name: None,
+                        ingredients: vec!["ingredient_1".into(), "ingredient_2".into()],
+                    }],
                     how_to_sections: vec![HowToSection {
                         name: None,
                         steps: vec!["instruction_1".into(), "instruction_2".into()],
-                    }]
+                    }],
+                    suitable_for_diet: vec![],
+                    recipe_yield: None,
+                    nutrition: None,
+                    prep_time: None,
+                    cook_time: None,
+                    total_time: None,
+                    tools: vec![],
                 }
             )
         }
@@ -341,11 +698,21 @@ mod tests {
                     name: Some("recipe_name".into()),
                     author: Some("author_name".into()),
                     description: Some("description".into()),
-                    ingredients: vec!["ingredient_1".into(), "ingredient_2".into()],
+                    ingredient_sections: vec![IngredientSection {
+                        name: None,
+                        ingredients: vec!["ingredient_1".into(), "ingredient_2".into()],
+                    }],
                     how_to_sections: vec![HowToSection {
                         name: None,
                         steps: vec!["instruction_1".into(), "instruction_2".into()],
-                    }]
+                    }],
+                    suitable_for_diet: vec![],
+                    recipe_yield: None,
+                    nutrition: None,
+                    prep_time: None,
+                    cook_time: None,
+                    total_time: None,
+                    tools: vec![],
                 }
             )
         }
@@ -360,7 +727,10 @@ mod tests {
                     name: Some("recipe_name".into()),
                     author: Some("author_name".into()),
                     description: Some("description".into()),
-                    ingredients: vec!["ingredient_1".into(), "ingredient_2".into()],
+                    ingredient_sections: vec![IngredientSection {
+                        name: None,
+                        ingredients: vec!["ingredient_1".into(), "ingredient_2".into()],
+                    }],
                     how_to_sections: vec![
                         HowToSection {
                             name: None,
@@ -370,7 +740,14 @@ mod tests {
                             name: Some("section_2".into()),
                             steps: vec!["instruction_3".into(), "instruction_4".into()],
                         }
-                    ]
+                    ],
+                    suitable_for_diet: vec![],
+                    recipe_yield: None,
+                    nutrition: None,
+                    prep_time: None,
+                    cook_time: None,
+                    total_time: None,
+                    tools: vec![],
                 }
             )
         }
@@ -385,7 +762,10 @@ mod tests {
                     name: Some("recipe_name".into()),
                     author: Some("author_name".into()),
                     description: Some("description".into()),
-                    ingredients: vec!["ingredient_1".into(), "ingredient_2".into()],
+                    ingredient_sections: vec![IngredientSection {
+                        name: None,
+                        ingredients: vec!["ingredient_1".into(), "ingredient_2".into()],
+                    }],
                     how_to_sections: vec![
                         HowToSection {
                             name: Some("section_1".into()),
@@ -395,7 +775,14 @@ mod tests {
                             name: Some("section_2".into()),
                             steps: vec!["instruction_3".into(), "instruction_4".into()],
                         }
-                    ]
+                    ],
+                    suitable_for_diet: vec![],
+                    recipe_yield: None,
+                    nutrition: None,
+                    prep_time: None,
+                    cook_time: None,
+                    total_time: None,
+                    tools: vec![],
                 }
             )
         }
@@ -410,7 +797,10 @@ mod tests {
                     name: Some("recipe_name".into()),
                     author: Some("author_name".into()),
                     description: Some("description".into()),
-                    ingredients: vec!["ingredient_1".into(), "ingredient_2".into()],
+                    ingredient_sections: vec![IngredientSection {
+                        name: None,
+                        ingredients: vec!["ingredient_1".into(), "ingredient_2".into()],
+                    }],
                     how_to_sections: vec![
                         HowToSection {
                             name: None,
@@ -420,7 +810,14 @@ mod tests {
                             name: Some("section_2".into()),
                             steps: vec!["instruction_3".into(), "instruction_4".into()],
                         }
-                    ]
+                    ],
+                    suitable_for_diet: vec![],
+                    recipe_yield: None,
+                    nutrition: None,
+                    prep_time: None,
+                    cook_time: None,
+                    total_time: None,
+                    tools: vec![],
                 }
             )
         }
@@ -432,21 +829,21 @@ mod tests {
         #[test]
         fn extract_simple_name() {
             let json = json!({"name": "Recipe Name"});
-            let recipe = extract_recipe(json.as_object().unwrap());
+            let recipe = extract_recipe(json.as_object().unwrap(), TextFormat::Plain, true);
             assert_eq!(recipe.name, Some("Recipe Name".to_string()));
         }
 
         #[test]
         fn extract_missing_name() {
             let json = json!({"description": "Recipe Name"});
-            let recipe = extract_recipe(json.as_object().unwrap());
+            let recipe = extract_recipe(json.as_object().unwrap(), TextFormat::Plain, true);
             assert_eq!(recipe.name, None);
         }
 
         #[test]
         fn extract_non_string_name() {
             let json = json!({"name": 123});
-            let recipe = extract_recipe(json.as_object().unwrap());
+            let recipe = extract_recipe(json.as_object().unwrap(), TextFormat::Plain, true);
             assert_eq!(recipe.name, None);
         }
     }
@@ -524,28 +921,28 @@ mod tests {
         #[test]
         fn extract_simple_description() {
             let json = json!({"description": "A description"});
-            let recipe = extract_recipe(json.as_object().unwrap());
+            let recipe = extract_recipe(json.as_object().unwrap(), TextFormat::Plain, true);
             assert_eq!(recipe.description, Some("A description".to_string()));
         }
 
         #[test]
         fn extract_missing_description() {
             let json = json!({"name": "Cake"});
-            let recipe = extract_recipe(json.as_object().unwrap());
+            let recipe = extract_recipe(json.as_object().unwrap(), TextFormat::Plain, true);
             assert_eq!(recipe.description, None);
         }
 
         #[test]
         fn extract_non_string_description() {
             let json = json!({"description": 456});
-            let recipe = extract_recipe(json.as_object().unwrap());
+            let recipe = extract_recipe(json.as_object().unwrap(), TextFormat::Plain, true);
             assert_eq!(recipe.description, None);
         }
 
         #[test]
         fn extract_empty_description() {
             let json = json!({"description": ""});
-            let recipe = extract_recipe(json.as_object().unwrap());
+            let recipe = extract_recipe(json.as_object().unwrap(), TextFormat::Plain, true);
             assert_eq!(recipe.description, Some("".to_string()));
         }
     }
@@ -605,6 +1002,328 @@ mod tests {
             let result = extract_ingredients(&value);
             assert_eq!(result, vec!["1 cup flour", "2 tbsp sugar"]);
         }
+
+        #[test]
+        fn group_without_headings() {
+            let result = group_ingredients(vec!["1 cup flour".into(), "2 eggs".into()]);
+            assert_eq!(
+                result,
+                vec![IngredientSection {
+                    name: None,
+                    ingredients: vec!["1 cup flour".into(), "2 eggs".into()],
+                }]
+            );
+        }
+
+        #[test]
+        fn group_with_headings() {
+            let result = group_ingredients(vec![
+                "1 pinch salt".into(),
+                "For the dough:".into(),
+                "500 g flour".into(),
+                "--- Sauce ---".into(),
+                "400 g tomatoes".into(),
+                "FILLING".into(),
+                "200 g ricotta".into(),
+            ]);
+            assert_eq!(
+                result,
+                vec![
+                    IngredientSection {
+                        name: None,
+                        ingredients: vec!["1 pinch salt".into()],
+                    },
+                    IngredientSection {
+                        name: Some("For the dough".into()),
+                        ingredients: vec!["500 g flour".into()],
+                    },
+                    IngredientSection {
+                        name: Some("Sauce".into()),
+                        ingredients: vec!["400 g tomatoes".into()],
+                    },
+                    IngredientSection {
+                        name: Some("FILLING".into()),
+                        ingredients: vec!["200 g ricotta".into()],
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn group_keeps_unconfirmed_headings() {
+            let result = group_ingredients(vec![
+                "For the dough:".into(),
+                "For the sauce:".into(),
+                "400 g tomatoes".into(),
+                "For the topping:".into(),
+            ]);
+            assert_eq!(
+                result,
+                vec![
+                    IngredientSection {
+                        name: None,
+                        ingredients: vec!["For the dough:".into()],
+                    },
+                    IngredientSection {
+                        name: Some("For the sauce".into()),
+                        ingredients: vec!["400 g tomatoes".into(), "For the topping:".into()],
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn group_upper_case_ingredients() {
+            let result = group_ingredients(vec![
+                "200 g pasta".into(),
+                "SALT".into(),
+                "PEPPER".into(),
+                "OLIVE OIL".into(),
+            ]);
+            assert_eq!(
+                result,
+                vec![IngredientSection {
+                    name: None,
+                    ingredients: vec![
+                        "200 g pasta".into(),
+                        "SALT".into(),
+                        "PEPPER".into(),
+                        "OLIVE OIL".into(),
+                    ],
+                }]
+            );
+        }
+
+        #[test]
+        fn group_empty() {
+            assert_eq!(group_ingredients(vec![]), vec![]);
+        }
+    }
+
+    mod diets {
+        use super::*;
+
+        #[test]
+        fn extract_string_diet() {
+            let value = json!("https://schema.org/VeganDiet");
+            assert_eq!(extract_diets(&value), vec!["VeganDiet"]);
+        }
+
+        #[test]
+        fn extract_array_diets() {
+            let value = json!(["http://schema.org/VeganDiet", "GlutenFreeDiet", ""]);
+            assert_eq!(extract_diets(&value), vec!["VeganDiet", "GlutenFreeDiet"]);
+        }
+
+        #[test]
+        fn extract_object_diets() {
+            let value = json!([{"@id": "https://schema.org/LowSaltDiet"}, {"name": "x"}]);
+            assert_eq!(extract_diets(&value), vec!["LowSaltDiet"]);
+        }
+
+        #[test]
+        fn extract_recipe_diets() {
+            let json = json!({"suitableForDiet": "https://schema.org/VegetarianDiet"});
+            let recipe = extract_recipe(json.as_object().unwrap(), TextFormat::Plain, true);
+            assert_eq!(recipe.suitable_for_diet, vec!["VegetarianDiet"]);
+        }
+
+        #[test]
+        fn extract_invalid_diets() {
+            assert_eq!(extract_diets(&json!(5)), Vec::<String>::new());
+        }
+    }
+
+    mod nutrition {
+        use super::*;
+
+        #[test]
+        fn extract_string_yield() {
+            assert_eq!(
+                extract_yield(&json!("4 servings")),
+                Some("4 servings".into())
+            );
+        }
+
+        #[test]
+        fn extract_number_yield() {
+            assert_eq!(extract_yield(&json!(4)), Some("4".into()));
+        }
+
+        #[test]
+        fn extract_array_yield() {
+            let value = json!(["4", "4 servings"]);
+            assert_eq!(extract_yield(&value), Some("4 servings".into()));
+        }
+
+        #[test]
+        fn extract_invalid_yield() {
+            assert_eq!(extract_yield(&json!("")), None);
+            assert_eq!(extract_yield(&json!({})), None);
+        }
+
+        #[test]
+        fn recipe_servings() {
+            let servings = |recipe_yield: &str| {
+                Recipe {
+                    recipe_yield: Some(recipe_yield.into()),
+                    ..Default::default()
+                }
+                .servings()
+            };
+            assert_eq!(servings("4"), Some(4.0));
+            assert_eq!(servings("Serves 4-6"), Some(4.0));
+            assert_eq!(servings("one loaf"), None);
+        }
+
+        #[test]
+        fn extract_nutrition_fields() {
+            let value = json!({
+                "@type": "NutritionInformation",
+                "calories": "240 kcal",
+                "proteinContent": "12 g",
+                "sodiumContent": 300
+            });
+            assert_eq!(
+                extract_nutrition(&value),
+                Some(NutritionInformation {
+                    calories: Some("240 kcal".into()),
+                    protein_content: Some("12 g".into()),
+                    sodium_content: Some("300".into()),
+                    ..Default::default()
+                })
+            );
+        }
+
+        #[test]
+        fn extract_empty_nutrition() {
+            assert_eq!(
+                extract_nutrition(&json!({"@type": "NutritionInformation"})),
+                None
+            );
+            assert_eq!(extract_nutrition(&json!("240 kcal")), None);
+        }
+    }
+
+    mod times {
+        use super::*;
+
+        #[test]
+        fn extract_times() {
+            let json = json!({"prepTime": "PT15M", "cookTime": " PT1H ", "totalTime": ""});
+            let recipe = extract_recipe(json.as_object().unwrap(), TextFormat::Plain, true);
+            assert_eq!(recipe.prep_time, Some("PT15M".into()));
+            assert_eq!(recipe.cook_time, Some("PT1H".into()));
+            assert_eq!(recipe.total_time, None);
+            assert_eq!(
+                recipe.total_duration(),
+                Some(std::time::Duration::from_secs(75 * 60))
+            );
+        }
+
+        #[test]
+        fn total_time_takes_precedence() {
+            let json = json!({"prepTime": "PT15M", "totalTime": "PT2H"});
+            let recipe = extract_recipe(json.as_object().unwrap(), TextFormat::Plain, true);
+            assert_eq!(
+                recipe.total_duration(),
+                Some(std::time::Duration::from_secs(7200))
+            );
+        }
+
+        #[test]
+        fn ignore_huge_times() {
+            let html = r#"<script type="application/ld+json">{"@type": "Recipe",
+                "totalTime": "PT99999999999999999999H",
+                "prepTime": "PT3000000000000000H", "cookTime": "PT3000000000000000H",
+                "recipeInstructions": ["Rest 99999999999999999999 days.", "Rest 1 day."]}</script>"#;
+            validate_recipe(html);
+            let recipe = parse_recipe(html).unwrap();
+            assert_eq!(recipe.total_duration(), None);
+            assert_eq!(
+                recipe.steps_duration().1,
+                std::time::Duration::from_secs(86400)
+            );
+        }
+    }
+
+    mod html_text {
+        use super::*;
+
+        #[test]
+        fn clean_all_fields() {
+            let json = json!({
+                "name": "Mac &amp;amp; Cheese",
+                "description": "<p>Creamy &amp; cheesy</p>",
+                "recipeIngredient": ["<b>Sauce:</b>", "200 g cheddar&nbsp;cheese"],
+                "recipeInstructions": [{"text": "Don&#39;t <em>burn</em> it."}]
+            });
+            let recipe = extract_recipe(json.as_object().unwrap(), TextFormat::Plain, true);
+            assert_eq!(recipe.name.as_deref(), Some("Mac & Cheese"));
+            assert_eq!(recipe.description.as_deref(), Some("Creamy & cheesy"));
+            assert_eq!(
+                recipe.ingredient_sections,
+                vec![IngredientSection {
+                    name: Some("Sauce".into()),
+                    ingredients: vec!["200 g cheddar cheese".into()],
+                }]
+            );
+            assert_eq!(recipe.how_to_sections[0].steps, vec!["Don't burn it."]);
+        }
+
+        #[test]
+        fn clean_names_and_amounts() {
+            let json = json!({
+                "author": [{"name": "Jane &amp; John"}, "<a href=\"https://example.org\">Max</a>"],
+                "recipeYield": "4&nbsp;servings",
+                "tool": ["<b>Dutch oven</b>"],
+                "nutrition": {"calories": "240&nbsp;kcal", "fatContent": "<span>12 g</span>"}
+            });
+            let recipe = extract_recipe(json.as_object().unwrap(), TextFormat::Markdown, true);
+            assert_eq!(recipe.author.as_deref(), Some("Jane & John, Max"));
+            assert_eq!(recipe.recipe_yield.as_deref(), Some("4 servings"));
+            assert_eq!(recipe.tools, vec!["Dutch oven"]);
+            let nutrition = recipe.nutrition.unwrap();
+            assert_eq!(nutrition.calories.as_deref(), Some("240 kcal"));
+            assert_eq!(nutrition.fat_content.as_deref(), Some("12 g"));
+        }
+
+        #[test]
+        fn convert_to_markdown() {
+            let json = json!({
+                "recipeInstructions": "<ol><li>Mix <b>well</b>.</li><li>Bake.</li></ol>"
+            });
+            let recipe = extract_recipe(json.as_object().unwrap(), TextFormat::Markdown, true);
+            assert_eq!(
+                recipe.how_to_sections[0].steps,
+                vec!["Mix **well**.", "Bake."]
+            );
+        }
+    }
+
+    mod tools {
+        use super::*;
+
+        #[test]
+        fn extract_string_tool() {
+            assert_eq!(extract_tools(&json!("Dutch oven")), vec!["Dutch oven"]);
+        }
+
+        #[test]
+        fn extract_mixed_tools() {
+            let value = json!([
+                "Whisk",
+                {"@type": "HowToTool", "name": "Dutch oven"},
+                {"@type": "HowToTool"},
+                ""
+            ]);
+            assert_eq!(extract_tools(&value), vec!["Whisk", "Dutch oven"]);
+        }
+
+        #[test]
+        fn extract_invalid_tools() {
+            assert_eq!(extract_tools(&json!(5)), Vec::<String>::new());
+        }
     }
 
     mod instructions {
@@ -623,6 +1342,24 @@ mod tests {
                 assert_eq!(result[0].steps, vec![instruction]);
             }
 
+            #[test]
+            fn extract_numbered() {
+                let value = json!("1. Preheat the oven. 2. Mix the flour.\n3. Bake.");
+                let result = extract_instructions(&value);
+                assert_eq!(result.len(), 1);
+                assert_eq!(
+                    result[0].steps,
+                    vec!["Preheat the oven.", "Mix the flour.", "Bake."]
+                );
+            }
+
+            #[test]
+            fn extract_newlines() {
+                let value = json!("Preheat the oven.\n\nMix the flour.");
+                let result = extract_instructions(&value);
+                assert_eq!(result[0].steps, vec!["Preheat the oven.", "Mix the flour."]);
+            }
+
             #[test]
             fn extract_invalid_type() {
                 let result = extract_step(&Value::Number(1.into()));
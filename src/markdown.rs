@@ -1,7 +1,14 @@
-use crate::Recipe;
+use crate::{HowToSection, IngredientSection, Recipe};
 
 use std::fmt::Write;
 
+/// The name of the ingredient section used by default
+const INGREDIENT_SECTION_NAME: &str = "Ingredients";
+/// The name used by default if the recipe does not have a name
+const DEFAULT_RECIPE_NAME: &str = "Recipe";
+/// The name used by default if a how to section does not have a name
+const DEFAULT_SECTION_NAME: &str = "Instructions";
+
 /// A builder to convert the contents of a recipe a markdown string
 ///
 /// ## Example
@@ -29,12 +36,16 @@ use std::fmt::Write;
 ///
 /// This is the description.
 ///
-/// # Ingredients
+/// ## Ingredients
 ///
 /// - Ingredient 1
 /// - Ingredient 2
 ///
-/// # Preparation
+/// ### Ingredient Section
+///
+/// - Ingredient 3
+///
+/// ## Preparation
 ///
 /// Step 1 do xyz.
 ///
@@ -65,9 +76,9 @@ impl<'a> MarkdownBuilder<'a> {
         MarkdownBuilder {
             recipe,
             url: None,
-            ingredient_section_name: "Ingredients",
-            default_recipe_name: "Recipe",
-            default_section_name: "Instructions",
+            ingredient_section_name: INGREDIENT_SECTION_NAME,
+            default_recipe_name: DEFAULT_RECIPE_NAME,
+            default_section_name: DEFAULT_SECTION_NAME,
             result: String::new(),
         }
     }
@@ -144,13 +155,21 @@ impl<'a> MarkdownBuilder<'a> {
     }
 
     /// Writes ingredients section to the output string, if there are any
+    ///
+    /// Named ingredient sections are written under their own subheading.
     fn put_ingredients(&mut self) {
-        if !self.recipe.ingredients.is_empty() {
+        if self.recipe.ingredients().next().is_some() {
             writeln!(self.result).unwrap();
             writeln!(self.result, "## {}", self.ingredient_section_name).unwrap();
-            writeln!(self.result).unwrap();
-            for ingredient in &self.recipe.ingredients {
-                writeln!(self.result, "- {ingredient}").unwrap();
+            for section in &self.recipe.ingredient_sections {
+                if let Some(name) = &section.name {
+                    writeln!(self.result).unwrap();
+                    writeln!(self.result, "### {name}").unwrap();
+                }
+                writeln!(self.result).unwrap();
+                for ingredient in &section.ingredients {
+                    writeln!(self.result, "- {ingredient}").unwrap();
+                }
             }
         }
     }
@@ -168,3 +187,280 @@ impl<'a> MarkdownBuilder<'a> {
         }
     }
 }
+
+/// A reader for the markdown written by a [MarkdownBuilder], e.g. to re-import notes that
+/// were edited by hand
+///
+/// The names of the ingredient section and the defaults have to match those of the
+/// builder. A title equal to the default name and sections named like the default section
+/// are read as having no name, so the recipe is read back exactly as it was written.
+///
+/// It deals with:
+///     - the front matter with the URL and the author, other properties are skipped
+///     - the title, the description and the ingredient section
+///     - ingredient sections as `###` headings, lists separated by a blank line as
+///       sections without a name
+///     - every other `##` heading as a section of steps, separated by blank lines
+///
+/// ## Example
+///
+/// ```
+/// use reget::{MarkdownReader, Recipe};
+///
+/// let recipe = Recipe {
+///     name: Some("Pancakes".into()),
+///     ..Default::default()
+/// };
+/// let markdown = recipe
+///     .to_markdown()
+///     .with_url("https://example.org/recipe")
+///     .with_ingredient_section("Zutaten")
+///     .convert();
+///
+/// let reader = MarkdownReader::new().with_ingredient_section("Zutaten");
+/// let (parsed, url) = reader.parse_with_url(&markdown).unwrap();
+/// assert_eq!(parsed, recipe);
+/// assert_eq!(url.as_deref(), Some("https://example.org/recipe"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarkdownReader<'a> {
+    /// The name of the ingredient section
+    ingredient_section_name: &'a str,
+    /// The name written if the recipe did not have a name
+    default_recipe_name: &'a str,
+    /// The name written if a how to section did not have a name
+    default_section_name: &'a str,
+}
+
+impl Default for MarkdownReader<'_> {
+    fn default() -> Self {
+        MarkdownReader {
+            ingredient_section_name: INGREDIENT_SECTION_NAME,
+            default_recipe_name: DEFAULT_RECIPE_NAME,
+            default_section_name: DEFAULT_SECTION_NAME,
+        }
+    }
+}
+
+/// The part of the markdown being read
+enum Part {
+    Description,
+    Ingredients,
+    Steps,
+}
+
+impl<'a> MarkdownReader<'a> {
+    const PROPERTY_MARKER: &'static str = "---";
+
+    /// Constructs a reader with the same names a [MarkdownBuilder] uses by default
+    pub fn new() -> Self {
+        MarkdownReader::default()
+    }
+
+    /// Uses the name for the ingredient section, default is *Ingredients*
+    pub fn with_ingredient_section(mut self, name: &'a str) -> Self {
+        self.ingredient_section_name = name;
+        self
+    }
+
+    /// Reads the recipe as having no name if the title is this name, default is *Recipe*
+    pub fn with_default_name(mut self, name: &'a str) -> Self {
+        self.default_recipe_name = name;
+        self
+    }
+
+    /// Reads sections with this name as having no name, default is *Instructions*
+    pub fn with_default_section(mut self, name: &'a str) -> Self {
+        self.default_section_name = name;
+        self
+    }
+
+    /// Reads the recipe, returns None if the markdown has no `#` title
+    pub fn parse(&self, markdown: &str) -> Option<Recipe> {
+        self.parse_with_url(markdown).map(|(recipe, _)| recipe)
+    }
+
+    /// Reads the recipe like [parse](MarkdownReader::parse), together with the URL of the
+    /// front matter
+    pub fn parse_with_url(&self, markdown: &str) -> Option<(Recipe, Option<String>)> {
+        let mut recipe = Recipe::default();
+        let mut url = None;
+        let mut lines = markdown.lines().skip_while(|line| line.trim().is_empty());
+        let mut line = lines.next()?;
+
+        if line.trim_end() == Self::PROPERTY_MARKER {
+            for property in lines.by_ref() {
+                if property.trim_end() == Self::PROPERTY_MARKER {
+                    break;
+                }
+                match property.split_once(':') {
+                    Some(("url", value)) => url = Some(value.trim().to_string()),
+                    Some(("author", value)) => recipe.author = Some(value.trim().to_string()),
+                    _ => {}
+                }
+            }
+            line = lines.find(|line| !line.trim().is_empty())?;
+        }
+
+        let name = line.strip_prefix("# ")?.trim();
+        recipe.name = (name != self.default_recipe_name).then(|| name.to_string());
+
+        let mut part = Part::Description;
+        let mut description: Vec<&str> = Vec::new();
+        let mut steps: Vec<&str> = Vec::new();
+        let mut new_list = true;
+        for line in lines {
+            if let Some(heading) = line.strip_prefix("## ") {
+                let heading = heading.trim();
+                if let Some(section) = recipe.how_to_sections.last_mut() {
+                    section.steps = paragraphs(&steps);
+                }
+                steps.clear();
+                if matches!(part, Part::Description) && heading == self.ingredient_section_name {
+                    part = Part::Ingredients;
+                    continue;
+                }
+                part = Part::Steps;
+                recipe.how_to_sections.push(HowToSection {
+                    name: (heading != self.default_section_name).then(|| heading.to_string()),
+                    steps: Vec::new(),
+                });
+                continue;
+            }
+
+            match part {
+                Part::Description => description.push(line),
+                Part::Steps => steps.push(line),
+                Part::Ingredients => {
+                    if let Some(name) = line.strip_prefix("### ") {
+                        recipe.ingredient_sections.push(IngredientSection {
+                            name: Some(name.trim().to_string()),
+                            ingredients: Vec::new(),
+                        });
+                        new_list = false;
+                    } else if let Some(ingredient) = line.strip_prefix("- ") {
+                        match recipe.ingredient_sections.last_mut() {
+                            Some(section) if !new_list || section.ingredients.is_empty() => {
+                                section.ingredients.push(ingredient.to_string())
+                            }
+                            _ => recipe.ingredient_sections.push(IngredientSection {
+                                name: None,
+                                ingredients: vec![ingredient.to_string()],
+                            }),
+                        }
+                        new_list = false;
+                    } else if line.trim().is_empty() {
+                        new_list = true;
+                    }
+                }
+            }
+        }
+        if let Some(section) = recipe.how_to_sections.last_mut() {
+            section.steps = paragraphs(&steps);
+        }
+
+        let description = description.join("\n");
+        let description = description.trim_matches('\n');
+        recipe.description = (!description.is_empty()).then(|| description.to_string());
+        Some((recipe, url))
+    }
+}
+
+/// Splits the lines into paragraphs at blank lines, the lines of a paragraph are kept
+fn paragraphs(lines: &[&str]) -> Vec<String> {
+    lines
+        .split(|line| line.trim().is_empty())
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| paragraph.join("\n"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Recipe {
+        Recipe {
+            name: Some("Pancakes".into()),
+            author: Some("Jane".into()),
+            description: Some("Fluffy.\n\nBest with berries.".into()),
+            ingredient_sections: vec![
+                IngredientSection {
+                    name: None,
+                    ingredients: vec!["2 eggs".into(), "250 ml milk".into()],
+                },
+                IngredientSection {
+                    name: None,
+                    ingredients: vec!["1 pinch salt".into()],
+                },
+                IngredientSection {
+                    name: Some("Topping".into()),
+                    ingredients: vec!["Maple syrup".into()],
+                },
+            ],
+            how_to_sections: vec![
+                HowToSection {
+                    name: None,
+                    steps: vec!["Whisk.".into(), "1. Heat a pan.\n2. Fry.".into()],
+                },
+                HowToSection {
+                    name: Some("Ingredients".into()),
+                    steps: vec!["Serve.".into()],
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn round_trip_default_names() {
+        let recipe = example();
+        let markdown = recipe
+            .to_markdown()
+            .with_url("https://example.org/recipe")
+            .convert();
+        assert_eq!(
+            MarkdownReader::new().parse_with_url(&markdown),
+            Some((recipe, Some("https://example.org/recipe".into())))
+        );
+    }
+
+    #[test]
+    fn round_trip_configured_names() {
+        let mut recipe = example();
+        recipe.name = None;
+        recipe.author = None;
+        recipe.description = None;
+        let markdown = recipe
+            .to_markdown()
+            .with_ingredient_section("Zutaten")
+            .with_default_name("Rezept")
+            .with_default_section("Zubereitung")
+            .convert();
+        let reader = MarkdownReader::new()
+            .with_ingredient_section("Zutaten")
+            .with_default_name("Rezept")
+            .with_default_section("Zubereitung");
+        assert_eq!(reader.parse(&markdown), Some(recipe));
+    }
+
+    #[test]
+    fn read_edited_markdown() {
+        let markdown = "---\r\ntags: dinner\r\nauthor: Jane\r\n---\r\n\r\n# Soup\r\n\r\n\
+                        ## Ingredients\r\n\r\n- Water\r\n\r\n## Steps\r\n\r\nBoil.\r\n";
+        let recipe = MarkdownReader::new().parse(markdown).unwrap();
+        assert_eq!(recipe.author.as_deref(), Some("Jane"));
+        assert_eq!(recipe.ingredients().collect::<Vec<_>>(), vec!["Water"]);
+        assert_eq!(recipe.how_to_sections[0].name.as_deref(), Some("Steps"));
+        assert_eq!(recipe.how_to_sections[0].steps, vec!["Boil."]);
+    }
+
+    #[test]
+    fn require_title() {
+        assert_eq!(
+            MarkdownReader::new().parse("## Ingredients\n\n- Water"),
+            None
+        );
+        assert_eq!(MarkdownReader::new().parse(""), None);
+    }
+}
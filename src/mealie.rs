@@ -0,0 +1,194 @@
+//! Exports recipes to the recipe JSON of Mealie.
+
+use crate::ingredient::parse_ingredient;
+use crate::json_ld::nutrition_to_json;
+use crate::timers::{format_duration, parse_time};
+use crate::{Ingredient, Recipe};
+
+use serde_json::{Map, Value, json};
+
+/// Converts the recipe to the recipe JSON of Mealie
+///
+/// It writes:
+///     - ingredients with their quantity, unit and food, ingredients without an amount
+///       only as a note
+///     - the names of sections as the title of their first ingredient or step
+///     - times as text, e.g. "1 hour 30 minutes"
+///     - tools with a slug, e.g. "Dutch oven" as "dutch-oven"
+pub(crate) fn to_mealie_json(recipe: &Recipe) -> Value {
+    let mut json = Map::new();
+    let name = recipe.name.as_deref().unwrap_or("Recipe");
+    json.insert("name".into(), name.into());
+    json.insert("slug".into(), slug(name).into());
+    json.insert(
+        "description".into(),
+        recipe.description.as_deref().unwrap_or_default().into(),
+    );
+    if let Some(recipe_yield) = &recipe.recipe_yield {
+        json.insert("recipeYield".into(), recipe_yield.as_str().into());
+    }
+    if let Some(servings) = recipe.servings() {
+        json.insert("recipeServings".into(), servings.into());
+    }
+    for (key, time) in [
+        ("prepTime", &recipe.prep_time),
+        ("performTime", &recipe.cook_time),
+        ("totalTime", &recipe.total_time),
+    ] {
+        if let Some(time) = time {
+            let time = parse_time(time).map_or_else(|| time.clone(), format_duration);
+            json.insert(key.into(), time.into());
+        }
+    }
+
+    let ingredients: Vec<Value> = recipe
+        .ingredient_sections
+        .iter()
+        .flat_map(|section| {
+            section
+                .ingredients
+                .iter()
+                .enumerate()
+                .map(|(i, ingredient)| {
+                    let title = section.name.as_deref().filter(|_| i == 0);
+                    ingredient_to_json(ingredient, title)
+                })
+        })
+        .collect();
+    json.insert("recipeIngredient".into(), ingredients.into());
+
+    let steps: Vec<Value> = recipe
+        .how_to_sections
+        .iter()
+        .flat_map(|section| {
+            section.steps.iter().enumerate().map(|(i, step)| {
+                let title = section.name.as_deref().filter(|_| i == 0);
+                json!({
+                    "title": title.unwrap_or_default(),
+                    "text": step,
+                    "ingredientReferences": [],
+                })
+            })
+        })
+        .collect();
+    json.insert("recipeInstructions".into(), steps.into());
+
+    let tools: Vec<Value> = recipe
+        .tools
+        .iter()
+        .map(|tool| json!({ "name": tool, "slug": slug(tool) }))
+        .collect();
+    json.insert("tools".into(), tools.into());
+    json.insert("recipeCategory".into(), json!([]));
+    json.insert("tags".into(), json!([]));
+
+    if let Some(Value::Object(mut nutrition)) =
+        recipe.nutrition.as_ref().and_then(nutrition_to_json)
+    {
+        nutrition.remove("@type");
+        json.insert("nutrition".into(), nutrition.into());
+    }
+
+    Value::Object(json)
+}
+
+fn ingredient_to_json(ingredient: &Ingredient, title: Option<&str>) -> Value {
+    let parsed = parse_ingredient(ingredient);
+    let (quantity, unit, food, note) = match parsed.amount {
+        Some(amount) => (
+            amount,
+            parsed
+                .unit
+                .map(|unit| json!({ "name": unit.symbol(amount) })),
+            Some(json!({ "name": parsed.name })),
+            "",
+        ),
+        None => (0.0, None, None, ingredient.as_str()),
+    };
+    json!({
+        "title": title,
+        "quantity": quantity,
+        "unit": unit,
+        "food": food,
+        "note": note,
+        "originalText": ingredient,
+        "display": ingredient,
+    })
+}
+
+/// Returns the slug of a name, e.g. "Mac & Cheese" -> "mac-cheese"
+fn slug(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HowToSection, IngredientSection, NutritionInformation};
+
+    #[test]
+    fn write_json() {
+        let recipe = Recipe {
+            name: Some("Mac & Cheese".into()),
+            recipe_yield: Some("4 servings".into()),
+            cook_time: Some("PT1H30M".into()),
+            ingredient_sections: vec![IngredientSection {
+                name: Some("Sauce".into()),
+                ingredients: vec!["2 cups milk".into(), "salt to taste".into()],
+            }],
+            how_to_sections: vec![HowToSection {
+                name: Some("Cooking".into()),
+                steps: vec!["Boil.".into(), "Stir.".into()],
+            }],
+            tools: vec!["Dutch oven".into()],
+            nutrition: Some(NutritionInformation {
+                calories: Some("540 kcal".into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            recipe.to_mealie_json(),
+            json!({
+                "name": "Mac & Cheese",
+                "slug": "mac-cheese",
+                "description": "",
+                "recipeYield": "4 servings",
+                "recipeServings": 4.0,
+                "performTime": "1 hour 30 minutes",
+                "recipeIngredient": [
+                    {
+                        "title": "Sauce",
+                        "quantity": 2.0,
+                        "unit": {"name": "cups"},
+                        "food": {"name": "milk"},
+                        "note": "",
+                        "originalText": "2 cups milk",
+                        "display": "2 cups milk"
+                    },
+                    {
+                        "title": null,
+                        "quantity": 0.0,
+                        "unit": null,
+                        "food": null,
+                        "note": "salt to taste",
+                        "originalText": "salt to taste",
+                        "display": "salt to taste"
+                    }
+                ],
+                "recipeInstructions": [
+                    {"title": "Cooking", "text": "Boil.", "ingredientReferences": []},
+                    {"title": "", "text": "Stir.", "ingredientReferences": []}
+                ],
+                "tools": [{"name": "Dutch oven", "slug": "dutch-oven"}],
+                "recipeCategory": [],
+                "tags": [],
+                "nutrition": {"calories": "540 kcal"}
+            })
+        );
+    }
+}
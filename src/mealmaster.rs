@@ -0,0 +1,494 @@
+//! Parses recipes from MealMaster (`.mmf`) text files.
+
+use crate::ingredient::parse_amount;
+use crate::{HowToSection, IngredientSection, Recipe};
+
+/// The unit codes of MealMaster with their singular and plural names
+///
+/// Codes without a name, like "ea" for each, are dropped.
+const UNITS: &[(&str, &str, &str)] = &[
+    ("x", "", ""),
+    ("ea", "", ""),
+    ("sm", "small", "small"),
+    ("md", "medium", "medium"),
+    ("lg", "large", "large"),
+    ("cn", "can", "cans"),
+    ("pk", "package", "packages"),
+    ("pn", "pinch", "pinches"),
+    ("dr", "drop", "drops"),
+    ("ds", "dash", "dashes"),
+    ("ct", "carton", "cartons"),
+    ("bn", "bunch", "bunches"),
+    ("sl", "slice", "slices"),
+    ("t", "teaspoon", "teaspoons"),
+    ("ts", "teaspoon", "teaspoons"),
+    ("T", "tablespoon", "tablespoons"),
+    ("tb", "tablespoon", "tablespoons"),
+    ("fl", "fluid ounce", "fluid ounces"),
+    ("c", "cup", "cups"),
+    ("pt", "pint", "pints"),
+    ("qt", "quart", "quarts"),
+    ("ga", "gallon", "gallons"),
+    ("oz", "ounce", "ounces"),
+    ("lb", "pound", "pounds"),
+    ("ml", "ml", "ml"),
+    ("cb", "cubic cm", "cubic cm"),
+    ("cl", "cl", "cl"),
+    ("dl", "dl", "dl"),
+    ("l", "liter", "liters"),
+    ("mg", "mg", "mg"),
+    ("cg", "cg", "cg"),
+    ("dg", "dg", "dg"),
+    ("g", "g", "g"),
+    ("kg", "kg", "kg"),
+];
+
+/// The column where the second column of a two-column ingredient list starts
+const SECOND_COLUMN: usize = 41;
+
+/// Parses all recipes of a MealMaster file
+///
+/// It deals with:
+///     - several recipes per file, each starting with a "MMMMM----- Recipe via Meal-Master"
+///       or "---------- Recipe via Meal-Master" line and ending with "MMMMM" or "-----"
+///     - the "Title:", "Categories:" and "Yield:" or "Servings:" headers
+///     - ingredients in fixed columns: the amount in columns 1-7, the unit code in columns
+///       9-10 and the name from column 12, in one or two columns
+///     - names continued on the next line, starting with "-"
+///     - headings like "MMMMM-----SAUCE-----", which start a new section
+///     - directions wrapped over several lines, every paragraph is a step
+///
+/// Text outside of recipes is ignored.
+///
+/// ## Example
+///
+/// ```
+/// let mmf = "\
+/// MMMMM----- Recipe via Meal-Master (tm) v8.05
+///
+///       Title: Pancakes
+///  Categories: Breakfast
+///       Yield: 4 servings
+///
+///       2 c  Flour
+///       2    Eggs
+///
+///   Mix everything and fry
+///   in a hot pan.
+///
+/// MMMMM";
+///
+/// let recipes = reget::parse_mealmaster(mmf);
+/// assert_eq!(recipes[0].name.as_deref(), Some("Pancakes"));
+/// assert_eq!(recipes[0].ingredient_sections[0].ingredients, vec!["2 cups Flour", "2 Eggs"]);
+/// assert_eq!(recipes[0].how_to_sections[0].steps, vec!["Mix everything and fry in a hot pan."]);
+/// ```
+pub fn parse_mealmaster(text: &str) -> Vec<Recipe> {
+    let mut recipes = Vec::new();
+    let mut current: Option<RecipeParser> = None;
+
+    for line in text.lines() {
+        let line = line.trim_end();
+        if is_recipe_start(line) {
+            recipes.extend(current.take().map(RecipeParser::finish));
+            current = Some(RecipeParser::default());
+            continue;
+        }
+        let Some(parser) = current.as_mut() else {
+            continue;
+        };
+        match separator(line) {
+            Some(None) => recipes.extend(current.take().map(RecipeParser::finish)),
+            Some(Some(name)) => parser.heading(name),
+            None => parser.line(line),
+        }
+    }
+
+    recipes.extend(current.map(RecipeParser::finish));
+    recipes
+}
+
+/// Where the parser is inside a recipe
+#[derive(Default, PartialEq)]
+enum Part {
+    #[default]
+    Header,
+    Ingredients,
+    Directions,
+}
+
+/// Collects the lines of a single recipe
+#[derive(Default)]
+struct RecipeParser {
+    recipe: Recipe,
+    part: Part,
+    /// The index of the last ingredient read in each column, to append continued names
+    last: [Option<usize>; 2],
+    paragraph: Vec<String>,
+}
+
+impl RecipeParser {
+    fn line(&mut self, line: &str) {
+        if line.trim().is_empty() {
+            self.end_paragraph();
+            return;
+        }
+
+        if self.part == Part::Header
+            && let Some((key, value)) = line.trim().split_once(':')
+        {
+            let value = Some(value.trim().to_string()).filter(|value| !value.is_empty());
+            match key.trim().to_lowercase().as_str() {
+                "title" => return self.recipe.name = value,
+                "yield" | "servings" => return self.recipe.recipe_yield = value,
+                "categories" => return,
+                _ => {}
+            }
+        }
+
+        if self.part != Part::Directions && self.ingredients(line) {
+            self.part = Part::Ingredients;
+            return;
+        }
+
+        self.part = Part::Directions;
+        self.paragraph.push(line.trim().to_string());
+    }
+
+    /// Reads a line of one or two ingredient columns, returns false if it is not one
+    fn ingredients(&mut self, line: &str) -> bool {
+        let chars: Vec<char> = line.chars().collect();
+        let (left, right) = if chars.len() > SECOND_COLUMN
+            && chars[SECOND_COLUMN - 2..SECOND_COLUMN]
+                .iter()
+                .all(|c| c.is_whitespace())
+            && parse_column(&chars[SECOND_COLUMN..]).is_some()
+        {
+            (&chars[..SECOND_COLUMN], Some(&chars[SECOND_COLUMN..]))
+        } else {
+            (&chars[..], None)
+        };
+
+        let Some(left) = parse_column(left) else {
+            return false;
+        };
+        self.add_ingredient(0, left);
+        if let Some(right) = right.and_then(parse_column) {
+            self.add_ingredient(1, right);
+        }
+        true
+    }
+
+    fn add_ingredient(&mut self, column: usize, ingredient: Column) {
+        if self.recipe.ingredient_sections.is_empty() {
+            self.recipe
+                .ingredient_sections
+                .push(IngredientSection::default());
+        }
+        let section = self
+            .recipe
+            .ingredient_sections
+            .last_mut()
+            .expect("a section was added");
+
+        match ingredient {
+            Column::Continued(text) => {
+                if let Some(last) = self.last[column].and_then(|i| section.ingredients.get_mut(i)) {
+                    last.push(' ');
+                    last.push_str(&text);
+                } else {
+                    section.ingredients.push(text);
+                    self.last[column] = Some(section.ingredients.len() - 1);
+                }
+            }
+            Column::Ingredient(text) => {
+                section.ingredients.push(text);
+                self.last[column] = Some(section.ingredients.len() - 1);
+            }
+        }
+    }
+
+    /// Starts a new ingredient section, or a new section of steps once the directions started
+    fn heading(&mut self, name: String) {
+        self.end_paragraph();
+        self.last = [None, None];
+        if self.part == Part::Directions {
+            self.recipe.how_to_sections.push(HowToSection {
+                name: Some(name),
+                steps: Vec::new(),
+            });
+        } else {
+            self.part = Part::Ingredients;
+            self.recipe.ingredient_sections.push(IngredientSection {
+                name: Some(name),
+                ingredients: Vec::new(),
+            });
+        }
+    }
+
+    fn end_paragraph(&mut self) {
+        if self.paragraph.is_empty() {
+            return;
+        }
+        let step = std::mem::take(&mut self.paragraph).join(" ");
+        match self.recipe.how_to_sections.last_mut() {
+            Some(section) => section.steps.push(step),
+            None => self.recipe.how_to_sections.push(HowToSection {
+                name: None,
+                steps: vec![step],
+            }),
+        }
+    }
+
+    fn finish(mut self) -> Recipe {
+        self.end_paragraph();
+        let mut recipe = self.recipe;
+        recipe
+            .ingredient_sections
+            .retain(|section| !section.ingredients.is_empty());
+        recipe
+            .how_to_sections
+            .retain(|section| !section.steps.is_empty());
+        recipe
+    }
+}
+
+/// An ingredient read from the columns of a line
+enum Column {
+    Ingredient(String),
+    /// The rest of the name of the ingredient above
+    Continued(String),
+}
+
+/// Parses the fixed columns of an ingredient: the amount, the unit code and the name
+///
+/// Returns None if the text does not follow the columns.
+fn parse_column(chars: &[char]) -> Option<Column> {
+    let field = |start: usize, end: usize| -> String {
+        chars
+            .get(start..end.min(chars.len()))
+            .unwrap_or_default()
+            .iter()
+            .collect()
+    };
+    let is_blank = |i: usize| chars.get(i).is_none_or(|c| c.is_whitespace());
+
+    let amount = field(0, 7);
+    let code = field(8, 10);
+    let name = field(11, chars.len());
+    let (amount, code, name) = (amount.trim(), code.trim(), name.trim());
+
+    if name.is_empty() && amount.is_empty()
+        || !is_blank(7)
+        || !is_blank(10)
+        || !amount
+            .chars()
+            .all(|c| c.is_ascii_digit() || " /.-".contains(c))
+    {
+        return None;
+    }
+
+    if amount.is_empty() && code.is_empty() {
+        // Without an amount or unit the name has to start right at its column
+        if is_blank(11) {
+            return None;
+        }
+        return Some(match name.strip_prefix('-') {
+            Some(rest) => Column::Continued(rest.trim().to_string()),
+            None => Column::Ingredient(name.to_string()),
+        });
+    }
+
+    let (_, singular, plural) = match code {
+        "" => &("", "", ""),
+        code => UNITS.iter().find(|(unit, ..)| *unit == code)?,
+    };
+    let unit = match parse_amount(amount) {
+        Some((amount, _)) if amount > 1.0 => plural,
+        _ => singular,
+    };
+    let text = [amount, unit, name]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(Column::Ingredient(text))
+}
+
+/// Returns true for the first line of a recipe, e.g. "MMMMM----- Recipe via Meal-Master (tm) v8.05"
+fn is_recipe_start(line: &str) -> bool {
+    (line.starts_with("MMMMM") || line.starts_with("-----"))
+        && line.to_lowercase().contains("meal-master")
+}
+
+/// Reads a line of "M"s and dashes
+///
+/// It deals with:
+///     - "MMMMM" or "-----", the end of a recipe, as Some(None)
+///     - "MMMMM-----SAUCE-----" or "-----SAUCE-----", a heading, as Some(Some("Sauce"))
+fn separator(line: &str) -> Option<Option<String>> {
+    if !line.starts_with("MMMMM") && !line.starts_with("-----") {
+        return None;
+    }
+    let name = line
+        .trim_start_matches('M')
+        .trim_matches(|c: char| c == '-' || c.is_whitespace());
+    if name.is_empty() {
+        return Some(None);
+    }
+    if name.chars().any(char::is_lowercase) {
+        return Some(Some(name.to_string()));
+    }
+    let mut chars = name.chars();
+    let first = chars.next().expect("the name is not empty");
+    Some(Some(first.to_string() + &chars.as_str().to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "\
+Some text before the first recipe
+
+MMMMM----- Recipe via Meal-Master (tm) v8.05
+
+      Title: Chocolate Chip Cookies
+ Categories: Cookies, Desserts
+      Yield: 36 cookies
+
+      1 c  Butter, softened
+    3/4 c  Sugar
+  2 1/4 c  Flour
+      1 ts Baking soda
+      2 c  Chocolate chips, coarsely
+           -chopped
+           Salt
+
+MMMMM--------------------------FROSTING-------------------------
+      1 c  Powdered sugar
+      1 T  Milk
+
+  Cream the butter and sugar. Stir
+  in the flour and baking soda.
+
+  Bake at 375F for 10 minutes.
+
+MMMMM
+
+---------- Recipe via Meal-Master (tm) v8.02
+
+      Title: Chili
+   Servings:  4
+
+      1 lb Ground beef                         2 cn Kidney beans
+      1 md Onion, chopped                      1 pn Cayenne
+           -finely                             1 ea Bay leaf
+
+  Brown the beef.
+
+-----------------------------TOPPING-----------------------------
+  Top with cheese.
+
+-----
+";
+
+    #[test]
+    fn parse_recipes() {
+        let recipes = parse_mealmaster(FILE);
+        assert_eq!(recipes.len(), 2);
+
+        let cookies = &recipes[0];
+        assert_eq!(cookies.name.as_deref(), Some("Chocolate Chip Cookies"));
+        assert_eq!(cookies.recipe_yield.as_deref(), Some("36 cookies"));
+        assert_eq!(
+            cookies.ingredient_sections,
+            vec![
+                IngredientSection {
+                    name: None,
+                    ingredients: vec![
+                        "1 cup Butter, softened".into(),
+                        "3/4 cup Sugar".into(),
+                        "2 1/4 cups Flour".into(),
+                        "1 teaspoon Baking soda".into(),
+                        "2 cups Chocolate chips, coarsely chopped".into(),
+                        "Salt".into(),
+                    ],
+                },
+                IngredientSection {
+                    name: Some("Frosting".into()),
+                    ingredients: vec!["1 cup Powdered sugar".into(), "1 tablespoon Milk".into(),],
+                },
+            ]
+        );
+        assert_eq!(
+            cookies.how_to_sections,
+            vec![HowToSection {
+                name: None,
+                steps: vec![
+                    "Cream the butter and sugar. Stir in the flour and baking soda.".into(),
+                    "Bake at 375F for 10 minutes.".into(),
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_two_columns() {
+        let chili = &parse_mealmaster(FILE)[1];
+        assert_eq!(chili.recipe_yield.as_deref(), Some("4"));
+        assert_eq!(
+            chili.ingredient_sections[0].ingredients,
+            vec![
+                "1 pound Ground beef",
+                "2 cans Kidney beans",
+                "1 medium Onion, chopped finely",
+                "1 pinch Cayenne",
+                "1 Bay leaf",
+            ]
+        );
+        assert_eq!(
+            chili.how_to_sections,
+            vec![
+                HowToSection {
+                    name: None,
+                    steps: vec!["Brown the beef.".into()],
+                },
+                HowToSection {
+                    name: Some("Topping".into()),
+                    steps: vec!["Top with cheese.".into()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_end() {
+        let recipes = parse_mealmaster(
+            "MMMMM----- Recipe via Meal-Master (tm) v8.05\n      Title: Tea\n      1    Tea bag",
+        );
+        assert_eq!(recipes.len(), 1);
+        assert_eq!(
+            recipes[0].ingredient_sections[0].ingredients,
+            vec!["1 Tea bag"]
+        );
+        assert!(parse_mealmaster("Title: Not a recipe").is_empty());
+    }
+
+    #[test]
+    fn ingredient_columns() {
+        let column = |line: &str| match parse_column(&line.chars().collect::<Vec<_>>()) {
+            Some(Column::Ingredient(text)) => Some(text),
+            Some(Column::Continued(text)) => Some(format!("-{text}")),
+            None => None,
+        };
+        assert_eq!(column("      2 x  Eggs").as_deref(), Some("2 Eggs"));
+        assert_eq!(
+            column("    1/2 ts Salt").as_deref(),
+            Some("1/2 teaspoon Salt")
+        );
+        assert_eq!(column("      1 zz Unknown unit"), None);
+        assert_eq!(column("  Mix the flour"), None);
+        assert_eq!(column("           -diced").as_deref(), Some("-diced"));
+    }
+}
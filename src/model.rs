@@ -1,5 +1,31 @@
 /// A recipe extracted from HTML using [parse_recipe](crate::parse_recipe).
+///
+/// ## Serialization
+///
+/// With the optional `serde` feature all model types implement `Serialize` and
+/// `Deserialize`. The layout is stable: fields keep their Rust names in snake case, missing
+/// values are `null` or `[]` and missing fields are filled with their defaults when
+/// deserializing.
+///
+/// ```json
+/// {
+///   "name": "Pancakes",
+///   "author": null,
+///   "description": null,
+///   "ingredient_sections": [{ "name": null, "ingredients": ["2 eggs"] }],
+///   "how_to_sections": [{ "name": null, "steps": ["Mix.", "Fry."] }],
+///   "suitable_for_diet": ["VegetarianDiet"],
+///   "recipe_yield": "4 servings",
+///   "nutrition": { "serving_size": null, "calories": "240 kcal", ... },
+///   "prep_time": "PT10M",
+///   "cook_time": null,
+///   "total_time": null,
+///   "tools": []
+/// }
+/// ```
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Recipe {
     /// The name of the recipe.
     pub name: Option<String>,
@@ -10,17 +36,72 @@ pub struct Recipe {
     pub author: Option<String>,
     /// The description of the recipe.
     pub description: Option<String>,
-    /// A list of ingredients used by the recipe.
-    pub ingredients: Vec<Ingredient>,
+    /// A list of [ingredient-sections](IngredientSection) for the recipe.
+    ///
+    /// If the recipe does not group its ingredients this will contain a single
+    /// section without a name.
+    pub ingredient_sections: Vec<IngredientSection>,
     /// A list of [how-to-sections](HowToSection) for the recipe.
     ///
     /// If the recipe does not use [how-to-sections](HowToSection) this will contain
     /// a single section without a name.
     pub how_to_sections: Vec<HowToSection>,
+    /// The diets the recipe is declared to be suitable for, without the schema.org
+    /// prefix (e.g. "VeganDiet").
+    ///
+    /// See [Recipe::diets] to infer diets from the ingredients.
+    pub suitable_for_diet: Vec<String>,
+    /// The quantity produced by the recipe, as given (e.g. "4 servings").
+    ///
+    /// See [Recipe::servings] for the number of servings.
+    pub recipe_yield: Option<String>,
+    /// The nutrition information of the recipe, if available.
+    pub nutrition: Option<NutritionInformation>,
+    /// The time to prepare the recipe, as an ISO 8601 duration (e.g. "PT15M").
+    pub prep_time: Option<String>,
+    /// The time to cook the recipe, as an ISO 8601 duration.
+    pub cook_time: Option<String>,
+    /// The total time of the recipe, as an ISO 8601 duration (e.g. "PT1H30M").
+    ///
+    /// See [Recipe::total_duration] for the parsed duration.
+    pub total_time: Option<String>,
+    /// The tools declared by the recipe (e.g. "Dutch oven").
+    ///
+    /// See [Recipe::equipment] to include the equipment mentioned in the steps.
+    pub tools: Vec<String>,
+}
+
+/// Nutritional information about a recipe
+///
+/// All values are kept as given, including their unit (e.g. "240 kcal" or "12 g").
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct NutritionInformation {
+    /// The size of a single serving, e.g. "1 slice".
+    pub serving_size: Option<String>,
+    /// The number of calories, e.g. "240 kcal".
+    pub calories: Option<String>,
+    /// The amount of fat, e.g. "12 g".
+    pub fat_content: Option<String>,
+    /// The amount of saturated fat.
+    pub saturated_fat_content: Option<String>,
+    /// The amount of carbohydrates.
+    pub carbohydrate_content: Option<String>,
+    /// The amount of sugar.
+    pub sugar_content: Option<String>,
+    /// The amount of fiber.
+    pub fiber_content: Option<String>,
+    /// The amount of protein.
+    pub protein_content: Option<String>,
+    /// The amount of sodium, e.g. "300 mg".
+    pub sodium_content: Option<String>,
 }
 
 /// A collection of [how-to-steps](HowToStep) with an optional name
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct HowToSection {
     /// The name of the section, if available.
     pub name: Option<String>,
@@ -28,6 +109,19 @@ pub struct HowToSection {
     pub steps: Vec<HowToStep>,
 }
 
+/// A group of [ingredients](Ingredient) with an optional name
+///
+/// Recipes often group their ingredients with headings like "For the dough:".
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct IngredientSection {
+    /// The name of the group, if available.
+    pub name: Option<String>,
+    /// A list of ingredients in the group.
+    pub ingredients: Vec<Ingredient>,
+}
+
 /// A single ingredient used in a recipe
 pub type Ingredient = String;
 
@@ -35,10 +129,528 @@ pub type Ingredient = String;
 pub type HowToStep = String;
 
 impl Recipe {
+    /// Calls the function for every text field of the recipe
+    pub(crate) fn for_each_text_mut(&mut self, mut f: impl FnMut(&mut String)) {
+        let Recipe {
+            name,
+            author,
+            description,
+            ingredient_sections,
+            how_to_sections,
+            suitable_for_diet,
+            recipe_yield,
+            nutrition,
+            prep_time,
+            cook_time,
+            total_time,
+            tools,
+        } = self;
+
+        let sections = ingredient_sections
+            .iter_mut()
+            .flat_map(|s| s.name.iter_mut().chain(s.ingredients.iter_mut()))
+            .chain(
+                how_to_sections
+                    .iter_mut()
+                    .flat_map(|s| s.name.iter_mut().chain(s.steps.iter_mut())),
+            );
+        let nutrition = nutrition.iter_mut().flat_map(|n| {
+            [
+                &mut n.serving_size,
+                &mut n.calories,
+                &mut n.fat_content,
+                &mut n.saturated_fat_content,
+                &mut n.carbohydrate_content,
+                &mut n.sugar_content,
+                &mut n.fiber_content,
+                &mut n.protein_content,
+                &mut n.sodium_content,
+            ]
+            .into_iter()
+            .flatten()
+        });
+
+        [
+            name,
+            author,
+            description,
+            recipe_yield,
+            prep_time,
+            cook_time,
+            total_time,
+        ]
+        .into_iter()
+        .flatten()
+        .chain(sections)
+        .chain(suitable_for_diet.iter_mut())
+        .chain(nutrition)
+        .chain(tools.iter_mut())
+        .for_each(&mut f);
+    }
+
+    /// Returns all ingredients of the recipe, regardless of their section
+    pub fn ingredients(&self) -> impl Iterator<Item = &Ingredient> {
+        self.ingredient_sections
+            .iter()
+            .flat_map(|section| section.ingredients.iter())
+    }
+
+    /// Returns the number of servings, taken from the first number in the
+    /// [yield](Recipe::recipe_yield)
+    ///
+    /// It deals with:
+    ///     - "4", "4 servings", "Serves 4"
+    ///     - "4-6 servings" (the lower bound is used)
+    pub fn servings(&self) -> Option<f64> {
+        let text = self.recipe_yield.as_deref()?;
+        let start = text.find(|c: char| c.is_ascii_digit())?;
+        let (servings, _) = crate::ingredient::parse_amount(&text[start..])?;
+        (servings > 0.0).then_some(servings)
+    }
+
+    /// Returns the [total time](Recipe::total_time) of the recipe, or the sum of the
+    /// prep and cook time if no total time is given
+    ///
+    /// Returns `None` if the times are missing, not valid ISO 8601 durations or too long.
+    pub fn total_duration(&self) -> Option<std::time::Duration> {
+        let parse =
+            |time: &Option<String>| time.as_deref().and_then(crate::timers::parse_iso_duration);
+        parse(&self.total_time).or_else(|| match (parse(&self.prep_time), parse(&self.cook_time)) {
+            (None, None) => None,
+            (prep, cook) => prep
+                .unwrap_or_default()
+                .checked_add(cook.unwrap_or_default()),
+        })
+    }
+
+    /// Sums the [durations](crate::Step::durations) mentioned in all steps, returning the
+    /// lower and upper bound
+    ///
+    /// Useful as a sanity check against the [total duration](Recipe::total_duration), the
+    /// steps usually leave out the time to prepare the ingredients.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use reget::{HowToSection, Recipe};
+    /// use std::time::Duration;
+    ///
+    /// let recipe = Recipe {
+    ///     how_to_sections: vec![HowToSection {
+    ///         name: None,
+    ///         steps: vec![
+    ///             "Simmer for 10-15 minutes.".into(),
+    ///             "Let rest 1 hour.".into(),
+    ///         ],
+    ///     }],
+    ///     total_time: Some("PT1H30M".into()),
+    ///     ..Default::default()
+    /// };
+    /// let (min, max) = recipe.steps_duration();
+    /// assert_eq!(min, Duration::from_secs(70 * 60));
+    /// assert_eq!(max, Duration::from_secs(75 * 60));
+    /// assert!(max <= recipe.total_duration().unwrap());
+    /// ```
+    pub fn steps_duration(&self) -> (std::time::Duration, std::time::Duration) {
+        self.steps().flat_map(|step| step.durations()).fold(
+            Default::default(),
+            |(min, max), duration| {
+                (
+                    min.saturating_add(duration.min),
+                    max.saturating_add(duration.max),
+                )
+            },
+        )
+    }
+
+    /// Returns all steps of the recipe together with their position
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use reget::{HowToSection, IngredientSection, Recipe};
+    ///
+    /// let recipe = Recipe {
+    ///     ingredient_sections: vec![IngredientSection {
+    ///         name: None,
+    ///         ingredients: vec!["2 cups flour".into(), "3 eggs".into()],
+    ///     }],
+    ///     how_to_sections: vec![HowToSection {
+    ///         name: None,
+    ///         steps: vec!["Beat the eggs.".into()],
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    /// for step in recipe.steps() {
+    ///     let refs = step.ingredient_refs();
+    ///     assert_eq!(refs[0].ingredient, "3 eggs");
+    /// }
+    /// ```
+    pub fn steps(&self) -> impl Iterator<Item = crate::Step<'_>> {
+        self.how_to_sections
+            .iter()
+            .enumerate()
+            .flat_map(move |(section, s)| {
+                (0..s.steps.len()).filter_map(move |index| crate::Step::new(self, section, index))
+            })
+    }
+
+    /// Returns the ingredients mentioned in a step, see [Step::ingredient_refs](crate::Step::ingredient_refs)
+    ///
+    /// Returns an empty list if there is no step at the given position.
+    pub fn ingredients_for_step(
+        &self,
+        section: usize,
+        index: usize,
+    ) -> Vec<crate::IngredientRef<'_>> {
+        crate::Step::new(self, section, index)
+            .map(|step| step.ingredient_refs())
+            .unwrap_or_default()
+    }
+
+    /// Returns the [allergens](crate::Allergen) found in the ingredients, together with
+    /// the ingredient that triggered each match
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use reget::{Allergen, IngredientSection, Recipe};
+    ///
+    /// let recipe = Recipe {
+    ///     ingredient_sections: vec![IngredientSection {
+    ///         name: None,
+    ///         ingredients: vec!["2 cups flour".into(), "2 tbsp peanut butter".into()],
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    /// let allergens: Vec<Allergen> = recipe.allergens().iter().map(|m| m.allergen).collect();
+    /// assert_eq!(allergens, vec![Allergen::Gluten, Allergen::Peanuts]);
+    /// ```
+    pub fn allergens(&self) -> Vec<crate::AllergenMatch<'_>> {
+        crate::allergens::find_allergens(self)
+    }
+
+    /// Classifies the recipe for every [diet](crate::Diet) based on its ingredients and the
+    /// diets declared in [suitable_for_diet](Recipe::suitable_for_diet)
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use reget::{Diet, IngredientSection, Recipe};
+    ///
+    /// let recipe = Recipe {
+    ///     ingredient_sections: vec![IngredientSection {
+    ///         name: None,
+    ///         ingredients: vec!["200 g spaghetti".into(), "100 g bacon".into()],
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    /// let vegetarian = recipe
+    ///     .diets()
+    ///     .into_iter()
+    ///     .find(|d| d.diet == Diet::Vegetarian)
+    ///     .unwrap();
+    /// assert!(!vegetarian.suitable);
+    /// assert_eq!(vegetarian.offending, vec!["100 g bacon"]);
+    /// ```
+    pub fn diets(&self) -> Vec<crate::DietAssessment<'_>> {
+        crate::diet::assess_diets(self)
+    }
+
+    /// Returns the declared [tools](Recipe::tools) followed by the equipment mentioned in the
+    /// steps, recognized using the [built-in vocabulary](crate::EquipmentVocabulary::builtin)
+    ///
+    /// Duplicates are removed, ignoring case.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use reget::{HowToSection, Recipe};
+    ///
+    /// let recipe = Recipe {
+    ///     how_to_sections: vec![HowToSection {
+    ///         name: None,
+    ///         steps: vec!["Pulse in a food processor, then bake on a sheet pan.".into()],
+    ///     }],
+    ///     tools: vec!["Sheet pan".into()],
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(recipe.equipment(), vec!["Sheet pan", "Food processor"]);
+    /// ```
+    pub fn equipment(&self) -> Vec<String> {
+        let mut result: Vec<String> = Vec::new();
+        let found = crate::EquipmentVocabulary::builtin().find_in_recipe(self);
+        let names = self
+            .tools
+            .iter()
+            .map(String::as_str)
+            .chain(found.iter().map(|equipment| equipment.name.as_str()));
+        for name in names {
+            if !result.iter().any(|other| other.eq_ignore_ascii_case(name)) {
+                result.push(name.to_string());
+            }
+        }
+        result
+    }
+
+    #[cfg(feature = "nutrition")]
+    /// Estimates calories and macros from the ingredients using the bundled nutrient table,
+    /// see [NutritionEstimate](crate::NutritionEstimate)
+    ///
+    /// This is meant as a fallback if the recipe does not provide
+    /// [nutrition information](Recipe::nutrition).
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use reget::{IngredientSection, Recipe};
+    ///
+    /// let recipe = Recipe {
+    ///     ingredient_sections: vec![IngredientSection {
+    ///         name: None,
+    ///         ingredients: vec!["200 g flour".into(), "2 eggs".into(), "a dash of love".into()],
+    ///     }],
+    ///     recipe_yield: Some("2 servings".into()),
+    ///     ..Default::default()
+    /// };
+    /// let estimate = recipe.estimate_nutrition();
+    /// assert_eq!(estimate.total.calories.as_deref(), Some("871 kcal"));
+    /// let per_serving = estimate.per_serving.unwrap();
+    /// assert_eq!(per_serving.calories.as_deref(), Some("436 kcal"));
+    /// assert_eq!(estimate.unmatched, vec!["a dash of love"]);
+    /// ```
+    pub fn estimate_nutrition(&self) -> crate::NutritionEstimate<'_> {
+        crate::nutrition::estimate_nutrition(self)
+    }
+
     #[cfg(feature = "markdown")]
     /// Constructs a [MarkdownBuilder](crate::MarkdownBuilder) to convert this
     /// recipe to markdown format
-    pub fn to_markdown(&self) -> crate::MarkdownBuilder {
+    pub fn to_markdown(&self) -> crate::MarkdownBuilder<'_> {
         crate::MarkdownBuilder::from(self)
     }
+
+    #[cfg(feature = "markdown")]
+    /// Constructs a [RecipeMdBuilder](crate::RecipeMdBuilder) to convert this
+    /// recipe to RecipeMD format
+    pub fn to_recipe_md(&self) -> crate::RecipeMdBuilder<'_> {
+        crate::RecipeMdBuilder::from(self)
+    }
+
+    #[cfg(feature = "cooklang")]
+    /// Constructs a [CooklangBuilder](crate::CooklangBuilder) to convert this
+    /// recipe to Cooklang format
+    pub fn to_cooklang(&self) -> crate::CooklangBuilder<'_> {
+        crate::CooklangBuilder::from(self)
+    }
+
+    #[cfg(feature = "paprika")]
+    /// Converts the recipe to the JSON Paprika stores for a recipe, see
+    /// [to_paprika_archive](crate::to_paprika_archive) to write a `.paprikarecipes` file
+    ///
+    /// Ingredients and steps are written one per line, with headings like "Dough:" for
+    /// named sections, and times as text. The uid is derived from the contents.
+    pub fn to_paprika_json(&self) -> serde_json::Value {
+        crate::paprika::to_paprika_json(self)
+    }
+
+    #[cfg(feature = "mealie")]
+    /// Converts the recipe to the recipe JSON of Mealie
+    ///
+    /// Ingredients with an amount are split into quantity, unit and food, the others are
+    /// written as a note. Section names become the title of their first ingredient or step.
+    pub fn to_mealie_json(&self) -> serde_json::Value {
+        crate::mealie::to_mealie_json(self)
+    }
+
+    #[cfg(feature = "tandoor")]
+    /// Converts the recipe to the `recipe.json` Tandoor imports
+    ///
+    /// All ingredients are attached to the first step, with a header for every named
+    /// section. Prep and cook time become the working and waiting time in minutes.
+    pub fn to_tandoor_json(&self) -> serde_json::Value {
+        crate::tandoor::to_tandoor_json(self)
+    }
+
+    #[cfg(feature = "nextcloud")]
+    /// Converts the recipe to the `recipe.json` of Nextcloud Cookbook
+    ///
+    /// It is the [JSON-LD](Recipe::to_json_ld) with steps and tools as plain texts and the
+    /// yield as the number of servings.
+    pub fn to_nextcloud_json(&self) -> serde_json::Value {
+        crate::nextcloud::to_nextcloud_json(self)
+    }
+
+    #[cfg(feature = "nextcloud")]
+    /// Writes the [Nextcloud Cookbook JSON](Recipe::to_nextcloud_json) to
+    /// `<dir>/<name>/recipe.json`, the folder layout of the Cookbook
+    ///
+    /// Existing folders are never overwritten: if `<dir>/<name>` exists, the recipe is
+    /// written to `<dir>/<name> (2)` and so on. Returns the path of the recipe folder.
+    pub fn write_nextcloud_folder(
+        &self,
+        dir: &std::path::Path,
+    ) -> std::io::Result<std::path::PathBuf> {
+        crate::nextcloud::write_nextcloud_folder(self, dir)
+    }
+
+    /// Converts the recipe to a schema.org Recipe object in JSON-LD
+    ///
+    /// Authors are written as a Person, steps as HowToStep and named sections as
+    /// HowToSection. Times that are not ISO 8601 durations are converted if possible (e.g.
+    /// "30 minutes" to "PT30M").
+    ///
+    /// Recipes returned by [parse_recipe](crate::parse_recipe) are read back to an equal
+    /// recipe, as long as their times are ISO 8601 durations. schema.org has no ingredient
+    /// sections, so they are written as headings like "Dough:" in the ingredient list. Other
+    /// recipes may therefore not be read back equally:
+    ///     - an ingredient section without a name that follows a named one is merged into it
+    ///     - ingredients that look like headings, e.g. "SALT" before another ingredient, start
+    ///       a section
+    ///     - ingredient sections without ingredients are left out
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use reget::{parse_recipe, Recipe};
+    ///
+    /// let recipe = Recipe {
+    ///     name: Some("Pancakes".into()),
+    ///     author: Some("Jane".into()),
+    ///     ..Default::default()
+    /// };
+    /// let json = recipe.to_json_ld();
+    /// assert_eq!(json["author"]["@type"], "Person");
+    ///
+    /// let html = recipe.to_json_ld_script();
+    /// assert_eq!(parse_recipe(&html), Some(recipe));
+    /// ```
+    pub fn to_json_ld(&self) -> serde_json::Value {
+        crate::json_ld::to_json_ld(self)
+    }
+
+    /// Converts the recipe to JSON-LD like [to_json_ld](Recipe::to_json_ld), wrapped in a
+    /// `<script type="application/ld+json">` tag
+    pub fn to_json_ld_script(&self) -> String {
+        crate::json_ld::to_script(&self.to_json_ld())
+    }
+
+    /// Returns a copy of this recipe with all ingredient quantities and the temperatures,
+    /// lengths and pan sizes in its steps converted to the given [unit system](crate::UnitSystem).
+    ///
+    /// Ingredients without a recognized quantity are left as they are. To keep the original
+    /// quantities in parentheses use a [UnitConverter](crate::UnitConverter).
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use reget::{IngredientSection, Recipe, UnitSystem};
+    ///
+    /// let recipe = Recipe {
+    ///     ingredient_sections: vec![IngredientSection {
+    ///         name: None,
+    ///         ingredients: vec!["2 cups flour".into(), "1 cup milk".into()],
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    /// let metric = recipe.convert_units(UnitSystem::Metric);
+    /// assert!(metric.ingredients().eq(["251 g flour", "237 ml milk"].iter()));
+    /// ```
+    pub fn convert_units(&self, system: crate::UnitSystem) -> Recipe {
+        crate::UnitConverter::new(system).convert(self)
+    }
+}
+
+#[cfg(test)]
+impl Recipe {
+    /// Constructs a recipe with one unnamed section of ingredients and one of steps, for
+    /// tests
+    pub(crate) fn from_lines(ingredients: &[&str], steps: &[&str]) -> Recipe {
+        Recipe {
+            ingredient_sections: vec![IngredientSection {
+                name: None,
+                ingredients: ingredients.iter().map(|i| i.to_string()).collect(),
+            }],
+            how_to_sections: vec![HowToSection {
+                name: None,
+                steps: steps.iter().map(|s| s.to_string()).collect(),
+            }],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn recipe() -> Recipe {
+        Recipe {
+            name: Some("Pancakes".into()),
+            ingredient_sections: vec![IngredientSection {
+                name: None,
+                ingredients: vec!["2 eggs".into()],
+            }],
+            how_to_sections: vec![HowToSection {
+                name: Some("Frying".into()),
+                steps: vec!["Fry.".into()],
+            }],
+            nutrition: Some(NutritionInformation {
+                calories: Some("240 kcal".into()),
+                ..Default::default()
+            }),
+            prep_time: Some("PT10M".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn serialize_layout() {
+        assert_eq!(
+            serde_json::to_value(recipe()).unwrap(),
+            json!({
+                "name": "Pancakes",
+                "author": null,
+                "description": null,
+                "ingredient_sections": [{"name": null, "ingredients": ["2 eggs"]}],
+                "how_to_sections": [{"name": "Frying", "steps": ["Fry."]}],
+                "suitable_for_diet": [],
+                "recipe_yield": null,
+                "nutrition": {
+                    "serving_size": null,
+                    "calories": "240 kcal",
+                    "fat_content": null,
+                    "saturated_fat_content": null,
+                    "carbohydrate_content": null,
+                    "sugar_content": null,
+                    "fiber_content": null,
+                    "protein_content": null,
+                    "sodium_content": null
+                },
+                "prep_time": "PT10M",
+                "cook_time": null,
+                "total_time": null,
+                "tools": []
+            })
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let json = serde_json::to_string(&recipe()).unwrap();
+        assert_eq!(serde_json::from_str::<Recipe>(&json).unwrap(), recipe());
+    }
+
+    #[test]
+    fn deserialize_missing_fields() {
+        let recipe: Recipe = serde_json::from_value(json!({"name": "Soup"})).unwrap();
+        assert_eq!(
+            recipe,
+            Recipe {
+                name: Some("Soup".into()),
+                ..Default::default()
+            }
+        );
+    }
 }
@@ -0,0 +1,154 @@
+//! Exports recipes for Nextcloud Cookbook.
+
+use crate::Recipe;
+use crate::constants::LdFields;
+use crate::json_ld::to_json_ld;
+use crate::text::file_name;
+
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+
+/// The file Nextcloud Cookbook reads from the folder of every recipe
+const RECIPE_FILE: &str = "recipe.json";
+
+/// Converts the recipe to the `recipe.json` of Nextcloud Cookbook
+///
+/// It is the [schema.org JSON-LD](crate::Recipe::to_json_ld), with the differences the
+/// Cookbook expects:
+///     - the steps as texts, with headings like "Frying:" for named sections
+///     - the tools as texts
+///     - the yield as the number of servings, omitted if it is unknown
+///     - a name, as the Cookbook requires one
+pub(crate) fn to_nextcloud_json(recipe: &Recipe) -> Value {
+    let mut json = to_json_ld(recipe);
+    let obj = json.as_object_mut().expect("JSON-LD is an object");
+
+    obj.insert(
+        LdFields::NAME.into(),
+        recipe.name.as_deref().unwrap_or("Recipe").into(),
+    );
+    match recipe.servings() {
+        Some(servings) => obj.insert(
+            LdFields::RECIPE_YIELD.into(),
+            json!(servings.round() as u64),
+        ),
+        None => obj.remove(LdFields::RECIPE_YIELD),
+    };
+    if !recipe.tools.is_empty() {
+        obj.insert(LdFields::TOOL.into(), json!(recipe.tools));
+    }
+    if !recipe.how_to_sections.is_empty() {
+        let steps: Vec<String> = recipe
+            .how_to_sections
+            .iter()
+            .flat_map(|section| {
+                let heading = section.name.as_ref().map(|name| format!("{name}:"));
+                heading.into_iter().chain(section.steps.iter().cloned())
+            })
+            .collect();
+        obj.insert(LdFields::RECIPE_INSTRUCTIONS.into(), json!(steps));
+    }
+
+    json
+}
+
+/// Writes the recipe into a folder named after it inside the directory, the layout of
+/// Nextcloud Cookbook
+///
+/// Existing folders are left alone, a number is added to the name instead, e.g.
+/// "Pancakes (2)". Returns the path of the created folder.
+pub(crate) fn write_nextcloud_folder(recipe: &Recipe, dir: &Path) -> std::io::Result<PathBuf> {
+    let name = file_name(recipe.name.as_deref().unwrap_or_default());
+    std::fs::create_dir_all(dir)?;
+    let mut folder = dir.join(&name);
+    for i in 2.. {
+        match std::fs::create_dir(&folder) {
+            Ok(()) => break,
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                folder = dir.join(format!("{name} ({i})"));
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    let json = serde_json::to_string_pretty(&to_nextcloud_json(recipe))?;
+    std::fs::write(folder.join(RECIPE_FILE), json)?;
+    Ok(folder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HowToSection, IngredientSection};
+
+    fn recipe() -> Recipe {
+        Recipe {
+            name: Some("Pancakes".into()),
+            recipe_yield: Some("4 servings".into()),
+            ingredient_sections: vec![IngredientSection {
+                name: None,
+                ingredients: vec!["2 eggs".into()],
+            }],
+            how_to_sections: vec![
+                HowToSection {
+                    name: None,
+                    steps: vec!["Mix.".into()],
+                },
+                HowToSection {
+                    name: Some("Frying".into()),
+                    steps: vec!["Fry.".into()],
+                },
+            ],
+            tools: vec!["Pan".into()],
+            cook_time: Some("20 minutes".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn write_json() {
+        assert_eq!(
+            recipe().to_nextcloud_json(),
+            json!({
+                "@context": "https://schema.org",
+                "@type": "Recipe",
+                "name": "Pancakes",
+                "cookTime": "PT20M",
+                "recipeYield": 4,
+                "tool": ["Pan"],
+                "recipeIngredient": ["2 eggs"],
+                "recipeInstructions": ["Mix.", "Frying:", "Fry."]
+            })
+        );
+        let json = Recipe::default().to_nextcloud_json();
+        assert_eq!(json["name"], "Recipe");
+        assert!(json.get("recipeYield").is_none());
+    }
+
+    #[test]
+    fn write_folder() {
+        let dir = std::env::temp_dir().join(format!("reget-nextcloud-{}", std::process::id()));
+        let folder = recipe().write_nextcloud_folder(&dir).unwrap();
+        assert_eq!(folder, dir.join("Pancakes"));
+        let written: Value =
+            serde_json::from_str(&std::fs::read_to_string(folder.join(RECIPE_FILE)).unwrap())
+                .unwrap();
+        assert_eq!(written, recipe().to_nextcloud_json());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_unique_folders() {
+        let dir =
+            std::env::temp_dir().join(format!("reget-nextcloud-unique-{}", std::process::id()));
+        let first = recipe().write_nextcloud_folder(&dir).unwrap();
+        let second = recipe().write_nextcloud_folder(&dir).unwrap();
+        let unnamed = Recipe::default().write_nextcloud_folder(&dir).unwrap();
+        let unnamed_again = Recipe::default().write_nextcloud_folder(&dir).unwrap();
+        assert_eq!(first, dir.join("Pancakes"));
+        assert_eq!(second, dir.join("Pancakes (2)"));
+        assert_eq!(unnamed, dir.join("Recipe"));
+        assert_eq!(unnamed_again, dir.join("Recipe (2)"));
+        assert!(first.join(RECIPE_FILE).exists() && second.join(RECIPE_FILE).exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
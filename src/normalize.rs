@@ -0,0 +1,358 @@
+//! Normalizes unicode, whitespace and quotes in the text fields of a recipe.
+
+use crate::Recipe;
+
+use unicode_normalization::UnicodeNormalization;
+
+/// The unicode normalization form applied by a [TextNormalizer]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnicodeForm {
+    /// Keeps the text as it is.
+    None,
+    /// Canonical composition, e.g. "e" followed by a combining accent becomes "é".
+    #[default]
+    Nfc,
+    /// Compatibility composition, which also replaces ligatures, full-width characters and
+    /// vulgar fractions ("½" becomes "1/2").
+    Nfkc,
+}
+
+/// How a [TextNormalizer] treats quotation marks and apostrophes
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuoteStyle {
+    /// Keeps the quotes as they are.
+    #[default]
+    Keep,
+    /// Replaces typographic quotes with straight ones, e.g. "’" with "'".
+    Straight,
+    /// Replaces straight quotes with typographic ones, e.g. "'" with "’".
+    Curly,
+}
+
+/// Normalizes the text fields of a [recipe](Recipe)
+///
+/// By default text is composed to [NFC](UnicodeForm::Nfc), mojibake like "Ã©" is repaired,
+/// invisible characters are removed and non-breaking spaces are replaced with regular ones.
+/// Quotes are kept as they are.
+///
+/// ## Example
+///
+/// ```
+/// use reget::{QuoteStyle, TextNormalizer, UnicodeForm};
+///
+/// let normalizer = TextNormalizer::new()
+///     .with_unicode_form(UnicodeForm::Nfkc)
+///     .with_quote_style(QuoteStyle::Straight);
+/// assert_eq!(normalizer.normalize("Crème brûlÃ©e"), "Crème brûlée");
+/// assert_eq!(normalizer.normalize("1½ cups"), "1 1/2 cups");
+/// assert_eq!(normalizer.normalize("Don’t\u{a0}stir\u{200b}"), "Don't stir");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextNormalizer {
+    /// The unicode normalization form
+    unicode_form: UnicodeForm,
+    /// Whether UTF-8 text that was decoded as Windows-1252 is repaired
+    repair_mojibake: bool,
+    /// Whether zero-width characters and soft hyphens are removed
+    remove_invisible: bool,
+    /// How quotes are treated
+    quote_style: QuoteStyle,
+}
+
+impl Default for TextNormalizer {
+    fn default() -> Self {
+        TextNormalizer {
+            unicode_form: UnicodeForm::Nfc,
+            repair_mojibake: true,
+            remove_invisible: true,
+            quote_style: QuoteStyle::Keep,
+        }
+    }
+}
+
+impl TextNormalizer {
+    /// Constructs a normalizer with the default settings
+    pub fn new() -> Self {
+        TextNormalizer::default()
+    }
+
+    /// Sets the [unicode normalization form](UnicodeForm), defaults to NFC
+    pub fn with_unicode_form(mut self, form: UnicodeForm) -> Self {
+        self.unicode_form = form;
+        self
+    }
+
+    /// Sets whether mojibake like "Ã©" for "é" is repaired, defaults to true
+    pub fn with_mojibake_repair(mut self, repair: bool) -> Self {
+        self.repair_mojibake = repair;
+        self
+    }
+
+    /// Sets whether zero-width characters and soft hyphens are removed and special spaces
+    /// are replaced with regular ones, defaults to true
+    pub fn with_invisible_removal(mut self, remove: bool) -> Self {
+        self.remove_invisible = remove;
+        self
+    }
+
+    /// Sets the [quote style](QuoteStyle), defaults to keeping the quotes
+    pub fn with_quote_style(mut self, style: QuoteStyle) -> Self {
+        self.quote_style = style;
+        self
+    }
+
+    /// Normalizes a single text
+    pub fn normalize(&self, text: &str) -> String {
+        let mut result = text.to_string();
+        if self.repair_mojibake {
+            result = repair_mojibake(&result);
+        }
+        if self.remove_invisible {
+            result = remove_invisible(&result);
+        }
+        result = match self.unicode_form {
+            UnicodeForm::None => result,
+            UnicodeForm::Nfc => result.nfc().collect(),
+            UnicodeForm::Nfkc => nfkc(&result),
+        };
+        match self.quote_style {
+            QuoteStyle::Keep => result,
+            QuoteStyle::Straight => straight_quotes(&result),
+            QuoteStyle::Curly => curly_quotes(&result),
+        }
+    }
+
+    /// Normalizes every text field of the recipe
+    pub fn normalize_recipe(&self, recipe: &mut Recipe) {
+        recipe.for_each_text_mut(|text| *text = self.normalize(text));
+    }
+}
+
+/// Repairs UTF-8 text that was decoded as Windows-1252, e.g. "Ã©" -> "é"
+///
+/// Only sequences that form valid UTF-8 are replaced, so correct text is left unchanged.
+fn repair_mojibake(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let length = match windows_1252_byte(chars[i]) {
+            Some(0xC2..=0xDF) => 2,
+            Some(0xE0..=0xEF) => 3,
+            Some(0xF0..=0xF4) => 4,
+            _ => 1,
+        };
+        let bytes: Option<Vec<u8>> = chars
+            .get(i..i + length)
+            .filter(|_| length > 1)
+            .and_then(|sequence| sequence.iter().map(|c| windows_1252_byte(*c)).collect());
+        match bytes.and_then(|bytes| String::from_utf8(bytes).ok()) {
+            Some(repaired) => {
+                result.push_str(&repaired);
+                i += length;
+            }
+            None => {
+                result.push(chars[i]);
+                i += 1;
+            }
+        }
+    }
+
+    result
+}
+
+/// Returns the Windows-1252 byte of a character, if it has one
+fn windows_1252_byte(c: char) -> Option<u8> {
+    let byte = match c {
+        '€' => 0x80,
+        '‚' => 0x82,
+        'ƒ' => 0x83,
+        '„' => 0x84,
+        '…' => 0x85,
+        '†' => 0x86,
+        '‡' => 0x87,
+        'ˆ' => 0x88,
+        '‰' => 0x89,
+        'Š' => 0x8A,
+        '‹' => 0x8B,
+        'Œ' => 0x8C,
+        'Ž' => 0x8E,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '˜' => 0x98,
+        '™' => 0x99,
+        'š' => 0x9A,
+        '›' => 0x9B,
+        'œ' => 0x9C,
+        'ž' => 0x9E,
+        'Ÿ' => 0x9F,
+        // Undefined in Windows-1252, but often passed through as is
+        '\u{81}' | '\u{8D}' | '\u{8F}' | '\u{90}' | '\u{9D}' => c as u8,
+        c if (c as u32) < 0x80 || (0xA0..=0xFF).contains(&(c as u32)) => c as u8,
+        _ => return None,
+    };
+    Some(byte)
+}
+
+/// Removes zero-width characters and soft hyphens and replaces special spaces with regular ones
+fn remove_invisible(text: &str) -> String {
+    text.chars()
+        .filter(|c| {
+            !matches!(
+                c,
+                '\u{200B}' | '\u{200C}' | '\u{2060}' | '\u{FEFF}' | '\u{AD}'
+            )
+        })
+        .map(|c| match c {
+            '\u{A0}' | '\u{2007}' | '\u{2009}' | '\u{200A}' | '\u{202F}' => ' ',
+            c => c,
+        })
+        .collect()
+}
+
+/// Applies NFKC, writing vulgar fractions with a slash and separated from a preceding
+/// number, e.g. "1½" -> "1 1/2"
+fn nfkc(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut previous = None;
+    for c in text.chars() {
+        if is_vulgar_fraction(c) {
+            if previous.is_some_and(|p: char| p.is_ascii_digit()) {
+                result.push(' ');
+            }
+            result.extend(std::iter::once(c).nfkc().map(|c| match c {
+                '\u{2044}' => '/',
+                c => c,
+            }));
+        } else {
+            result.push(c);
+        }
+        previous = Some(c);
+    }
+    result.nfkc().collect()
+}
+
+fn is_vulgar_fraction(c: char) -> bool {
+    matches!(c, '¼'..='¾' | '\u{2150}'..='\u{215E}')
+}
+
+fn straight_quotes(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '‘' | '’' | '‚' | '‛' | '′' => '\'',
+            '“' | '”' | '„' | '‟' | '″' => '"',
+            c => c,
+        })
+        .collect()
+}
+
+/// Replaces straight quotes with typographic ones, a quote at the start of a word opens
+fn curly_quotes(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut previous: Option<char> = None;
+    for c in text.chars() {
+        let opening = previous.is_none_or(|p| p.is_whitespace() || "([{".contains(p));
+        result.push(match (c, opening) {
+            ('\'', true) => '‘',
+            ('\'', false) => '’',
+            ('"', true) => '“',
+            ('"', false) => '”',
+            (c, _) => c,
+        });
+        previous = Some(c);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod mojibake {
+        use super::*;
+
+        #[test]
+        fn repair_latin() {
+            assert_eq!(repair_mojibake("CrÃ¨me brÃ»lÃ©e"), "Crème brûlée");
+            assert_eq!(repair_mojibake("GrÃ¶ÃŸe"), "Größe");
+        }
+
+        #[test]
+        fn repair_punctuation() {
+            assert_eq!(repair_mojibake("Donâ€™t"), "Don’t");
+            assert_eq!(repair_mojibake("Â½ cup"), "½ cup");
+        }
+
+        #[test]
+        fn keep_correct_text() {
+            for text in [
+                "Crème brûlée",
+                "½ cup",
+                "Größe",
+                "Ã",
+                "Tomaten – frisch",
+                "⅓",
+            ] {
+                assert_eq!(repair_mojibake(text), text);
+            }
+        }
+    }
+
+    mod normalizer {
+        use super::*;
+
+        #[test]
+        fn default_settings() {
+            let normalizer = TextNormalizer::default();
+            assert_eq!(normalizer.normalize("Cre\u{300}me"), "Crème");
+            assert_eq!(normalizer.normalize("1\u{a0}cup\u{200b}"), "1 cup");
+            assert_eq!(normalizer.normalize("Don’t"), "Don’t");
+            assert_eq!(normalizer.normalize("½ cup"), "½ cup");
+        }
+
+        #[test]
+        fn nfkc_fractions() {
+            let normalizer = TextNormalizer::new().with_unicode_form(UnicodeForm::Nfkc);
+            assert_eq!(normalizer.normalize("1½ cups"), "1 1/2 cups");
+            assert_eq!(normalizer.normalize("⅓ cup"), "1/3 cup");
+            assert_eq!(normalizer.normalize("ﬂour"), "flour");
+        }
+
+        #[test]
+        fn disable_steps() {
+            let normalizer = TextNormalizer::new()
+                .with_unicode_form(UnicodeForm::None)
+                .with_mojibake_repair(false)
+                .with_invisible_removal(false);
+            for text in ["Cre\u{300}me", "CrÃ¨me", "a\u{200b}b"] {
+                assert_eq!(normalizer.normalize(text), text);
+            }
+        }
+
+        #[test]
+        fn quote_styles() {
+            let straight = TextNormalizer::new().with_quote_style(QuoteStyle::Straight);
+            assert_eq!(straight.normalize("„Don’t“"), "\"Don't\"");
+            let curly = TextNormalizer::new().with_quote_style(QuoteStyle::Curly);
+            assert_eq!(curly.normalize("Say \"don't\""), "Say “don’t”");
+        }
+
+        #[test]
+        fn normalize_recipe() {
+            let mut recipe = Recipe {
+                name: Some("CrÃ¨me".into()),
+                tools: vec!["Pan\u{200b}".into()],
+                ..Default::default()
+            };
+            TextNormalizer::default().normalize_recipe(&mut recipe);
+            assert_eq!(recipe.name.as_deref(), Some("Crème"));
+            assert_eq!(recipe.tools, vec!["Pan"]);
+        }
+    }
+}
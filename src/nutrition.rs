@@ -0,0 +1,930 @@
+use crate::ingredient::parse_ingredient;
+use crate::model::{Ingredient, NutritionInformation, Recipe};
+use crate::units::{Unit, format_decimal};
+
+/// Nutrient values: kcal, fat, saturated fat, carbohydrates, sugar, fiber and protein
+/// in grams, sodium in milligrams
+type Nutrients = [f64; 8];
+
+/// Estimated [nutrition information](NutritionInformation) of a recipe, created using
+/// [Recipe::estimate_nutrition]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NutritionEstimate<'a> {
+    /// The nutrition of the whole recipe.
+    pub total: NutritionInformation,
+    /// The nutrition of a single serving, if the [number of servings](Recipe::servings)
+    /// is known.
+    pub per_serving: Option<NutritionInformation>,
+    /// The ingredients that were left out of the estimate, because the food is not in the
+    /// nutrient table or the amount could not be converted to grams.
+    pub unmatched: Vec<&'a Ingredient>,
+}
+
+pub(crate) fn estimate_nutrition(recipe: &Recipe) -> NutritionEstimate<'_> {
+    let mut total: Nutrients = [0.0; 8];
+    let mut unmatched = Vec::new();
+
+    for ingredient in recipe.ingredients() {
+        match ingredient_nutrients(ingredient) {
+            Some(nutrients) => {
+                for (sum, value) in total.iter_mut().zip(nutrients) {
+                    *sum += value;
+                }
+            }
+            None => unmatched.push(ingredient),
+        }
+    }
+
+    let per_serving = recipe.servings().map(|servings| {
+        let mut information = nutrition_information(total.map(|value| value / servings));
+        information.serving_size = Some("1 serving".into());
+        information
+    });
+
+    NutritionEstimate {
+        total: nutrition_information(total),
+        per_serving,
+        unmatched,
+    }
+}
+
+/// Returns the nutrients of a single ingredient line, if its food and weight are known
+fn ingredient_nutrients(ingredient: &str) -> Option<Nutrients> {
+    let parsed = parse_ingredient(ingredient);
+    let food = parsed.food.as_deref()?;
+    let (_, per_100g, piece, density) = NUTRIENTS.iter().find(|(id, ..)| *id == food)?;
+
+    let grams = match parsed.quantity() {
+        Some(quantity) => match quantity.to_for(Unit::Gram, &parsed.name) {
+            Some(grams) => grams.value,
+            None => quantity.base_value() * (*density)?,
+        },
+        None => parsed.amount? * (*piece)?,
+    };
+    Some(per_100g.map(|value| value * grams / 100.0))
+}
+
+fn nutrition_information(nutrients: Nutrients) -> NutritionInformation {
+    let [
+        calories,
+        fat,
+        saturated_fat,
+        carbohydrates,
+        sugar,
+        fiber,
+        protein,
+        sodium,
+    ] = nutrients;
+    let grams = |value: f64| Some(format!("{} g", format_decimal(value)));
+    NutritionInformation {
+        serving_size: None,
+        calories: Some(format!("{} kcal", calories.round())),
+        fat_content: grams(fat),
+        saturated_fat_content: grams(saturated_fat),
+        carbohydrate_content: grams(carbohydrates),
+        sugar_content: grams(sugar),
+        fiber_content: grams(fiber),
+        protein_content: grams(protein),
+        sodium_content: Some(format!("{} mg", sodium.round())),
+    }
+}
+
+/// Nutrients per 100 g for the foods of the [built-in dictionary](crate::FoodDictionary::builtin),
+/// derived from the USDA FoodData Central.
+///
+/// Each entry has the food id, the [nutrients](Nutrients), the weight of a single piece in grams
+/// (e.g. one egg) and the density in g/ml, used when no density is known for the ingredient name.
+const NUTRIENTS: &[(&str, Nutrients, Option<f64>, Option<f64>)] = &[
+    // Dairy & Eggs
+    (
+        "egg",
+        [143.0, 9.5, 3.1, 0.7, 0.4, 0.0, 12.6, 142.0],
+        Some(50.0),
+        None,
+    ),
+    (
+        "egg_yolk",
+        [322.0, 26.5, 9.6, 3.6, 0.6, 0.0, 15.9, 48.0],
+        Some(17.0),
+        None,
+    ),
+    (
+        "egg_white",
+        [52.0, 0.2, 0.0, 0.7, 0.7, 0.0, 10.9, 166.0],
+        Some(33.0),
+        None,
+    ),
+    (
+        "butter",
+        [717.0, 81.0, 51.0, 0.1, 0.1, 0.0, 0.9, 11.0],
+        None,
+        Some(0.96),
+    ),
+    (
+        "milk",
+        [61.0, 3.3, 1.9, 4.8, 5.1, 0.0, 3.2, 43.0],
+        None,
+        Some(1.03),
+    ),
+    (
+        "buttermilk",
+        [40.0, 0.9, 0.5, 4.8, 4.8, 0.0, 3.3, 105.0],
+        None,
+        Some(1.03),
+    ),
+    (
+        "cream",
+        [340.0, 36.0, 23.0, 2.8, 2.9, 0.0, 2.8, 27.0],
+        None,
+        Some(1.0),
+    ),
+    (
+        "sour_cream",
+        [198.0, 19.0, 10.0, 4.6, 3.4, 0.0, 2.4, 31.0],
+        None,
+        Some(1.0),
+    ),
+    (
+        "creme_fraiche",
+        [292.0, 30.0, 20.0, 2.5, 2.5, 0.0, 2.4, 35.0],
+        None,
+        Some(1.0),
+    ),
+    (
+        "yogurt",
+        [61.0, 3.3, 2.1, 4.7, 4.7, 0.0, 3.5, 46.0],
+        None,
+        Some(1.05),
+    ),
+    (
+        "cheese",
+        [402.0, 33.0, 19.0, 1.3, 0.5, 0.0, 25.0, 653.0],
+        None,
+        Some(0.48),
+    ),
+    (
+        "parmesan",
+        [431.0, 29.0, 17.0, 4.1, 0.9, 0.0, 38.0, 1529.0],
+        None,
+        Some(0.42),
+    ),
+    (
+        "mozzarella",
+        [280.0, 17.0, 10.0, 3.1, 1.0, 0.0, 28.0, 627.0],
+        Some(125.0),
+        Some(0.48),
+    ),
+    (
+        "cheddar",
+        [403.0, 33.0, 21.0, 1.3, 0.5, 0.0, 25.0, 653.0],
+        None,
+        Some(0.48),
+    ),
+    (
+        "feta",
+        [264.0, 21.0, 15.0, 4.1, 4.1, 0.0, 14.0, 917.0],
+        None,
+        Some(0.6),
+    ),
+    (
+        "ricotta",
+        [174.0, 13.0, 8.3, 3.0, 0.3, 0.0, 11.0, 84.0],
+        None,
+        Some(1.0),
+    ),
+    (
+        "cream_cheese",
+        [342.0, 34.0, 19.0, 4.1, 3.2, 0.0, 5.9, 321.0],
+        None,
+        Some(1.0),
+    ),
+    (
+        "quark",
+        [67.0, 0.3, 0.2, 4.0, 4.0, 0.0, 12.0, 40.0],
+        None,
+        Some(1.0),
+    ),
+    // Meat
+    (
+        "chicken",
+        [120.0, 2.6, 0.6, 0.0, 0.0, 0.0, 22.5, 45.0],
+        Some(170.0),
+        None,
+    ),
+    (
+        "beef",
+        [217.0, 13.0, 5.3, 0.0, 0.0, 0.0, 23.0, 60.0],
+        None,
+        None,
+    ),
+    (
+        "ground_beef",
+        [254.0, 20.0, 7.7, 0.0, 0.0, 0.0, 17.0, 66.0],
+        None,
+        None,
+    ),
+    (
+        "pork",
+        [211.0, 13.0, 4.6, 0.0, 0.0, 0.0, 21.0, 55.0],
+        None,
+        None,
+    ),
+    (
+        "bacon",
+        [417.0, 40.0, 13.0, 0.7, 0.0, 0.0, 13.0, 833.0],
+        Some(25.0),
+        None,
+    ),
+    (
+        "sausage",
+        [301.0, 27.0, 9.0, 2.0, 1.0, 0.0, 12.0, 800.0],
+        Some(75.0),
+        None,
+    ),
+    (
+        "ham",
+        [145.0, 5.5, 1.8, 1.5, 1.5, 0.0, 21.0, 1200.0],
+        Some(28.0),
+        None,
+    ),
+    (
+        "lamb",
+        [282.0, 23.0, 10.0, 0.0, 0.0, 0.0, 17.0, 59.0],
+        None,
+        None,
+    ),
+    (
+        "turkey",
+        [114.0, 1.5, 0.4, 0.0, 0.0, 0.0, 24.0, 110.0],
+        None,
+        None,
+    ),
+    // Seafood
+    (
+        "salmon",
+        [208.0, 13.0, 3.1, 0.0, 0.0, 0.0, 20.0, 59.0],
+        Some(150.0),
+        None,
+    ),
+    (
+        "tuna",
+        [116.0, 0.8, 0.2, 0.0, 0.0, 0.0, 26.0, 338.0],
+        None,
+        None,
+    ),
+    (
+        "cod",
+        [82.0, 0.7, 0.1, 0.0, 0.0, 0.0, 18.0, 54.0],
+        Some(150.0),
+        None,
+    ),
+    (
+        "fish",
+        [110.0, 2.0, 0.5, 0.0, 0.0, 0.0, 20.0, 60.0],
+        Some(150.0),
+        None,
+    ),
+    (
+        "shrimp",
+        [85.0, 0.5, 0.1, 0.0, 0.0, 0.0, 20.0, 119.0],
+        Some(12.0),
+        None,
+    ),
+    (
+        "anchovy",
+        [210.0, 9.7, 2.2, 0.0, 0.0, 0.0, 29.0, 3668.0],
+        Some(4.0),
+        None,
+    ),
+    (
+        "mussel",
+        [86.0, 2.2, 0.4, 3.7, 0.0, 0.0, 12.0, 286.0],
+        Some(15.0),
+        None,
+    ),
+    // Bakery
+    (
+        "bread",
+        [265.0, 3.2, 0.7, 49.0, 5.0, 2.7, 9.0, 491.0],
+        Some(30.0),
+        None,
+    ),
+    (
+        "baguette",
+        [270.0, 1.5, 0.3, 55.0, 3.0, 2.5, 9.0, 600.0],
+        Some(250.0),
+        None,
+    ),
+    (
+        "tortilla",
+        [310.0, 8.0, 3.0, 52.0, 2.0, 2.0, 8.0, 600.0],
+        Some(45.0),
+        None,
+    ),
+    (
+        "bun",
+        [280.0, 4.5, 1.0, 50.0, 6.0, 2.0, 9.0, 480.0],
+        Some(60.0),
+        None,
+    ),
+    // Produce
+    (
+        "onion",
+        [40.0, 0.1, 0.0, 9.3, 4.2, 1.7, 1.1, 4.0],
+        Some(110.0),
+        Some(0.68),
+    ),
+    (
+        "red_onion",
+        [40.0, 0.1, 0.0, 9.3, 4.2, 1.7, 1.1, 4.0],
+        Some(110.0),
+        Some(0.68),
+    ),
+    (
+        "spring_onion",
+        [32.0, 0.2, 0.0, 7.3, 2.3, 2.6, 1.8, 16.0],
+        Some(15.0),
+        Some(0.42),
+    ),
+    (
+        "shallot",
+        [72.0, 0.1, 0.0, 17.0, 7.9, 3.2, 2.5, 12.0],
+        Some(30.0),
+        Some(0.68),
+    ),
+    (
+        "garlic",
+        [149.0, 0.5, 0.1, 33.0, 1.0, 2.1, 6.4, 17.0],
+        Some(3.0),
+        Some(0.58),
+    ),
+    (
+        "tomato",
+        [18.0, 0.2, 0.0, 3.9, 2.6, 1.2, 0.9, 5.0],
+        Some(120.0),
+        Some(0.76),
+    ),
+    (
+        "potato",
+        [77.0, 0.1, 0.0, 17.0, 0.8, 2.2, 2.0, 6.0],
+        Some(170.0),
+        Some(0.64),
+    ),
+    (
+        "sweet_potato",
+        [86.0, 0.1, 0.0, 20.0, 4.2, 3.0, 1.6, 55.0],
+        Some(130.0),
+        Some(0.56),
+    ),
+    (
+        "carrot",
+        [41.0, 0.2, 0.0, 9.6, 4.7, 2.8, 0.9, 69.0],
+        Some(60.0),
+        Some(0.54),
+    ),
+    (
+        "celery",
+        [16.0, 0.2, 0.0, 3.0, 1.3, 1.6, 0.7, 80.0],
+        Some(40.0),
+        Some(0.43),
+    ),
+    (
+        "bell_pepper",
+        [31.0, 0.3, 0.0, 6.0, 4.2, 2.1, 1.0, 4.0],
+        Some(120.0),
+        Some(0.63),
+    ),
+    (
+        "chili",
+        [40.0, 0.4, 0.0, 8.8, 5.3, 1.5, 1.9, 9.0],
+        Some(15.0),
+        None,
+    ),
+    (
+        "zucchini",
+        [17.0, 0.3, 0.1, 3.1, 2.5, 1.0, 1.2, 8.0],
+        Some(200.0),
+        Some(0.53),
+    ),
+    (
+        "eggplant",
+        [25.0, 0.2, 0.0, 5.9, 3.5, 3.0, 1.0, 2.0],
+        Some(300.0),
+        Some(0.35),
+    ),
+    (
+        "cucumber",
+        [15.0, 0.1, 0.0, 3.6, 1.7, 0.5, 0.7, 2.0],
+        Some(300.0),
+        Some(0.55),
+    ),
+    (
+        "mushroom",
+        [22.0, 0.3, 0.0, 3.3, 2.0, 1.0, 3.1, 5.0],
+        Some(18.0),
+        Some(0.3),
+    ),
+    (
+        "spinach",
+        [23.0, 0.4, 0.1, 3.6, 0.4, 2.2, 2.9, 79.0],
+        None,
+        Some(0.13),
+    ),
+    (
+        "lettuce",
+        [15.0, 0.2, 0.0, 2.9, 0.8, 1.3, 1.4, 28.0],
+        Some(300.0),
+        Some(0.2),
+    ),
+    (
+        "cabbage",
+        [25.0, 0.1, 0.0, 5.8, 3.2, 2.5, 1.3, 18.0],
+        Some(900.0),
+        Some(0.37),
+    ),
+    (
+        "broccoli",
+        [34.0, 0.4, 0.0, 6.6, 1.7, 2.6, 2.8, 33.0],
+        Some(300.0),
+        Some(0.38),
+    ),
+    (
+        "leek",
+        [61.0, 0.3, 0.0, 14.0, 3.9, 1.8, 1.5, 20.0],
+        Some(150.0),
+        Some(0.38),
+    ),
+    (
+        "lemon",
+        [29.0, 0.3, 0.0, 9.3, 2.5, 2.8, 1.1, 2.0],
+        Some(85.0),
+        Some(1.03),
+    ),
+    (
+        "lime",
+        [30.0, 0.2, 0.0, 10.5, 1.7, 2.8, 0.7, 2.0],
+        Some(65.0),
+        Some(1.03),
+    ),
+    (
+        "orange",
+        [47.0, 0.1, 0.0, 11.8, 9.4, 2.4, 0.9, 0.0],
+        Some(130.0),
+        Some(1.04),
+    ),
+    (
+        "apple",
+        [52.0, 0.2, 0.0, 13.8, 10.4, 2.4, 0.3, 1.0],
+        Some(180.0),
+        Some(0.5),
+    ),
+    (
+        "banana",
+        [89.0, 0.3, 0.1, 22.8, 12.2, 2.6, 1.1, 1.0],
+        Some(120.0),
+        Some(0.95),
+    ),
+    (
+        "berries",
+        [57.0, 0.3, 0.0, 14.5, 10.0, 2.4, 0.7, 1.0],
+        None,
+        Some(0.6),
+    ),
+    (
+        "avocado",
+        [160.0, 14.7, 2.1, 8.5, 0.7, 6.7, 2.0, 7.0],
+        Some(150.0),
+        Some(0.62),
+    ),
+    (
+        "ginger",
+        [80.0, 0.8, 0.2, 18.0, 1.7, 2.0, 1.8, 13.0],
+        None,
+        Some(0.4),
+    ),
+    (
+        "parsley",
+        [36.0, 0.8, 0.1, 6.3, 0.9, 3.3, 3.0, 56.0],
+        None,
+        Some(0.25),
+    ),
+    (
+        "basil",
+        [23.0, 0.6, 0.0, 2.7, 0.3, 1.6, 3.2, 4.0],
+        None,
+        Some(0.1),
+    ),
+    (
+        "cilantro",
+        [23.0, 0.5, 0.0, 3.7, 0.9, 2.8, 2.1, 46.0],
+        None,
+        Some(0.07),
+    ),
+    (
+        "chives",
+        [30.0, 0.7, 0.1, 4.4, 1.9, 2.5, 3.3, 3.0],
+        None,
+        Some(0.2),
+    ),
+    (
+        "dill",
+        [43.0, 1.1, 0.0, 7.0, 0.0, 2.1, 3.5, 61.0],
+        None,
+        Some(0.04),
+    ),
+    (
+        "mint",
+        [70.0, 0.9, 0.2, 15.0, 0.0, 8.0, 3.8, 31.0],
+        None,
+        Some(0.1),
+    ),
+    (
+        "rosemary",
+        [131.0, 5.9, 2.8, 21.0, 0.0, 14.0, 3.3, 26.0],
+        None,
+        Some(0.12),
+    ),
+    (
+        "thyme",
+        [101.0, 1.7, 0.5, 24.0, 0.0, 14.0, 5.6, 9.0],
+        None,
+        Some(0.12),
+    ),
+    // Pantry
+    (
+        "flour",
+        [364.0, 1.0, 0.2, 76.0, 0.3, 2.7, 10.0, 2.0],
+        None,
+        Some(0.53),
+    ),
+    (
+        "sugar",
+        [387.0, 0.0, 0.0, 100.0, 100.0, 0.0, 0.0, 1.0],
+        None,
+        Some(0.85),
+    ),
+    (
+        "brown_sugar",
+        [380.0, 0.0, 0.0, 98.0, 97.0, 0.0, 0.1, 28.0],
+        None,
+        Some(0.93),
+    ),
+    (
+        "powdered_sugar",
+        [389.0, 0.0, 0.0, 100.0, 98.0, 0.0, 0.0, 2.0],
+        None,
+        Some(0.51),
+    ),
+    (
+        "honey",
+        [304.0, 0.0, 0.0, 82.0, 82.0, 0.2, 0.3, 4.0],
+        None,
+        Some(1.42),
+    ),
+    (
+        "rice",
+        [365.0, 0.7, 0.2, 80.0, 0.1, 1.3, 7.1, 5.0],
+        None,
+        Some(0.78),
+    ),
+    (
+        "pasta",
+        [371.0, 1.5, 0.3, 75.0, 2.7, 3.2, 13.0, 6.0],
+        None,
+        Some(0.42),
+    ),
+    (
+        "oats",
+        [389.0, 6.9, 1.2, 66.0, 1.0, 10.6, 16.9, 2.0],
+        None,
+        Some(0.38),
+    ),
+    (
+        "semolina",
+        [360.0, 1.0, 0.2, 73.0, 0.0, 3.9, 12.7, 1.0],
+        None,
+        Some(0.71),
+    ),
+    (
+        "olive_oil",
+        [884.0, 100.0, 13.8, 0.0, 0.0, 0.0, 0.0, 2.0],
+        None,
+        Some(0.92),
+    ),
+    (
+        "oil",
+        [884.0, 100.0, 7.4, 0.0, 0.0, 0.0, 0.0, 0.0],
+        None,
+        Some(0.92),
+    ),
+    (
+        "vinegar",
+        [18.0, 0.0, 0.0, 0.04, 0.04, 0.0, 0.0, 2.0],
+        None,
+        Some(1.01),
+    ),
+    (
+        "soy_sauce",
+        [53.0, 0.6, 0.1, 4.9, 0.4, 0.8, 8.1, 5493.0],
+        None,
+        Some(1.2),
+    ),
+    (
+        "mustard",
+        [66.0, 4.0, 0.2, 5.8, 0.9, 3.3, 4.4, 1135.0],
+        None,
+        Some(1.05),
+    ),
+    (
+        "baking_powder",
+        [53.0, 0.0, 0.0, 28.0, 0.0, 0.2, 0.0, 10600.0],
+        None,
+        Some(0.81),
+    ),
+    (
+        "baking_soda",
+        [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 27360.0],
+        None,
+        Some(0.93),
+    ),
+    (
+        "yeast",
+        [325.0, 7.6, 1.0, 41.0, 0.0, 27.0, 40.0, 51.0],
+        Some(7.0),
+        Some(0.6),
+    ),
+    (
+        "cornstarch",
+        [381.0, 0.1, 0.0, 91.0, 0.0, 0.9, 0.3, 9.0],
+        None,
+        Some(0.54),
+    ),
+    (
+        "stock",
+        [5.0, 0.2, 0.1, 0.4, 0.2, 0.0, 0.6, 343.0],
+        None,
+        Some(1.0),
+    ),
+    (
+        "canned_tomatoes",
+        [21.0, 0.1, 0.0, 4.0, 2.5, 1.0, 1.0, 140.0],
+        Some(400.0),
+        Some(1.03),
+    ),
+    (
+        "tomato_paste",
+        [82.0, 0.5, 0.1, 19.0, 12.0, 4.1, 4.3, 59.0],
+        None,
+        Some(1.1),
+    ),
+    (
+        "chickpeas",
+        [164.0, 2.6, 0.3, 27.0, 4.8, 7.6, 8.9, 7.0],
+        Some(240.0),
+        Some(0.66),
+    ),
+    (
+        "lentils",
+        [352.0, 1.1, 0.2, 63.0, 2.0, 11.0, 25.0, 6.0],
+        None,
+        Some(0.8),
+    ),
+    (
+        "beans",
+        [127.0, 0.5, 0.1, 23.0, 0.3, 6.4, 8.7, 1.0],
+        Some(240.0),
+        Some(0.7),
+    ),
+    (
+        "coconut_milk",
+        [197.0, 21.0, 19.0, 2.8, 3.3, 0.0, 2.0, 13.0],
+        Some(400.0),
+        Some(1.0),
+    ),
+    (
+        "peanut_butter",
+        [588.0, 50.0, 10.0, 20.0, 9.0, 6.0, 25.0, 459.0],
+        None,
+        Some(1.09),
+    ),
+    (
+        "chocolate",
+        [546.0, 31.0, 19.0, 61.0, 48.0, 7.0, 4.9, 24.0],
+        None,
+        Some(0.72),
+    ),
+    (
+        "cocoa",
+        [228.0, 14.0, 8.0, 58.0, 1.8, 37.0, 20.0, 21.0],
+        None,
+        Some(0.42),
+    ),
+    (
+        "nuts",
+        [607.0, 54.0, 6.0, 21.0, 4.0, 7.0, 20.0, 5.0],
+        None,
+        Some(0.6),
+    ),
+    (
+        "peanuts",
+        [567.0, 49.0, 7.0, 16.0, 4.0, 8.5, 26.0, 18.0],
+        None,
+        Some(0.6),
+    ),
+    (
+        "breadcrumbs",
+        [395.0, 5.3, 1.2, 72.0, 6.2, 4.5, 13.0, 732.0],
+        None,
+        Some(0.45),
+    ),
+    (
+        "sesame",
+        [573.0, 50.0, 7.0, 23.0, 0.3, 11.8, 17.7, 11.0],
+        None,
+        Some(0.6),
+    ),
+    (
+        "gochujang",
+        [210.0, 1.5, 0.3, 45.0, 20.0, 2.0, 4.0, 2800.0],
+        None,
+        Some(1.2),
+    ),
+    // Spices
+    (
+        "salt",
+        [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 38758.0],
+        None,
+        Some(1.22),
+    ),
+    (
+        "black_pepper",
+        [251.0, 3.3, 1.4, 64.0, 0.6, 25.0, 10.0, 20.0],
+        None,
+        Some(0.46),
+    ),
+    (
+        "paprika",
+        [282.0, 13.0, 2.1, 54.0, 10.0, 35.0, 14.0, 68.0],
+        None,
+        Some(0.46),
+    ),
+    (
+        "cinnamon",
+        [247.0, 1.2, 0.3, 81.0, 2.2, 53.0, 4.0, 10.0],
+        None,
+        Some(0.56),
+    ),
+    (
+        "cumin",
+        [375.0, 22.0, 1.5, 44.0, 2.3, 10.5, 18.0, 168.0],
+        None,
+        Some(0.41),
+    ),
+    (
+        "oregano",
+        [265.0, 4.3, 1.6, 69.0, 4.1, 42.0, 9.0, 25.0],
+        None,
+        Some(0.2),
+    ),
+    (
+        "nutmeg",
+        [525.0, 36.0, 26.0, 49.0, 2.8, 21.0, 5.8, 16.0],
+        None,
+        Some(0.47),
+    ),
+    (
+        "vanilla",
+        [288.0, 0.1, 0.0, 12.7, 12.7, 0.0, 0.1, 9.0],
+        None,
+        Some(0.88),
+    ),
+    (
+        "chili_flakes",
+        [318.0, 17.0, 3.0, 57.0, 10.0, 27.0, 12.0, 30.0],
+        None,
+        Some(0.35),
+    ),
+    (
+        "curry_powder",
+        [325.0, 14.0, 2.2, 56.0, 2.8, 53.0, 14.0, 52.0],
+        None,
+        Some(0.42),
+    ),
+    (
+        "bay_leaf",
+        [313.0, 8.4, 2.3, 75.0, 0.0, 26.0, 7.6, 23.0],
+        Some(0.2),
+        None,
+    ),
+    // Frozen
+    (
+        "frozen_peas",
+        [77.0, 0.4, 0.1, 13.6, 5.0, 4.5, 5.2, 108.0],
+        None,
+        Some(0.57),
+    ),
+    (
+        "ice_cream",
+        [207.0, 11.0, 6.8, 24.0, 21.0, 0.7, 3.5, 80.0],
+        None,
+        Some(0.55),
+    ),
+    // Beverages
+    (
+        "water",
+        [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+        None,
+        Some(1.0),
+    ),
+    (
+        "wine",
+        [83.0, 0.0, 0.0, 2.6, 0.6, 0.0, 0.1, 5.0],
+        None,
+        Some(0.99),
+    ),
+    (
+        "beer",
+        [43.0, 0.0, 0.0, 3.6, 0.0, 0.0, 0.5, 4.0],
+        None,
+        Some(1.0),
+    ),
+    (
+        "juice",
+        [45.0, 0.2, 0.0, 10.4, 8.4, 0.2, 0.7, 1.0],
+        None,
+        Some(1.04),
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_covers_builtin_foods() {
+        for food in crate::FoodDictionary::builtin().foods() {
+            assert!(
+                NUTRIENTS.iter().any(|(id, ..)| *id == food.id),
+                "missing nutrients for {}",
+                food.id
+            );
+        }
+    }
+
+    #[test]
+    fn estimate_weights() {
+        let recipe = Recipe::from_lines(&["100 g butter", "200 g sugar"], &[]);
+        let estimate = estimate_nutrition(&recipe);
+        assert_eq!(estimate.total.calories.as_deref(), Some("1491 kcal"));
+        assert_eq!(estimate.total.fat_content.as_deref(), Some("81 g"));
+        assert_eq!(estimate.total.sugar_content.as_deref(), Some("200 g"));
+        assert!(estimate.unmatched.is_empty());
+        assert_eq!(estimate.per_serving, None);
+    }
+
+    #[test]
+    fn estimate_volumes() {
+        let recipe = Recipe::from_lines(&["1 cup milk", "2 EL Olivenöl"], &[]);
+        let estimate = estimate_nutrition(&recipe);
+        assert_eq!(estimate.total.calories.as_deref(), Some("389 kcal"));
+    }
+
+    #[test]
+    fn estimate_pieces() {
+        let recipe = Recipe::from_lines(&["2 eggs", "1 onion"], &[]);
+        let estimate = estimate_nutrition(&recipe);
+        assert_eq!(estimate.total.calories.as_deref(), Some("187 kcal"));
+        assert_eq!(estimate.total.protein_content.as_deref(), Some("14 g"));
+    }
+
+    #[test]
+    fn estimate_per_serving() {
+        let recipe = Recipe {
+            recipe_yield: Some("4 servings".into()),
+            ..Recipe::from_lines(&["400 g pasta"], &[])
+        };
+        let estimate = estimate_nutrition(&recipe);
+        let per_serving = estimate.per_serving.unwrap();
+        assert_eq!(per_serving.calories.as_deref(), Some("371 kcal"));
+        assert_eq!(per_serving.serving_size.as_deref(), Some("1 serving"));
+    }
+
+    #[test]
+    fn report_unmatched() {
+        let recipe = Recipe::from_lines(
+            &[
+                "salt to taste",
+                "2 quinces",
+                "1 pinch of sugar",
+                "100 g flour",
+            ],
+            &[],
+        );
+        let estimate = estimate_nutrition(&recipe);
+        assert_eq!(
+            estimate.unmatched,
+            vec!["salt to taste", "2 quinces", "1 pinch of sugar"]
+        );
+        assert_eq!(estimate.total.calories.as_deref(), Some("364 kcal"));
+    }
+}
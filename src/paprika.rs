@@ -0,0 +1,418 @@
+//! Exports recipes to Paprika's `.paprikarecipes` archives and imports them back.
+
+use crate::ingredient::heading_name;
+use crate::json_ld::{ingredient_lines, iso_duration};
+use crate::text::file_name;
+use crate::timers::{format_duration, parse_time};
+use crate::{HowToSection, IngredientSection, NutritionInformation, Recipe};
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde_json::{Value, json};
+use std::io::{Cursor, Read, Write};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+/// The extension of a single recipe inside the archive
+const RECIPE_EXTENSION: &str = "paprikarecipe";
+
+/// Converts the recipe to the JSON Paprika stores for every recipe
+///
+/// Ingredients and steps are written one per line, with headings like "Dough:" for named
+/// sections. Times are written as text, e.g. "1 hour 30 minutes", and the author as the
+/// source. The uid is derived from the contents, so the same recipe always gets the same
+/// uid.
+pub(crate) fn to_paprika_json(recipe: &Recipe) -> Value {
+    let time = |time: &Option<String>| match time {
+        Some(time) => parse_time(time).map_or_else(|| time.clone(), format_duration),
+        None => String::new(),
+    };
+    let directions: Vec<String> = recipe
+        .how_to_sections
+        .iter()
+        .flat_map(|section| {
+            let heading = section.name.as_ref().map(|name| format!("{name}:"));
+            heading.into_iter().chain(section.steps.iter().cloned())
+        })
+        .collect();
+
+    let mut json = json!({
+        "name": recipe.name.as_deref().unwrap_or("Recipe"),
+        "description": recipe.description.as_deref().unwrap_or_default(),
+        "ingredients": ingredient_lines(recipe).join("\n"),
+        "directions": directions.join("\n"),
+        "servings": recipe.recipe_yield.as_deref().unwrap_or_default(),
+        "prep_time": time(&recipe.prep_time),
+        "cook_time": time(&recipe.cook_time),
+        "total_time": time(&recipe.total_time),
+        "nutritional_info": recipe.nutrition.as_ref().map(nutrition_text).unwrap_or_default(),
+        "source": recipe.author.as_deref().unwrap_or_default(),
+        "source_url": "",
+        "notes": "",
+        "categories": [],
+        "difficulty": "",
+        "rating": 0,
+        "photo": null,
+        "photo_data": null,
+        "photos": [],
+        "image_url": null,
+    });
+
+    let content = json.to_string();
+    let (high, low) = (fnv(&content, 0), fnv(&content, 1));
+    let hex = format!("{high:016X}{low:016X}");
+    json["uid"] = format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+    .into();
+    json["hash"] = hex.to_lowercase().into();
+    json
+}
+
+/// Writes the recipes to a `.paprikarecipes` archive: a zip file with the gzipped JSON of
+/// every recipe
+pub fn to_paprika_archive(recipes: &[Recipe]) -> std::io::Result<Vec<u8>> {
+    let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let mut names: Vec<String> = Vec::new();
+
+    for recipe in recipes {
+        let name = file_name(recipe.name.as_deref().unwrap_or_default());
+        let mut entry = format!("{name}.{RECIPE_EXTENSION}");
+        for i in 2.. {
+            if !names.contains(&entry) {
+                break;
+            }
+            entry = format!("{name} ({i}).{RECIPE_EXTENSION}");
+        }
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(to_paprika_json(recipe).to_string().as_bytes())?;
+        archive.start_file(entry.as_str(), options)?;
+        archive.write_all(&encoder.finish()?)?;
+        names.push(entry);
+    }
+
+    Ok(archive.finish()?.into_inner())
+}
+
+/// Reads the recipes of a `.paprikarecipes` archive
+///
+/// Every entry of the archive is the gzipped JSON of one recipe. Entries that are not
+/// gzipped are read as plain JSON. Fails if the archive or one of its recipes cannot be
+/// read.
+pub fn parse_paprika_archive(bytes: &[u8]) -> std::io::Result<Vec<Recipe>> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+    let mut recipes = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+        if content.starts_with(&GZIP_MAGIC) {
+            let mut json = Vec::new();
+            GzDecoder::new(content.as_slice()).read_to_end(&mut json)?;
+            content = json;
+        }
+        let json: Value = serde_json::from_slice(&content)?;
+        recipes.push(from_paprika_json(&json));
+    }
+
+    Ok(recipes)
+}
+
+/// The first bytes of a gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Converts the JSON Paprika stores for every recipe
+///
+/// It deals with:
+///     - ingredients and directions one per line, headings like "Dough:" start a section
+///     - times as text, converted to ISO 8601 durations when they can be parsed
+///     - the nutrition as "Label: value" lines
+///     - empty strings for missing values
+fn from_paprika_json(json: &Value) -> Recipe {
+    let text = |key: &str| {
+        json.get(key)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(str::to_string)
+    };
+    let time = |key: &str| text(key).map(|time| iso_duration(&time).unwrap_or(time));
+
+    Recipe {
+        name: text("name"),
+        author: text("source"),
+        description: text("description"),
+        ingredient_sections: group_lines(&text("ingredients").unwrap_or_default())
+            .into_iter()
+            .map(|(name, ingredients)| IngredientSection { name, ingredients })
+            .collect(),
+        how_to_sections: group_lines(&text("directions").unwrap_or_default())
+            .into_iter()
+            .map(|(name, steps)| HowToSection { name, steps })
+            .collect(),
+        recipe_yield: text("servings"),
+        nutrition: text("nutritional_info").and_then(|text| parse_nutrition(&text)),
+        prep_time: time("prep_time"),
+        cook_time: time("cook_time"),
+        total_time: time("total_time"),
+        ..Default::default()
+    }
+}
+
+/// Splits the text into lines and groups them under the headings found between them
+///
+/// Lines before the first heading are put in a group without a name. A line only counts
+/// as a heading if a line that is not a heading follows it, otherwise it is kept as a
+/// line, e.g. a trailing "SERVE HOT".
+fn group_lines(text: &str) -> Vec<(Option<String>, Vec<String>)> {
+    let mut groups = Vec::new();
+    let mut current = (None, Vec::new());
+    let mut lines = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .peekable();
+
+    while let Some(line) = lines.next() {
+        let followed_by_line = lines
+            .peek()
+            .is_some_and(|next| heading_name(next).is_none());
+        match heading_name(line).filter(|_| followed_by_line) {
+            Some(name) => {
+                if !current.1.is_empty() {
+                    groups.push(std::mem::take(&mut current));
+                }
+                current.0 = Some(name);
+            }
+            None => current.1.push(line.to_string()),
+        }
+    }
+
+    if !current.1.is_empty() {
+        groups.push(current);
+    }
+    groups
+}
+
+/// Writes the nutrition one value per line, e.g. "Calories: 240 kcal"
+fn nutrition_text(nutrition: &NutritionInformation) -> String {
+    [
+        ("Serving size", &nutrition.serving_size),
+        ("Calories", &nutrition.calories),
+        ("Fat", &nutrition.fat_content),
+        ("Saturated fat", &nutrition.saturated_fat_content),
+        ("Carbohydrates", &nutrition.carbohydrate_content),
+        ("Sugar", &nutrition.sugar_content),
+        ("Fiber", &nutrition.fiber_content),
+        ("Protein", &nutrition.protein_content),
+        ("Sodium", &nutrition.sodium_content),
+    ]
+    .into_iter()
+    .filter_map(|(label, value)| value.as_ref().map(|value| format!("{label}: {value}")))
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// Reads the nutrition from "Label: value" lines, ignoring labels it does not know
+fn parse_nutrition(text: &str) -> Option<NutritionInformation> {
+    let mut nutrition = NutritionInformation::default();
+    for line in text.lines() {
+        let Some((label, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (label, value) = (label.trim(), value.trim());
+        let field = match label.to_lowercase().as_str() {
+            "serving size" => &mut nutrition.serving_size,
+            "calories" => &mut nutrition.calories,
+            "fat" | "total fat" => &mut nutrition.fat_content,
+            "saturated fat" => &mut nutrition.saturated_fat_content,
+            "carbohydrates" | "carbs" => &mut nutrition.carbohydrate_content,
+            "sugar" | "sugars" => &mut nutrition.sugar_content,
+            "fiber" | "fibre" => &mut nutrition.fiber_content,
+            "protein" => &mut nutrition.protein_content,
+            "sodium" => &mut nutrition.sodium_content,
+            _ => continue,
+        };
+        if !value.is_empty() {
+            *field = Some(value.to_string());
+        }
+    }
+    (nutrition != NutritionInformation::default()).then_some(nutrition)
+}
+
+/// The FNV-1a hash of the text, with the seed mixed into the offset basis
+fn fnv(text: &str, seed: u64) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325 ^ seed, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipe() -> Recipe {
+        Recipe {
+            name: Some("Pancakes".into()),
+            author: Some("Jane".into()),
+            recipe_yield: Some("4 servings".into()),
+            prep_time: Some("PT1H30M".into()),
+            cook_time: Some("soon".into()),
+            ingredient_sections: vec![
+                IngredientSection {
+                    name: None,
+                    ingredients: vec!["2 eggs".into()],
+                },
+                IngredientSection {
+                    name: Some("Topping".into()),
+                    ingredients: vec!["Maple syrup".into()],
+                },
+            ],
+            how_to_sections: vec![HowToSection {
+                name: Some("Frying".into()),
+                steps: vec!["Mix.".into(), "Fry.".into()],
+            }],
+            nutrition: Some(NutritionInformation {
+                calories: Some("240 kcal".into()),
+                protein_content: Some("8 g".into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn write_json() {
+        let json = recipe().to_paprika_json();
+        assert_eq!(json["ingredients"], "2 eggs\nTopping:\nMaple syrup");
+        assert_eq!(json["directions"], "Frying:\nMix.\nFry.");
+        assert_eq!(json["servings"], "4 servings");
+        assert_eq!(json["prep_time"], "1 hour 30 minutes");
+        assert_eq!(json["cook_time"], "soon");
+        assert_eq!(json["total_time"], "");
+        assert_eq!(json["nutritional_info"], "Calories: 240 kcal\nProtein: 8 g");
+        assert_eq!(json["source"], "Jane");
+    }
+
+    #[test]
+    fn stable_uid() {
+        let uid = recipe().to_paprika_json()["uid"].clone();
+        assert_eq!(uid, recipe().to_paprika_json()["uid"]);
+        assert_eq!(uid.as_str().unwrap().len(), 36);
+        assert_ne!(uid, Recipe::default().to_paprika_json()["uid"]);
+    }
+
+    #[test]
+    fn write_archive() {
+        let recipes = [recipe(), recipe(), Recipe::default()];
+        let archive = to_paprika_archive(&recipes).unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(archive)).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+        assert_eq!(names.len(), 3);
+        for name in [
+            "Pancakes.paprikarecipe",
+            "Pancakes (2).paprikarecipe",
+            "Recipe.paprikarecipe",
+        ] {
+            assert!(names.contains(&name), "{name} is missing");
+        }
+
+        let mut json = String::new();
+        GzDecoder::new(archive.by_name("Pancakes.paprikarecipe").unwrap())
+            .read_to_string(&mut json)
+            .unwrap();
+        let json: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json, recipe().to_paprika_json());
+    }
+
+    #[test]
+    fn read_archive() {
+        let recipes = [recipe(), Recipe::default()];
+        let archive = to_paprika_archive(&recipes).unwrap();
+        let read = parse_paprika_archive(&archive).unwrap();
+        assert_eq!(read.len(), 2);
+        assert_eq!(
+            read[0],
+            Recipe {
+                prep_time: Some("PT1H30M".into()),
+                ..recipe()
+            }
+        );
+        assert_eq!(read[1].name.as_deref(), Some("Recipe"));
+        assert!(read[1].ingredient_sections.is_empty());
+    }
+
+    #[test]
+    fn read_plain_json() {
+        let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+        archive
+            .start_file("Soup.paprikarecipe", SimpleFileOptions::default())
+            .unwrap();
+        let json = json!({
+            "name": "Soup",
+            "ingredients": "1 onion\n\n2 cups stock",
+            "directions": "Chop the onion.\n\nSimmer.",
+            "servings": "",
+            "cook_time": "20 mins",
+            "nutritional_info": "calories: 90\nVitamin C: 5 mg",
+        });
+        archive.write_all(json.to_string().as_bytes()).unwrap();
+        let archive = archive.finish().unwrap().into_inner();
+
+        let recipes = parse_paprika_archive(&archive).unwrap();
+        assert_eq!(recipes.len(), 1);
+        let recipe = &recipes[0];
+        assert_eq!(recipe.recipe_yield, None);
+        assert_eq!(recipe.cook_time.as_deref(), Some("PT20M"));
+        assert_eq!(
+            recipe.ingredient_sections[0].ingredients,
+            vec!["1 onion", "2 cups stock"]
+        );
+        assert_eq!(recipe.how_to_sections[0].steps.len(), 2);
+        assert_eq!(
+            recipe.nutrition.as_ref().unwrap().calories.as_deref(),
+            Some("90")
+        );
+    }
+
+    #[test]
+    fn group_headings() {
+        let groups = group_lines("1 onion\nSAUCE:\n2 cups stock\nGARNISH\nTOPPING\nSERVE HOT");
+        assert_eq!(
+            groups,
+            vec![
+                (None, vec!["1 onion".to_string()]),
+                (
+                    Some("SAUCE".to_string()),
+                    vec![
+                        "2 cups stock".to_string(),
+                        "GARNISH".to_string(),
+                        "TOPPING".to_string(),
+                        "SERVE HOT".to_string(),
+                    ]
+                ),
+            ]
+        );
+        assert_eq!(
+            group_lines("Chop.\nSERVE HOT"),
+            vec![(None, vec!["Chop.".to_string(), "SERVE HOT".to_string()])]
+        );
+    }
+
+    #[test]
+    fn read_invalid_archive() {
+        assert!(parse_paprika_archive(b"not a zip").is_err());
+    }
+}
@@ -0,0 +1,922 @@
+//! Finds the recipe in an HTML document using JSON-LD, microdata, RDFa or the structure of
+//! the page.
+
+use crate::constants::LdFields;
+use crate::report::{ExtractionWarning, inspect_recipe};
+use crate::validate::{Diagnostic, validate_scripts};
+use crate::{Recipe, SCHEMA_ORG_PREFIXES, TextFormat, TextNormalizer, extract_recipe};
+
+use scraper::{ElementRef, Html, Selector};
+use serde_json::{Map, Value};
+
+const JSON_LD_SELECTOR: &str = r#"script[type="application/ld+json"]"#;
+const MICRODATA_SELECTOR: &str = "[itemscope][itemtype]";
+const RDFA_SELECTOR: &str = "[typeof]";
+const HEADING_SELECTOR: &str = "h1, h2, h3, h4, h5, h6";
+const TITLE_SELECTOR: &str = r#"meta[property="og:title"], title"#;
+const DESCRIPTION_SELECTOR: &str = r#"meta[name="description"], meta[property="og:description"]"#;
+const RECIPE_TYPE: &str = "Recipe";
+
+/// How strictly a [RecipeParser] follows the schema.org specification
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Strictness {
+    /// Accepts common mistakes, e.g. `"@type": "schema:Recipe"`, line breaks inside JSON
+    /// strings or the superseded `ingredients` property.
+    Lenient,
+    /// Only accepts `"@type": "Recipe"` and valid JSON.
+    #[default]
+    Standard,
+    /// Like [Standard](Strictness::Standard), and requires a name, ingredients and
+    /// instructions. The heuristic tier is never used.
+    Strict,
+}
+
+/// The language of the headings a [RecipeParser] looks for when no structured data is found
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Locale {
+    /// Looks for headings in all supported languages.
+    #[default]
+    Any,
+    /// Looks for headings like "Ingredients" and "Instructions".
+    English,
+    /// Looks for headings like "Zutaten" and "Zubereitung".
+    German,
+}
+
+/// A parser to extract [recipes](Recipe) from HTML documents
+///
+/// The recipe is looked for in the following tiers, the first tier that finds a recipe wins:
+///     - JSON-LD, i.e. `<script type="application/ld+json">`
+///     - microdata, i.e. `itemscope`, `itemtype` and `itemprop` attributes (disabled by default)
+///     - RDFa, i.e. `typeof` and `property` attributes (disabled by default)
+///     - heuristics, i.e. lists following headings like "Ingredients" (disabled by default)
+///
+/// The parser can be reused for many documents.
+///
+/// ## Example
+///
+/// ```
+/// use reget::{Locale, RecipeParser, TextFormat};
+///
+/// let parser = RecipeParser::new()
+///     .with_heuristics(true)
+///     .with_locale(Locale::English)
+///     .with_text_format(TextFormat::Markdown);
+///
+/// let html = r#"
+/// <h1>Pancakes</h1>
+/// <h2>Ingredients</h2>
+/// <ul><li>2 eggs</li><li>200 g flour</li></ul>
+/// <h2>Instructions</h2>
+/// <ol><li>Mix <b>well</b>.</li><li>Fry.</li></ol>
+/// "#;
+///
+/// let recipe = parser.parse(html).unwrap();
+/// assert_eq!(recipe.name.as_deref(), Some("Pancakes"));
+/// assert_eq!(recipe.how_to_sections[0].steps, vec!["Mix **well**.", "Fry."]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecipeParser {
+    /// Whether JSON-LD is used
+    json_ld: bool,
+    /// Whether microdata is used
+    microdata: bool,
+    /// Whether RDFa is used
+    rdfa: bool,
+    /// Whether the recipe is guessed from the headings of the document
+    heuristics: bool,
+    /// The format HTML inside the text fields is converted to
+    text_format: TextFormat,
+    /// The normalizer applied to all text fields
+    normalizer: Option<TextNormalizer>,
+    /// Whether headings in the ingredient list start new sections
+    ingredient_sections: bool,
+    /// How strictly the specification is followed
+    strictness: Strictness,
+    /// The language of the headings for the heuristics
+    locale: Locale,
+    /// The selectors, compiled once for all documents
+    selectors: Selectors,
+}
+
+#[derive(Debug, Clone)]
+struct Selectors {
+    json_ld: Selector,
+    microdata: Selector,
+    rdfa: Selector,
+    heading: Selector,
+    title: Selector,
+    description: Selector,
+}
+
+impl Default for RecipeParser {
+    fn default() -> Self {
+        RecipeParser {
+            json_ld: true,
+            microdata: false,
+            rdfa: false,
+            heuristics: false,
+            text_format: TextFormat::Plain,
+            normalizer: Some(TextNormalizer::default()),
+            ingredient_sections: true,
+            strictness: Strictness::Standard,
+            locale: Locale::Any,
+            selectors: Selectors {
+                json_ld: Selector::parse(JSON_LD_SELECTOR).unwrap(),
+                microdata: Selector::parse(MICRODATA_SELECTOR).unwrap(),
+                rdfa: Selector::parse(RDFA_SELECTOR).unwrap(),
+                heading: Selector::parse(HEADING_SELECTOR).unwrap(),
+                title: Selector::parse(TITLE_SELECTOR).unwrap(),
+                description: Selector::parse(DESCRIPTION_SELECTOR).unwrap(),
+            },
+        }
+    }
+}
+
+impl RecipeParser {
+    /// Constructs a parser with the default settings
+    pub fn new() -> Self {
+        RecipeParser::default()
+    }
+
+    /// Sets whether JSON-LD is used, defaults to true
+    pub fn with_json_ld(mut self, enabled: bool) -> Self {
+        self.json_ld = enabled;
+        self
+    }
+
+    /// Sets whether microdata is used, defaults to false
+    pub fn with_microdata(mut self, enabled: bool) -> Self {
+        self.microdata = enabled;
+        self
+    }
+
+    /// Sets whether RDFa is used, defaults to false
+    pub fn with_rdfa(mut self, enabled: bool) -> Self {
+        self.rdfa = enabled;
+        self
+    }
+
+    /// Sets whether the recipe is guessed from the headings of the document if there is no
+    /// structured data, defaults to false
+    pub fn with_heuristics(mut self, enabled: bool) -> Self {
+        self.heuristics = enabled;
+        self
+    }
+
+    /// Sets the [format](TextFormat) HTML inside the text fields is converted to, defaults
+    /// to plain text
+    pub fn with_text_format(mut self, format: TextFormat) -> Self {
+        self.text_format = format;
+        self
+    }
+
+    /// Sets the [normalizer](TextNormalizer) applied to all text fields, defaults to
+    /// [TextNormalizer::default]
+    pub fn with_normalizer(mut self, normalizer: TextNormalizer) -> Self {
+        self.normalizer = Some(normalizer);
+        self
+    }
+
+    /// Keeps the text fields as they are after removing HTML
+    pub fn without_normalizer(mut self) -> Self {
+        self.normalizer = None;
+        self
+    }
+
+    /// Sets whether headings like "For the dough:" in the ingredient list start new
+    /// [sections](crate::IngredientSection), defaults to true. Otherwise all ingredients are
+    /// kept in a single section.
+    pub fn with_ingredient_sections(mut self, enabled: bool) -> Self {
+        self.ingredient_sections = enabled;
+        self
+    }
+
+    /// Sets the [strictness](Strictness), defaults to standard
+    pub fn with_strictness(mut self, strictness: Strictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
+
+    /// Sets the [locale](Locale) of the headings used by the heuristics, defaults to any
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Parses the first [recipe](Recipe) found in the HTML document
+    pub fn parse(&self, html: &str) -> Option<Recipe> {
+        let json = self.find_recipe_json(html)?;
+        self.extract(&json)
+    }
+
+    /// Parses the recipe like [parse](Self::parse) and reports the data that was ignored,
+    /// see [parse_recipe_with_report](crate::parse_recipe_with_report)
+    pub fn parse_with_report(&self, html: &str) -> Option<(Recipe, Vec<ExtractionWarning>)> {
+        let json = self.find_recipe_json(html)?;
+        let recipe = self.extract(&json)?;
+        Some((recipe, inspect_recipe(&json)))
+    }
+
+    /// Validates the JSON-LD of the first recipe in the HTML document against schema.org and
+    /// the requirements of Google's recipe rich results, see [validate_recipe](crate::validate_recipe)
+    ///
+    /// Validation is always strict, the settings of the parser only affect [parse](Self::parse).
+    pub fn validate(&self, html: &str) -> Vec<Diagnostic> {
+        let document = Html::parse_document(html);
+        let scripts: Vec<String> = document
+            .select(&self.selectors.json_ld)
+            .map(|e| e.text().collect())
+            .collect();
+        validate_scripts(scripts.iter().map(String::as_str))
+    }
+
+    /// Finds the recipe in the document and returns it as JSON-LD
+    fn find_recipe_json(&self, html: &str) -> Option<Map<String, Value>> {
+        let document = Html::parse_document(html);
+        let mut json = self.find_recipe(&document)?;
+        if self.strictness == Strictness::Lenient {
+            apply_aliases(&mut json);
+        }
+        Some(json)
+    }
+
+    /// Extracts and normalizes the recipe, returns None if it is incomplete in strict mode
+    fn extract(&self, json: &Map<String, Value>) -> Option<Recipe> {
+        let mut recipe = extract_recipe(json, self.text_format, self.ingredient_sections);
+        if self.strictness == Strictness::Strict && !is_complete(&recipe) {
+            return None;
+        }
+        if let Some(normalizer) = &self.normalizer {
+            normalizer.normalize_recipe(&mut recipe);
+        }
+        Some(recipe)
+    }
+
+    /// Tries the enabled tiers in order and returns the recipe as JSON-LD
+    fn find_recipe(&self, document: &Html) -> Option<Map<String, Value>> {
+        let heuristics = self.heuristics && self.strictness != Strictness::Strict;
+        None.or_else(|| self.json_ld.then(|| self.find_json_ld(document))?)
+            .or_else(|| {
+                self.microdata
+                    .then(|| self.find_item(document, &self.selectors.microdata, &MICRODATA))?
+            })
+            .or_else(|| {
+                self.rdfa
+                    .then(|| self.find_item(document, &self.selectors.rdfa, &RDFA))?
+            })
+            .or_else(|| heuristics.then(|| self.guess_recipe(document))?)
+    }
+
+    /// Looks for `type="application/ld+json"` with `"@type": Recipe`
+    fn find_json_ld(&self, document: &Html) -> Option<Map<String, Value>> {
+        document.select(&self.selectors.json_ld).find_map(|e| {
+            let text = e.text().collect::<String>();
+            let value = match serde_json::from_str::<Value>(&text) {
+                Ok(value) => value,
+                Err(_) if self.strictness == Strictness::Lenient => {
+                    serde_json::from_str(&escape_control_characters(&text)).ok()?
+                }
+                Err(_) => return None,
+            };
+            find_recipe_in_value(value, self.strictness)
+        })
+    }
+
+    /// Looks for a microdata or RDFa item of the type Recipe and converts it to JSON-LD
+    fn find_item(
+        &self,
+        document: &Html,
+        selector: &Selector,
+        attributes: &ItemAttributes,
+    ) -> Option<Map<String, Value>> {
+        document
+            .select(selector)
+            .map(|element| item_to_json(element, attributes))
+            .find(|item| is_recipe_type(item, self.strictness))
+    }
+
+    /// Guesses the recipe from the headings of the document, e.g. the list following
+    /// "Ingredients"
+    fn guess_recipe(&self, document: &Html) -> Option<Map<String, Value>> {
+        let (ingredient_words, instruction_words) = self.heading_words();
+        let headings: Vec<ElementRef> = document.select(&self.selectors.heading).collect();
+        let find_heading = |words: &[&str]| {
+            headings.iter().copied().find(|heading| {
+                let text = heading.text().collect::<String>().trim().to_lowercase();
+                words.iter().any(|word| text.starts_with(word))
+            })
+        };
+
+        let ingredients = content_after(document, find_heading(&ingredient_words)?, true);
+        if ingredients.is_empty() {
+            return None;
+        }
+        let instructions = find_heading(&instruction_words)
+            .map(|heading| content_after(document, heading, false))
+            .unwrap_or_default();
+
+        let mut json = Map::new();
+        json.insert(LdFields::TYPE.into(), RECIPE_TYPE.into());
+        let name = headings
+            .iter()
+            .find(|heading| heading.value().name() == "h1")
+            .map(|heading| heading.text().collect::<String>())
+            .or_else(|| {
+                document
+                    .select(&self.selectors.title)
+                    .find_map(element_text)
+            });
+        if let Some(name) = name {
+            json.insert(LdFields::NAME.into(), name.into());
+        }
+        if let Some(description) = document
+            .select(&self.selectors.description)
+            .find_map(element_text)
+        {
+            json.insert(LdFields::DESCRIPTION.into(), description.into());
+        }
+        json.insert(LdFields::RECIPE_INGREDIENT.into(), ingredients.into());
+        json.insert(LdFields::RECIPE_INSTRUCTIONS.into(), instructions.into());
+        Some(json)
+    }
+
+    /// Returns the beginnings of ingredient and instruction headings for the locale
+    fn heading_words(&self) -> (Vec<&'static str>, Vec<&'static str>) {
+        let locales: &[(&[&str], &[&str])] = match self.locale {
+            Locale::Any => &[ENGLISH_HEADINGS, GERMAN_HEADINGS],
+            Locale::English => &[ENGLISH_HEADINGS],
+            Locale::German => &[GERMAN_HEADINGS],
+        };
+        let ingredients = locales.iter().flat_map(|(words, _)| *words).copied();
+        let instructions = locales.iter().flat_map(|(_, words)| *words).copied();
+        (ingredients.collect(), instructions.collect())
+    }
+}
+
+/// The beginnings of ingredient and instruction headings in English
+const ENGLISH_HEADINGS: (&[&str], &[&str]) = (
+    &["ingredients"],
+    &[
+        "instructions",
+        "directions",
+        "method",
+        "preparation",
+        "steps",
+    ],
+);
+
+/// The beginnings of ingredient and instruction headings in German
+const GERMAN_HEADINGS: (&[&str], &[&str]) = (
+    &["zutaten"],
+    &["zubereitung", "anleitung", "so geht", "schritte"],
+);
+
+/// Tries to recursively find a recipe by looking for the tag `"@type": Recipe`.
+fn find_recipe_in_value(value: Value, strictness: Strictness) -> Option<Map<String, Value>> {
+    match value {
+        Value::Object(obj) => {
+            if is_recipe_type(&obj, strictness) {
+                return Some(obj);
+            }
+            obj.into_iter()
+                .find_map(|(_, v)| find_recipe_in_value(v, strictness))
+        }
+        Value::Array(arr) => arr
+            .into_iter()
+            .find_map(|item| find_recipe_in_value(item, strictness)),
+        _ => None,
+    }
+}
+
+/// Verifies that the obj contains the tag `"@type": Recipe`, lenient parsing also accepts
+/// types with a schema.org prefix like "schema:Recipe".
+pub(crate) fn is_recipe_type(obj: &Map<String, Value>, strictness: Strictness) -> bool {
+    let matches = |type_str: &str| match strictness {
+        Strictness::Standard | Strictness::Strict => type_str == RECIPE_TYPE,
+        Strictness::Lenient => strip_schema_prefix(type_str) == RECIPE_TYPE,
+    };
+    match obj.get(LdFields::TYPE) {
+        Some(Value::String(s)) => matches(s),
+        Some(Value::Array(arr)) => arr
+            .iter()
+            .any(|t| matches!(t, Value::String(type_str) if matches(type_str))),
+        _ => false,
+    }
+}
+
+fn strip_schema_prefix(text: &str) -> &str {
+    SCHEMA_ORG_PREFIXES
+        .iter()
+        .find_map(|prefix| text.strip_prefix(prefix))
+        .unwrap_or(text)
+}
+
+/// Whether the recipe has a name, ingredients and instructions
+fn is_complete(recipe: &Recipe) -> bool {
+    recipe.name.as_deref().is_some_and(|name| !name.is_empty())
+        && !recipe.ingredient_sections.is_empty()
+        && !recipe.how_to_sections.is_empty()
+}
+
+/// Uses superseded properties if their replacement is missing, e.g. `ingredients` for
+/// `recipeIngredient`
+fn apply_aliases(json: &mut Map<String, Value>) {
+    if !json.contains_key(LdFields::RECIPE_INGREDIENT)
+        && let Some(ingredients) = json.remove(LdFields::INGREDIENTS)
+    {
+        json.insert(LdFields::RECIPE_INGREDIENT.into(), ingredients);
+    }
+}
+
+/// Escapes line breaks and tabs inside strings, which are not allowed in JSON but common
+/// in JSON-LD
+fn escape_control_characters(json: &str) -> String {
+    let mut result = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in json.chars() {
+        match c {
+            '"' if !escaped => in_string = !in_string,
+            '\n' if in_string => {
+                result.push_str("\\n");
+                continue;
+            }
+            '\r' if in_string => continue,
+            '\t' if in_string => {
+                result.push_str("\\t");
+                continue;
+            }
+            _ => {}
+        }
+        escaped = in_string && c == '\\' && !escaped;
+        result.push(c);
+    }
+    result
+}
+
+/// The attributes that mark items and their properties
+struct ItemAttributes {
+    /// The attribute marking an element as an item
+    scope: &'static str,
+    /// The attribute containing the type of an item
+    item_type: &'static str,
+    /// The attribute containing the property names of an element
+    property: &'static str,
+}
+
+const MICRODATA: ItemAttributes = ItemAttributes {
+    scope: "itemscope",
+    item_type: "itemtype",
+    property: "itemprop",
+};
+
+const RDFA: ItemAttributes = ItemAttributes {
+    scope: "typeof",
+    item_type: "typeof",
+    property: "property",
+};
+
+/// Converts a microdata or RDFa item to JSON-LD
+///
+/// It deals with:
+///     - `itemtype="https://schema.org/Recipe"` and `typeof="schema:Recipe"`
+///     - nested items, e.g. `<div itemprop="author" itemscope itemtype="Person">`
+///     - properties repeated or with multiple names, which become arrays
+fn item_to_json(element: ElementRef, attributes: &ItemAttributes) -> Map<String, Value> {
+    let mut json = Map::new();
+    let types: Vec<Value> = element
+        .value()
+        .attr(attributes.item_type)
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(|t| strip_schema_prefix(t).into())
+        .collect();
+    let item_type = match types.len() {
+        1 => types.into_iter().next().unwrap(),
+        _ => Value::Array(types),
+    };
+    json.insert(LdFields::TYPE.into(), item_type);
+    collect_properties(element, attributes, &mut json);
+    json
+}
+
+/// Adds the properties of the descendants that belong to the item, without going into
+/// nested items
+fn collect_properties(
+    element: ElementRef,
+    attributes: &ItemAttributes,
+    json: &mut Map<String, Value>,
+) {
+    for child in element.children().filter_map(ElementRef::wrap) {
+        let is_item = child.value().attr(attributes.scope).is_some();
+        if let Some(names) = child.value().attr(attributes.property) {
+            let value = if is_item {
+                Value::Object(item_to_json(child, attributes))
+            } else {
+                Value::String(property_value(child))
+            };
+            for name in names.split_whitespace() {
+                insert_property(json, strip_schema_prefix(name), value.clone());
+            }
+        }
+        if !is_item {
+            collect_properties(child, attributes, json);
+        }
+    }
+}
+
+/// Returns the value of a property element, e.g. the `content` of a `<meta>`, the
+/// `datetime` of a `<time>` or the inner HTML
+fn property_value(element: ElementRef) -> String {
+    let attr = |name| element.value().attr(name).map(str::to_string);
+    attr("content")
+        .or_else(|| match element.value().name() {
+            "a" | "link" | "area" => attr("href"),
+            "img" | "audio" | "video" | "source" | "embed" | "iframe" => attr("src"),
+            "time" => attr("datetime"),
+            "data" | "meter" => attr("value"),
+            "object" => attr("data"),
+            _ => None,
+        })
+        .unwrap_or_else(|| element.inner_html().trim().to_string())
+}
+
+/// Inserts the value, turning the property into an array if it already exists
+fn insert_property(json: &mut Map<String, Value>, name: &str, value: Value) {
+    match json.get_mut(name) {
+        Some(Value::Array(values)) => values.push(value),
+        Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+        None => {
+            json.insert(name.into(), value);
+        }
+    }
+}
+
+/// Returns the `content` of a meta element or the text of any other element
+fn element_text(element: ElementRef) -> Option<String> {
+    let text = match element.value().attr("content") {
+        Some(content) => content.to_string(),
+        None => element.text().collect(),
+    };
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Returns the list items following the heading up to the next heading of the same or a
+/// higher level
+///
+/// Lower level headings become ingredient headings like "Dough:", if there are no lists
+/// the paragraphs are returned instead.
+fn content_after(document: &Html, heading: ElementRef, ingredients: bool) -> Vec<String> {
+    let level = heading_level(heading.value().name()).unwrap_or(6);
+    let mut after = false;
+    let mut lists = Vec::new();
+    let mut items = Vec::new();
+    let mut paragraphs = Vec::new();
+
+    for node in document.tree.root().descendants() {
+        if node.id() == heading.id() {
+            after = true;
+            continue;
+        }
+        if !after
+            || node
+                .ancestors()
+                .any(|a| a.id() == heading.id() || lists.contains(&a.id()))
+        {
+            continue;
+        }
+        let Some(element) = ElementRef::wrap(node) else {
+            continue;
+        };
+        let name = element.value().name();
+        if let Some(other) = heading_level(name) {
+            if other <= level {
+                break;
+            }
+            if ingredients && let Some(text) = element_text(element) {
+                items.push(format!("{}:", text.trim_end_matches(':')));
+            }
+            continue;
+        }
+        match name {
+            "ul" | "ol" => {
+                lists.push(node.id());
+                items.extend(
+                    element
+                        .children()
+                        .filter_map(ElementRef::wrap)
+                        .filter(|child| child.value().name() == "li")
+                        .map(|li| li.inner_html().trim().to_string())
+                        .filter(|item| !item.is_empty()),
+                );
+            }
+            "p" if !ingredients => {
+                let text = element.inner_html().trim().to_string();
+                if !text.is_empty() {
+                    paragraphs.push(text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if lists.is_empty() { paragraphs } else { items }
+}
+
+fn heading_level(name: &str) -> Option<u8> {
+    match name.as_bytes() {
+        [b'h', level @ b'1'..=b'6'] => Some(level - b'0'),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HowToSection, IngredientSection};
+
+    fn steps(recipe: &Recipe) -> Vec<&str> {
+        recipe
+            .how_to_sections
+            .iter()
+            .flat_map(|section| section.steps.iter().map(String::as_str))
+            .collect()
+    }
+
+    mod json_ld {
+        use super::*;
+
+        #[test]
+        fn prefixed_type() {
+            let html = r#"<script type="application/ld+json">
+                {"@type": "schema:Recipe", "name": "Soup"}
+            </script>"#;
+            let lenient = RecipeParser::new().with_strictness(Strictness::Lenient);
+            let recipe = lenient.parse(html).unwrap();
+            assert_eq!(recipe.name.as_deref(), Some("Soup"));
+            assert!(RecipeParser::new().parse(html).is_none());
+        }
+
+        #[test]
+        fn line_breaks_in_strings() {
+            let html = "<script type=\"application/ld+json\">
+                {\"@type\": \"Recipe\", \"name\": \"Soup\", \"recipeInstructions\": \"Boil.\nServe.\"}
+            </script>";
+            let lenient = RecipeParser::new().with_strictness(Strictness::Lenient);
+            let recipe = lenient.parse(html).unwrap();
+            assert_eq!(steps(&recipe), vec!["Boil.", "Serve."]);
+            assert!(RecipeParser::new().parse(html).is_none());
+        }
+
+        #[test]
+        fn superseded_ingredients() {
+            let html = r#"<script type="application/ld+json">
+                {"@type": "Recipe", "ingredients": ["1 egg"]}
+            </script>"#;
+            let lenient = RecipeParser::new().with_strictness(Strictness::Lenient);
+            let recipe = lenient.parse(html).unwrap();
+            assert_eq!(recipe.ingredients().collect::<Vec<_>>(), vec!["1 egg"]);
+            let recipe = RecipeParser::new().parse(html).unwrap();
+            assert!(recipe.ingredient_sections.is_empty());
+        }
+
+        #[test]
+        fn strict_requires_fields() {
+            let html = r#"<script type="application/ld+json">
+                {"@type": "Recipe", "name": "Soup", "recipeIngredient": ["Water"]}
+            </script>"#;
+            let strict = RecipeParser::new().with_strictness(Strictness::Strict);
+            assert!(strict.parse(html).is_none());
+            assert!(RecipeParser::new().parse(html).is_some());
+        }
+    }
+
+    mod microdata {
+        use super::*;
+
+        const HTML: &str = r#"
+            <div itemscope itemtype="https://schema.org/Recipe">
+                <h1 itemprop="name">Pancakes</h1>
+                <span itemprop="author" itemscope itemtype="https://schema.org/Person">
+                    <span itemprop="name">Jane</span>
+                </span>
+                <meta itemprop="prepTime" content="PT10M">
+                <ul>
+                    <li itemprop="recipeIngredient">2 eggs</li>
+                    <li itemprop="recipeIngredient">200 g <b>flour</b></li>
+                </ul>
+                <ol itemprop="recipeInstructions">
+                    <li>Mix.</li>
+                    <li>Fry.</li>
+                </ol>
+            </div>
+        "#;
+
+        #[test]
+        fn parse_item() {
+            let recipe = RecipeParser::new()
+                .with_microdata(true)
+                .parse(HTML)
+                .unwrap();
+            assert_eq!(recipe.name.as_deref(), Some("Pancakes"));
+            assert_eq!(recipe.author.as_deref(), Some("Jane"));
+            assert_eq!(recipe.prep_time.as_deref(), Some("PT10M"));
+            assert_eq!(
+                recipe.ingredient_sections,
+                vec![IngredientSection {
+                    name: None,
+                    ingredients: vec!["2 eggs".into(), "200 g flour".into()],
+                }]
+            );
+            assert_eq!(steps(&recipe), vec!["Mix.", "Fry."]);
+        }
+
+        #[test]
+        fn nested_steps() {
+            let html = r#"
+                <div itemscope itemtype="http://schema.org/Recipe">
+                    <div itemprop="recipeInstructions" itemscope itemtype="http://schema.org/HowToStep">
+                        <p itemprop="text">Mix.</p>
+                    </div>
+                    <div itemprop="recipeInstructions" itemscope itemtype="http://schema.org/HowToStep">
+                        <p itemprop="text">Fry.</p>
+                    </div>
+                </div>
+            "#;
+            let recipe = RecipeParser::new()
+                .with_microdata(true)
+                .parse(html)
+                .unwrap();
+            assert_eq!(steps(&recipe), vec!["Mix.", "Fry."]);
+        }
+
+        #[test]
+        fn disabled_by_default() {
+            assert!(RecipeParser::new().parse(HTML).is_none());
+        }
+    }
+
+    mod rdfa {
+        use super::*;
+
+        #[test]
+        fn parse_item() {
+            let html = r#"
+                <div vocab="https://schema.org/" typeof="Recipe">
+                    <h1 property="name">Soup</h1>
+                    <div property="author" typeof="Person"><span property="name">Jane</span></div>
+                    <span property="recipeIngredient">1 l water</span>
+                    <span property="schema:recipeIngredient">1 onion</span>
+                    <p property="recipeInstructions">Boil everything.</p>
+                </div>
+            "#;
+            let recipe = RecipeParser::new().with_rdfa(true).parse(html).unwrap();
+            assert_eq!(recipe.name.as_deref(), Some("Soup"));
+            assert_eq!(recipe.author.as_deref(), Some("Jane"));
+            assert_eq!(
+                recipe.ingredients().collect::<Vec<_>>(),
+                vec!["1 l water", "1 onion"]
+            );
+            assert_eq!(steps(&recipe), vec!["Boil everything."]);
+            assert!(RecipeParser::new().parse(html).is_none());
+        }
+    }
+
+    mod heuristics {
+        use super::*;
+
+        const HTML: &str = r#"
+            <html>
+            <head>
+                <title>Best Pancakes | Example</title>
+                <meta name="description" content="Fluffy pancakes.">
+            </head>
+            <body>
+                <h2>Ingredients for 4 pancakes</h2>
+                <h3>Batter</h3>
+                <ul><li>2 eggs</li><li>200 g flour</li></ul>
+                <h3>Topping</h3>
+                <ul><li>Maple syrup</li></ul>
+                <h2>Instructions:</h2>
+                <p>Mix the batter.</p>
+                <p>Fry the pancakes.</p>
+                <h2>Comments</h2>
+                <p>Great!</p>
+            </body>
+            </html>
+        "#;
+
+        #[test]
+        fn guess_recipe() {
+            let recipe = RecipeParser::new()
+                .with_heuristics(true)
+                .parse(HTML)
+                .unwrap();
+            assert_eq!(recipe.name.as_deref(), Some("Best Pancakes | Example"));
+            assert_eq!(recipe.description.as_deref(), Some("Fluffy pancakes."));
+            assert_eq!(
+                recipe.ingredient_sections,
+                vec![
+                    IngredientSection {
+                        name: Some("Batter".into()),
+                        ingredients: vec!["2 eggs".into(), "200 g flour".into()],
+                    },
+                    IngredientSection {
+                        name: Some("Topping".into()),
+                        ingredients: vec!["Maple syrup".into()],
+                    },
+                ]
+            );
+            assert_eq!(
+                recipe.how_to_sections,
+                vec![HowToSection {
+                    name: None,
+                    steps: vec!["Mix the batter.".into(), "Fry the pancakes.".into()],
+                }]
+            );
+        }
+
+        #[test]
+        fn disabled_by_default() {
+            assert!(RecipeParser::new().parse(HTML).is_none());
+            assert!(
+                RecipeParser::new()
+                    .with_heuristics(true)
+                    .with_strictness(Strictness::Strict)
+                    .parse(HTML)
+                    .is_none()
+            );
+        }
+
+        #[test]
+        fn locale() {
+            let html = r#"
+                <h1>Pfannkuchen</h1>
+                <h2>Zutaten</h2>
+                <ul><li>2 Eier</li></ul>
+                <h2>Zubereitung</h2>
+                <ol><li>Verrühren.</li></ol>
+            "#;
+            let parser = RecipeParser::new().with_heuristics(true);
+            let recipe = parser.clone().parse(html).unwrap();
+            assert_eq!(recipe.name.as_deref(), Some("Pfannkuchen"));
+            assert_eq!(steps(&recipe), vec!["Verrühren."]);
+            assert!(parser.with_locale(Locale::English).parse(html).is_none());
+        }
+    }
+
+    mod options {
+        use super::*;
+
+        const HTML: &str = r#"<script type="application/ld+json">
+            {
+                "@type": "Recipe",
+                "name": "Cr\u00c3\u00a8me",
+                "recipeIngredient": ["For the dough:", "200 g flour"]
+            }
+        </script>"#;
+
+        #[test]
+        fn without_ingredient_sections() {
+            let recipe = RecipeParser::new()
+                .with_ingredient_sections(false)
+                .parse(HTML)
+                .unwrap();
+            assert_eq!(
+                recipe.ingredient_sections,
+                vec![IngredientSection {
+                    name: None,
+                    ingredients: vec!["For the dough:".into(), "200 g flour".into()],
+                }]
+            );
+        }
+
+        #[test]
+        fn without_normalizer() {
+            let parser = RecipeParser::new();
+            assert_eq!(parser.parse(HTML).unwrap().name.as_deref(), Some("Crème"));
+            let recipe = parser.without_normalizer().parse(HTML).unwrap();
+            assert_eq!(recipe.name.as_deref(), Some("CrÃ¨me"));
+        }
+
+        #[test]
+        fn disabled_json_ld() {
+            assert!(
+                RecipeParser::new()
+                    .with_json_ld(false)
+                    .parse(HTML)
+                    .is_none()
+            );
+        }
+    }
+
+    #[test]
+    fn escape_line_breaks() {
+        assert_eq!(
+            escape_control_characters("{\"a\": \"x\ny\\\"\tz\",\n\"b\": 1}"),
+            "{\"a\": \"x\\ny\\\"\\tz\",\n\"b\": 1}"
+        );
+    }
+}
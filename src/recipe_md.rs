@@ -0,0 +1,718 @@
+use crate::ingredient::{parse_amount, parse_unit};
+use crate::{HowToSection, Ingredient, IngredientSection, Recipe};
+
+use std::fmt::Write;
+
+/// A builder to convert a recipe to [RecipeMD](https://recipemd.org/specification.html)
+///
+/// Unlike the free-form markdown of the [MarkdownBuilder](crate::MarkdownBuilder), the
+/// output follows the structure RecipeMD tools expect: the title, the description, the
+/// tags in italics, the yield in bold and the ingredients between two horizontal rules,
+/// with the amounts in italics and named ingredient sections as groups. Unnamed ingredient
+/// sections come first, as RecipeMD has no way to end a group. Characters at the start of
+/// a line that would turn free text into a heading, a list, emphasis or a thematic break
+/// are escaped.
+///
+/// ## Example
+///
+/// ```
+/// let recipe = reget::Recipe::default();
+/// recipe
+///     .to_recipe_md()
+///     .with_tags(&["breakfast", "vegetarian"])
+///     .with_default_section("Preparation")
+///     .convert();
+/// ```
+///
+/// <details>
+/// <summary>Example Output</summary>
+///
+/// ```text
+/// # Pancakes
+///
+/// Fluffy pancakes.
+///
+/// *breakfast, vegetarian*
+///
+/// **4 servings**
+///
+/// ---
+///
+/// - *2* eggs
+/// - *250 ml* milk
+///
+/// ## Topping
+///
+/// - maple syrup
+///
+/// ---
+///
+/// Whisk the eggs and the milk.
+///
+/// ## Frying
+///
+/// Fry in a pan.
+/// ```
+/// </details>
+///
+pub struct RecipeMdBuilder<'a> {
+    /// The recipe that is being converted
+    recipe: &'a Recipe,
+    /// The tags written below the description
+    tags: &'a [&'a str],
+    /// The name being used if the recipe does not have a name
+    default_recipe_name: &'a str,
+    /// The name being used if a how to section other than the first does not have a name
+    default_section_name: &'a str,
+    /// The output string being built
+    result: String,
+}
+
+impl<'a> RecipeMdBuilder<'a> {
+    const HORIZONTAL_RULE: &'static str = "---";
+
+    /// Constructs a new RecipeMdBuilder for a [recipe](Recipe)
+    pub fn from(recipe: &'a Recipe) -> Self {
+        RecipeMdBuilder {
+            recipe,
+            tags: &[],
+            default_recipe_name: "Recipe",
+            default_section_name: "Instructions",
+            result: String::new(),
+        }
+    }
+
+    /// Adds tags to be written below the description
+    pub fn with_tags(mut self, tags: &'a [&'a str]) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Uses the name if the recipe does not have a name included, as RecipeMD requires a
+    /// title, default is *Recipe*
+    pub fn with_default_name(mut self, name: &'a str) -> Self {
+        self.default_recipe_name = name;
+        self
+    }
+
+    /// Uses the name for any section but the first that does not have a name, default is
+    /// *Instructions*
+    pub fn with_default_section(mut self, name: &'a str) -> Self {
+        self.default_section_name = name;
+        self
+    }
+
+    /// Performs the conversion
+    pub fn convert(mut self) -> String {
+        self.put_name();
+        self.put_description();
+        self.put_tags_yield();
+        self.put_ingredients();
+        self.put_instructions();
+        self.result
+    }
+
+    /// Writes recipe name or the default name as the title
+    fn put_name(&mut self) {
+        let name = self
+            .recipe
+            .name
+            .as_deref()
+            .unwrap_or(self.default_recipe_name);
+        writeln!(self.result, "# {name}").unwrap();
+    }
+
+    /// Writes the description, if there is one
+    fn put_description(&mut self) {
+        if let Some(description) = &self.recipe.description {
+            writeln!(self.result).unwrap();
+            writeln!(self.result, "{}", escape_lines(description)).unwrap();
+        }
+    }
+
+    /// Writes the tags in italics and the yield in bold, if there are any
+    fn put_tags_yield(&mut self) {
+        if !self.tags.is_empty() {
+            writeln!(self.result).unwrap();
+            writeln!(self.result, "*{}*", escape(&self.tags.join(", "))).unwrap();
+        }
+        if let Some(recipe_yield) = &self.recipe.recipe_yield {
+            writeln!(self.result).unwrap();
+            writeln!(self.result, "**{}**", escape(recipe_yield)).unwrap();
+        }
+    }
+
+    /// Writes the ingredients after the first horizontal rule, named sections as groups
+    ///
+    /// Unnamed sections are written first, as they would be read as part of the group
+    /// before them otherwise.
+    fn put_ingredients(&mut self) {
+        writeln!(self.result).unwrap();
+        writeln!(self.result, "{}", Self::HORIZONTAL_RULE).unwrap();
+        let sections = &self.recipe.ingredient_sections;
+        let unnamed = sections.iter().filter(|section| section.name.is_none());
+        let named = sections.iter().filter(|section| section.name.is_some());
+        for section in unnamed.chain(named) {
+            if let Some(name) = &section.name {
+                writeln!(self.result).unwrap();
+                writeln!(self.result, "## {name}").unwrap();
+            }
+            if !section.ingredients.is_empty() {
+                writeln!(self.result).unwrap();
+            }
+            for ingredient in &section.ingredients {
+                match split_amount(ingredient) {
+                    (Some(amount), name) => {
+                        writeln!(self.result, "- *{}* {name}", escape(amount)).unwrap()
+                    }
+                    (None, name) => writeln!(self.result, "- {}", escape_line_start(name)).unwrap(),
+                }
+            }
+        }
+    }
+
+    /// Writes the steps as paragraphs after the second horizontal rule, if there are any
+    ///
+    /// Every section but an unnamed first one gets a heading.
+    fn put_instructions(&mut self) {
+        if self.recipe.steps().next().is_none() {
+            return;
+        }
+        writeln!(self.result).unwrap();
+        writeln!(self.result, "{}", Self::HORIZONTAL_RULE).unwrap();
+        for (i, section) in self.recipe.how_to_sections.iter().enumerate() {
+            let name = match &section.name {
+                Some(name) => Some(name.as_str()),
+                None if i > 0 => Some(self.default_section_name),
+                None => None,
+            };
+            if let Some(name) = name {
+                writeln!(self.result).unwrap();
+                writeln!(self.result, "## {name}").unwrap();
+            }
+            for step in &section.steps {
+                writeln!(self.result).unwrap();
+                writeln!(self.result, "{}", escape_lines(step)).unwrap();
+            }
+        }
+    }
+}
+
+/// Splits an ingredient into its amount including the unit and the rest, e.g.
+/// "1 1/2 cups of milk" into "1 1/2 cups" and "of milk"
+fn split_amount(ingredient: &str) -> (Option<&str>, &str) {
+    let ingredient = ingredient.trim();
+    let Some((_, rest)) = parse_amount(ingredient) else {
+        return (None, ingredient);
+    };
+    let rest = parse_unit(rest).map_or(rest, |(_, rest)| rest);
+    let amount = ingredient[..ingredient.len() - rest.len()].trim_end();
+    match rest.trim() {
+        "" => (None, ingredient),
+        rest => (Some(amount), rest),
+    }
+}
+
+/// Escapes the characters that would end the emphasis around amounts, tags and yields
+fn escape(text: &str) -> String {
+    text.replace('*', "\\*")
+}
+
+/// Escapes the start of every line of a free text, see [escape_line_start]
+fn escape_lines(text: &str) -> String {
+    text.split('\n')
+        .map(escape_line_start)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Escapes the start of a line that would make it a heading, a list item, emphasis or a
+/// thematic break, e.g. "# 1" -> "\# 1", "---" -> "\---" or "1. Heat" -> "1\. Heat"
+fn escape_line_start(line: &str) -> String {
+    let text = line.trim_start();
+    let indent = &line[..line.len() - text.len()];
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if text.starts_with(LINE_START_MARKERS) {
+        format!("{indent}\\{text}")
+    } else if digits > 0 && text[digits..].starts_with(['.', ')']) {
+        format!("{indent}{}\\{}", &text[..digits], &text[digits..])
+    } else {
+        line.to_string()
+    }
+}
+
+/// Removes the escapes of [escape_line_start] from every line of a free text
+fn unescape_lines(text: &str) -> String {
+    text.split('\n')
+        .map(unescape_line_start)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Removes the escape of [escape_line_start], e.g. "\# 1" -> "# 1"
+fn unescape_line_start(line: &str) -> String {
+    let text = line.trim_start();
+    let indent = &line[..line.len() - text.len()];
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if let Some(rest) = text.strip_prefix('\\')
+        && rest.starts_with(LINE_START_MARKERS)
+    {
+        format!("{indent}{rest}")
+    } else if digits > 0
+        && let Some(rest) = text[digits..].strip_prefix('\\')
+        && rest.starts_with(['.', ')'])
+    {
+        format!("{indent}{}{rest}", &text[..digits])
+    } else {
+        line.to_string()
+    }
+}
+
+/// Characters that start a heading, a list item, emphasis or a thematic break
+const LINE_START_MARKERS: &[char] = &['#', '*', '_', '-', '+'];
+
+/// Parses a [RecipeMD](https://recipemd.org/specification.html) recipe
+///
+/// Returns None if the text has no title or no horizontal rule before the ingredients,
+/// which RecipeMD requires.
+///
+/// It deals with:
+///     - the title as `# Title` or underlined with `===`
+///     - the description, tags in italics and the yield in bold, which can be given in
+///       any order
+///     - ingredients as list items with or without an amount in italics, like
+///       `- *2 cups* flour` or `- salt`
+///     - groups of ingredients and sections of instructions as headings of any level
+///     - links in ingredients, like `- *1* [pizza dough](dough.md)`
+///
+/// The tags are not part of the [recipe](Recipe) and skipped. Yields are joined by commas
+/// and every paragraph of the instructions becomes a step.
+///
+/// ## Example
+///
+/// ```
+/// use reget::parse_recipe_md;
+///
+/// let recipe = parse_recipe_md(
+///     "# Pancakes\n\n*breakfast*\n\n**4 servings**\n\n---\n\n\
+///      - *2* eggs\n- *250 ml* milk\n\n---\n\nWhisk and fry.",
+/// )
+/// .unwrap();
+/// assert_eq!(recipe.recipe_yield.as_deref(), Some("4 servings"));
+/// assert_eq!(recipe.ingredients().collect::<Vec<_>>(), vec!["2 eggs", "250 ml milk"]);
+/// assert_eq!(recipe.how_to_sections[0].steps, vec!["Whisk and fry."]);
+/// ```
+pub fn parse_recipe_md(text: &str) -> Option<Recipe> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut position = lines.iter().position(|line| !line.trim().is_empty())?;
+
+    let name = match heading(lines[position]) {
+        Some((1, name)) => name,
+        _ if is_title_underline(lines.get(position + 1).copied()) => {
+            position += 1;
+            lines[position - 1].trim().to_string()
+        }
+        _ => return None,
+    };
+    let mut recipe = Recipe {
+        name: Some(name),
+        ..Default::default()
+    };
+    position += 1;
+
+    let rule = (position..lines.len()).find(|&i| is_rule(&lines, i))?;
+    let mut description = Vec::new();
+    let mut yields = Vec::new();
+    for paragraph in paragraphs(&lines[position..rule]) {
+        if let Some(inner) = strip_emphasis(&paragraph, "**").or(strip_emphasis(&paragraph, "__")) {
+            yields.push(inner);
+        } else if strip_emphasis(&paragraph, "*")
+            .or(strip_emphasis(&paragraph, "_"))
+            .is_none()
+        {
+            description.push(unescape_lines(&paragraph));
+        }
+    }
+    recipe.description = (!description.is_empty()).then(|| description.join("\n\n"));
+    recipe.recipe_yield = (!yields.is_empty()).then(|| yields.join(", "));
+
+    let end = (rule + 1..lines.len())
+        .find(|&i| is_rule(&lines, i))
+        .unwrap_or(lines.len());
+    recipe.ingredient_sections = parse_ingredients(&lines[rule + 1..end]);
+    if end < lines.len() {
+        recipe.how_to_sections = parse_instructions(&lines[end + 1..]);
+    }
+    Some(recipe)
+}
+
+/// Parses the list items of the ingredient block, headings start a new group
+fn parse_ingredients(lines: &[&str]) -> Vec<IngredientSection> {
+    let mut sections = vec![IngredientSection::default()];
+    for line in lines {
+        if let Some((_, name)) = heading(line) {
+            sections.push(IngredientSection {
+                name: Some(name),
+                ingredients: Vec::new(),
+            });
+        } else if let Some(item) = list_item(line) {
+            let section = sections.last_mut().expect("there is always a section");
+            section.ingredients.push(ingredient(item));
+        } else if line.starts_with([' ', '\t'])
+            && !line.trim().is_empty()
+            && let Some(last) = sections.last_mut().and_then(|s| s.ingredients.last_mut())
+        {
+            // The continuation of a list item
+            last.push(' ');
+            last.push_str(line.trim());
+        }
+    }
+    sections.retain(|section| section.name.is_some() || !section.ingredients.is_empty());
+    sections
+}
+
+/// Parses an ingredient list item, e.g. "*2 cups* [flour](flour.md)" to "2 cups flour"
+fn ingredient(item: &str) -> Ingredient {
+    let name = remove_links(item.trim());
+    for marker in ["*", "_"] {
+        if let Some(rest) = name.strip_prefix(marker)
+            && let Some(end) = unescaped_find(rest, marker)
+        {
+            let amount = unescape(rest[..end].trim());
+            return format!("{amount} {}", rest[end + 1..].trim())
+                .trim()
+                .to_string();
+        }
+    }
+    unescape_line_start(&name)
+}
+
+/// Parses the instructions, every paragraph is a step and headings start a new section
+fn parse_instructions(lines: &[&str]) -> Vec<HowToSection> {
+    let mut sections = vec![HowToSection::default()];
+    let mut start = 0;
+    for (i, line) in lines.iter().enumerate().chain([(lines.len(), &"# ")]) {
+        if let Some((_, name)) = heading(line) {
+            let section = sections.last_mut().expect("there is always a section");
+            let steps = paragraphs(&lines[start..i]);
+            section
+                .steps
+                .extend(steps.iter().map(|step| unescape_lines(step)));
+            if i < lines.len() {
+                sections.push(HowToSection {
+                    name: Some(name),
+                    steps: Vec::new(),
+                });
+            }
+            start = i + 1;
+        }
+    }
+    sections.retain(|section| section.name.is_some() || !section.steps.is_empty());
+    sections
+}
+
+/// Returns the level and the text of an ATX heading, e.g. "## Sauce"
+fn heading(line: &str) -> Option<(usize, String)> {
+    let line = line.trim();
+    let level = line.len() - line.trim_start_matches('#').len();
+    let text = &line[level..];
+    if !(1..=6).contains(&level) || !(text.is_empty() || text.starts_with([' ', '\t'])) {
+        return None;
+    }
+    let text = text.trim().trim_end_matches('#').trim_end();
+    Some((level, text.to_string()))
+}
+
+/// Checks whether the line is a thematic break, e.g. "---", "***" or "- - -"
+///
+/// A line of dashes directly below a paragraph underlines a heading instead.
+fn is_rule(lines: &[&str], i: usize) -> bool {
+    let line: String = lines[i].chars().filter(|c| !c.is_whitespace()).collect();
+    let Some(first) = line.chars().next() else {
+        return false;
+    };
+    let thematic = "-*_".contains(first) && line.len() >= 3 && line.chars().all(|c| c == first);
+    let below_paragraph = i > 0 && !lines[i - 1].trim().is_empty();
+    thematic && !(first == '-' && below_paragraph)
+}
+
+/// Checks whether the line underlines a title, e.g. "====="
+fn is_title_underline(line: Option<&str>) -> bool {
+    line.map(str::trim)
+        .is_some_and(|line| !line.is_empty() && line.chars().all(|c| c == '='))
+}
+
+/// Returns the text of a bullet or numbered list item
+fn list_item(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    if let Some(item) = line.strip_prefix(['-', '*', '+'])
+        && (item.is_empty() || item.starts_with([' ', '\t']))
+    {
+        return Some(item.trim());
+    }
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let item = line[digits..].strip_prefix(['.', ')'])?;
+    (digits > 0 && item.starts_with([' ', '\t'])).then(|| item.trim())
+}
+
+/// Splits the lines into paragraphs at blank lines, keeping the lines of a paragraph
+fn paragraphs(lines: &[&str]) -> Vec<String> {
+    lines
+        .split(|line| line.trim().is_empty())
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| paragraph.join("\n").trim().to_string())
+        .collect()
+}
+
+/// Returns the text inside the emphasis if the whole paragraph is emphasized
+fn strip_emphasis(paragraph: &str, marker: &str) -> Option<String> {
+    let inner = paragraph.strip_prefix(marker)?.strip_suffix(marker)?;
+    let emphasized = !inner.is_empty()
+        && !inner.starts_with(marker)
+        && !inner.ends_with(['*', '_'])
+        && unescaped_find(inner, marker).is_none();
+    emphasized.then(|| unescape(inner.trim()))
+}
+
+/// Finds the marker in the text, skipping escaped ones
+fn unescaped_find(text: &str, marker: &str) -> Option<usize> {
+    text.match_indices(marker)
+        .map(|(i, _)| i)
+        .find(|&i| !text[..i].ends_with('\\'))
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("\\*", "*").replace("\\_", "_")
+}
+
+/// Replaces markdown links with their text, e.g. "[dough](dough.md)" with "dough"
+fn remove_links(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find('[') {
+        let link = rest[open..].find("](").and_then(|middle| {
+            let close = rest[open + middle..].find(')')?;
+            Some((open + middle, open + middle + close))
+        });
+        let Some((middle, close)) = link else {
+            break;
+        };
+        result.push_str(&rest[..open]);
+        result.push_str(&rest[open + 1..middle]);
+        rest = &rest[close + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Recipe {
+        Recipe {
+            name: Some("Pancakes".into()),
+            description: Some("Fluffy pancakes.\n\nBest with *fresh* berries.".into()),
+            recipe_yield: Some("4 servings".into()),
+            ingredient_sections: vec![
+                IngredientSection {
+                    name: None,
+                    ingredients: vec!["2 eggs".into(), "1 1/2 cups of milk".into(), "salt".into()],
+                },
+                IngredientSection {
+                    name: Some("Topping".into()),
+                    ingredients: vec!["maple syrup".into()],
+                },
+            ],
+            how_to_sections: vec![
+                HowToSection {
+                    name: None,
+                    steps: vec!["Whisk the eggs and the milk.".into()],
+                },
+                HowToSection {
+                    name: Some("Frying".into()),
+                    steps: vec!["1. Heat a pan.\n2. Fry.".into(), "Serve.".into()],
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    mod builder {
+        use super::*;
+
+        #[test]
+        fn write_structure() {
+            assert_eq!(
+                example().to_recipe_md().with_tags(&["breakfast"]).convert(),
+                "# Pancakes\n\n\
+                 Fluffy pancakes.\n\nBest with *fresh* berries.\n\n\
+                 *breakfast*\n\n\
+                 **4 servings**\n\n\
+                 ---\n\n\
+                 - *2* eggs\n\
+                 - *1 1/2 cups* of milk\n\
+                 - salt\n\n\
+                 ## Topping\n\n\
+                 - maple syrup\n\n\
+                 ---\n\n\
+                 Whisk the eggs and the milk.\n\n\
+                 ## Frying\n\n\
+                 1\\. Heat a pan.\n2\\. Fry.\n\n\
+                 Serve.\n"
+            );
+        }
+
+        #[test]
+        fn write_without_instructions() {
+            let recipe = Recipe {
+                ingredient_sections: vec![IngredientSection {
+                    name: None,
+                    ingredients: vec!["2 eggs".into()],
+                }],
+                ..Default::default()
+            };
+            assert_eq!(
+                recipe.to_recipe_md().with_default_name("Eggs").convert(),
+                "# Eggs\n\n---\n\n- *2* eggs\n"
+            );
+        }
+
+        #[test]
+        fn split_amounts() {
+            assert_eq!(split_amount("2 cups flour"), (Some("2 cups"), "flour"));
+            assert_eq!(split_amount("1 ½ tsp salt"), (Some("1 ½ tsp"), "salt"));
+            assert_eq!(split_amount("3 large eggs"), (Some("3"), "large eggs"));
+            assert_eq!(split_amount("salt to taste"), (None, "salt to taste"));
+            assert_eq!(split_amount("4"), (None, "4"));
+        }
+    }
+
+    mod parser {
+        use super::*;
+
+        #[test]
+        fn round_trip() {
+            let recipe = example();
+            let text = recipe.to_recipe_md().with_tags(&["breakfast"]).convert();
+            assert_eq!(parse_recipe_md(&text), Some(recipe));
+        }
+
+        #[test]
+        fn round_trip_markdown_in_free_text() {
+            let recipe = Recipe {
+                name: Some("Chili".into()),
+                description: Some("*A classic.*\n\n**Spicy**\n---\n- not a list".into()),
+                ingredient_sections: vec![IngredientSection {
+                    name: None,
+                    ingredients: vec![
+                        "_optional_ chili flakes".into(),
+                        "* chopped herbs".into(),
+                        "# 5 nails".into(),
+                        "2 *large* onions".into(),
+                    ],
+                }],
+                how_to_sections: vec![HowToSection {
+                    name: None,
+                    steps: vec![
+                        "# Prepare the beans.".into(),
+                        "***".into(),
+                        "+ Add salt.\n3) Stir.".into(),
+                        "_Serve_ hot.".into(),
+                    ],
+                }],
+                ..Default::default()
+            };
+            let text = recipe.to_recipe_md().convert();
+            assert_eq!(parse_recipe_md(&text), Some(recipe));
+        }
+
+        #[test]
+        fn round_trip_unnamed_group_after_named() {
+            let recipe = Recipe {
+                name: Some("Cake".into()),
+                ingredient_sections: vec![
+                    IngredientSection {
+                        name: Some("Frosting".into()),
+                        ingredients: vec!["100 g butter".into()],
+                    },
+                    IngredientSection {
+                        name: None,
+                        ingredients: vec!["200 g flour".into()],
+                    },
+                ],
+                ..Default::default()
+            };
+            let parsed = parse_recipe_md(&recipe.to_recipe_md().convert()).unwrap();
+            assert_eq!(
+                parsed.ingredient_sections,
+                vec![
+                    recipe.ingredient_sections[1].clone(),
+                    recipe.ingredient_sections[0].clone(),
+                ]
+            );
+        }
+
+        #[test]
+        fn parse_specification_example() {
+            let recipe = parse_recipe_md(
+                "Guacamole\n\
+                 =========\n\n\
+                 Some people call it guac.\n\n\
+                 _sauce, vegan_\n\n\
+                 __4 Servings, 200g__\n\n\
+                 ***\n\n\
+                 * *1* avocado\n\
+                 * *.5 teaspoon* salt\n\
+                 * *1 1/2 pinches* [red pepper flakes](flakes.md)\n\n\
+                 ### Garnish\n\n\
+                 1. *1* lime,\n   juiced\n\n\
+                 - - -\n\n\
+                 Remove flesh from avocado and roughly mash with fork.\n\
+                 Season to taste.",
+            )
+            .unwrap();
+            assert_eq!(recipe.name.as_deref(), Some("Guacamole"));
+            assert_eq!(
+                recipe.description.as_deref(),
+                Some("Some people call it guac.")
+            );
+            assert_eq!(recipe.recipe_yield.as_deref(), Some("4 Servings, 200g"));
+            assert_eq!(
+                recipe.ingredient_sections,
+                vec![
+                    IngredientSection {
+                        name: None,
+                        ingredients: vec![
+                            "1 avocado".into(),
+                            ".5 teaspoon salt".into(),
+                            "1 1/2 pinches red pepper flakes".into(),
+                        ],
+                    },
+                    IngredientSection {
+                        name: Some("Garnish".into()),
+                        ingredients: vec!["1 lime, juiced".into()],
+                    },
+                ]
+            );
+            assert_eq!(
+                recipe.how_to_sections[0].steps,
+                vec!["Remove flesh from avocado and roughly mash with fork.\nSeason to taste."]
+            );
+        }
+
+        #[test]
+        fn reject_invalid() {
+            assert_eq!(parse_recipe_md("Just text\n\n---\n\n- *1* egg"), None);
+            assert_eq!(parse_recipe_md("# Title\n\n- *1* egg"), None);
+            assert_eq!(parse_recipe_md(""), None);
+        }
+
+        #[test]
+        fn setext_heading_in_description() {
+            let recipe = parse_recipe_md("# Title\n\nNotes\n---\n\n---\n\n- egg").unwrap();
+            assert_eq!(recipe.description.as_deref(), Some("Notes\n---"));
+            assert_eq!(recipe.ingredients().collect::<Vec<_>>(), vec!["egg"]);
+        }
+    }
+}
@@ -0,0 +1,368 @@
+//! Reports the data of a recipe that is ignored during extraction.
+
+use crate::constants::LdFields;
+use crate::validate::pointer;
+
+use serde_json::{Map, Value};
+
+/// Data of the recipe that was ignored, see [parse_recipe_with_report](crate::parse_recipe_with_report)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractionWarning {
+    /// The JSON pointer to the ignored value relative to the recipe, e.g. "/recipeIngredient/2".
+    pub path: String,
+    /// Why the value was ignored.
+    pub reason: String,
+    /// The beginning of the ignored value as JSON.
+    pub excerpt: String,
+}
+
+/// The maximum number of characters of an excerpt
+const EXCERPT_LENGTH: usize = 80;
+
+/// Properties of schema.org recipes that are known, but not extracted
+const IGNORED: &[&str] = &[
+    "about",
+    "aggregateRating",
+    "alternateName",
+    "articleBody",
+    "audience",
+    "comment",
+    "commentCount",
+    "contributor",
+    "cookingMethod",
+    "copyrightHolder",
+    "copyrightYear",
+    "countryOfOrigin",
+    "creator",
+    "dateCreated",
+    "dateModified",
+    "datePublished",
+    "editor",
+    "estimatedCost",
+    "headline",
+    "identifier",
+    "image",
+    "inLanguage",
+    "ingredients",
+    "interactionStatistic",
+    "isPartOf",
+    "keywords",
+    "license",
+    "mainEntityOfPage",
+    "performTime",
+    "potentialAction",
+    "publisher",
+    "recipeCategory",
+    "recipeCuisine",
+    "review",
+    "sameAs",
+    "step",
+    "supply",
+    "thumbnailUrl",
+    "url",
+    "video",
+    "wordCount",
+    "yield",
+];
+
+/// The properties of nutrition information that are extracted
+const NUTRITION_FIELDS: &[&str] = &[
+    LdFields::SERVING_SIZE,
+    LdFields::CALORIES,
+    LdFields::FAT_CONTENT,
+    LdFields::SATURATED_FAT_CONTENT,
+    LdFields::CARBOHYDRATE_CONTENT,
+    LdFields::SUGAR_CONTENT,
+    LdFields::FIBER_CONTENT,
+    LdFields::PROTEIN_CONTENT,
+    LdFields::SODIUM_CONTENT,
+];
+
+/// Returns the values of the recipe the extraction ignores, following the same rules as
+/// the `extract_*` functions
+pub(crate) fn inspect_recipe(json: &Map<String, Value>) -> Vec<ExtractionWarning> {
+    let mut inspector = Inspector::default();
+    for (key, value) in json {
+        let path = pointer("", key);
+        match key.as_str() {
+            LdFields::NAME
+            | LdFields::DESCRIPTION
+            | LdFields::PREP_TIME
+            | LdFields::COOK_TIME
+            | LdFields::TOTAL_TIME => {
+                if !value.is_string() {
+                    inspector.warn(&path, "Expected a text", value);
+                }
+            }
+            LdFields::AUTHOR => inspector.author(value, &path),
+            LdFields::RECIPE_INGREDIENT => inspector.ingredients(value, &path),
+            LdFields::RECIPE_INSTRUCTIONS => inspector.instructions(value, &path),
+            LdFields::SUITABLE_FOR_DIET => inspector.diets(value, &path),
+            LdFields::RECIPE_YIELD => inspector.recipe_yield(value, &path),
+            LdFields::NUTRITION => inspector.nutrition(value, &path),
+            LdFields::TOOL => inspector.tools(value, &path),
+            key if key.starts_with('@') || IGNORED.contains(&key) => {}
+            _ => inspector.warn(&path, "Unknown property", value),
+        }
+    }
+    inspector.warnings
+}
+
+#[derive(Default)]
+struct Inspector {
+    warnings: Vec<ExtractionWarning>,
+}
+
+impl Inspector {
+    fn warn(&mut self, path: &str, reason: &str, value: &Value) {
+        self.warnings.push(ExtractionWarning {
+            path: path.to_string(),
+            reason: reason.to_string(),
+            excerpt: excerpt(value),
+        });
+    }
+
+    /// Calls `f` for every item of an array with its path
+    fn each(&mut self, items: &[Value], path: &str, mut f: impl FnMut(&mut Self, &Value, &str)) {
+        for (i, item) in items.iter().enumerate() {
+            f(self, item, &pointer(path, &i.to_string()));
+        }
+    }
+
+    /// See `extract_author`
+    fn author(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::String(_) => {}
+            Value::Object(obj) if obj.get(LdFields::NAME).is_some_and(Value::is_string) => {}
+            Value::Object(_) => self.warn(path, "Author without a name", value),
+            Value::Array(arr) => self.each(arr, path, Self::author),
+            _ => self.warn(path, "Expected a text or a Person", value),
+        }
+    }
+
+    /// See `extract_ingredients`
+    fn ingredients(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::String(_) => {}
+            Value::Array(arr) => self.each(arr, path, |inspector, item, path| {
+                if !item.is_string() {
+                    inspector.warn(path, "Ingredient is not a text", item);
+                }
+            }),
+            _ => self.warn(path, "Expected a text or a list of texts", value),
+        }
+    }
+
+    /// See `extract_instructions`
+    fn instructions(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::Array(arr) => self.each(arr, path, |inspector, item, path| {
+                if is_section(item) {
+                    inspector.section(item, path);
+                } else {
+                    inspector.step(item, path);
+                }
+            }),
+            Value::Object(_) if is_section(value) => self.section(value, path),
+            _ => self.step(value, path),
+        }
+    }
+
+    /// See `extract_section`
+    fn section(&mut self, value: &Value, path: &str) {
+        if let Some(name) = value.get(LdFields::NAME)
+            && !name.is_string()
+        {
+            self.warn(
+                &pointer(path, LdFields::NAME),
+                "Section name is not a text",
+                name,
+            );
+        }
+        match value.get(LdFields::ITEM_LIST_ELEMENT) {
+            Some(steps) => self.step(steps, &pointer(path, LdFields::ITEM_LIST_ELEMENT)),
+            None if value.get(LdFields::TEXT).is_some_and(Value::is_string) => {}
+            None => self.warn(path, "Section without steps", value),
+        }
+    }
+
+    /// See `extract_step`
+    fn step(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::String(_) => {}
+            Value::Array(arr) => self.each(arr, path, Self::step),
+            Value::Object(obj) if obj.get(LdFields::TEXT).is_some_and(Value::is_string) => {}
+            Value::Object(_) => self.warn(path, "Step without a text", value),
+            _ => self.warn(path, "Expected a text or a HowToStep", value),
+        }
+    }
+
+    /// See `extract_diets`
+    fn diets(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::String(_) => {}
+            Value::Array(arr) => self.each(arr, path, Self::diets),
+            Value::Object(obj) => match obj.get(LdFields::ID) {
+                Some(id) => self.diets(id, &pointer(path, LdFields::ID)),
+                None => self.warn(path, "Diet without an @id", value),
+            },
+            _ => self.warn(path, "Expected a text", value),
+        }
+    }
+
+    /// See `extract_yield`
+    fn recipe_yield(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::String(_) | Value::Number(_) => {}
+            Value::Array(arr) => self.each(arr, path, Self::recipe_yield),
+            _ => self.warn(path, "Expected a text or a number", value),
+        }
+    }
+
+    /// See `extract_nutrition`
+    fn nutrition(&mut self, value: &Value, path: &str) {
+        let Value::Object(obj) = value else {
+            return self.warn(path, "Expected NutritionInformation", value);
+        };
+        for (key, value) in obj.iter().filter(|(key, _)| !key.starts_with('@')) {
+            let path = pointer(path, key);
+            if !NUTRITION_FIELDS.contains(&key.as_str()) {
+                self.warn(&path, "Unsupported nutrition property", value);
+            } else if !value.is_string() && !value.is_number() {
+                self.warn(&path, "Expected a text or a number", value);
+            }
+        }
+    }
+
+    /// See `extract_tools`
+    fn tools(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::String(_) => {}
+            Value::Array(arr) => self.each(arr, path, Self::tools),
+            Value::Object(obj) => match obj.get(LdFields::NAME) {
+                Some(name) => self.tools(name, &pointer(path, LdFields::NAME)),
+                None => self.warn(path, "Tool without a name", value),
+            },
+            _ => self.warn(path, "Expected a text", value),
+        }
+    }
+}
+
+fn is_section(value: &Value) -> bool {
+    value.get(LdFields::TYPE).and_then(Value::as_str) == Some(crate::HOW_TO_SECTION_TYPE)
+}
+
+/// Returns the value as JSON, shortened to [EXCERPT_LENGTH] characters
+fn excerpt(value: &Value) -> String {
+    let json = value.to_string();
+    match json.char_indices().nth(EXCERPT_LENGTH) {
+        Some((end, _)) => format!("{}…", &json[..end]),
+        None => json,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn inspect(value: Value) -> Vec<(String, String)> {
+        inspect_recipe(value.as_object().unwrap())
+            .into_iter()
+            .map(|warning| (warning.path, warning.reason))
+            .collect()
+    }
+
+    #[test]
+    fn nothing_ignored() {
+        let warnings = inspect(json!({
+            "@context": "https://schema.org",
+            "@type": "Recipe",
+            "name": "Pancakes",
+            "image": "https://example.org/pancakes.jpg",
+            "author": [{"@type": "Person", "name": "Jane"}, "John"],
+            "recipeIngredient": ["2 eggs"],
+            "recipeInstructions": [
+                "Mix.",
+                {"@type": "HowToSection", "name": "Frying", "itemListElement": [{"text": "Fry."}]}
+            ],
+            "suitableForDiet": [{"@id": "https://schema.org/VegetarianDiet"}],
+            "recipeYield": ["4", 4],
+            "nutrition": {"@type": "NutritionInformation", "calories": 240},
+            "tool": [{"name": "Pan"}]
+        }));
+        assert_eq!(warnings, vec![]);
+    }
+
+    #[test]
+    fn dropped_values() {
+        let warnings = inspect(json!({
+            "name": ["Pancakes"],
+            "author": {"@type": "Person"},
+            "recipeIngredient": ["2 eggs", {"name": "flour"}, 3],
+            "recipeInstructions": [
+                {"@type": "HowToStep", "name": "Mix"},
+                {"@type": "HowToSection", "name": 1, "itemListElement": [{"text": "Fry."}, null]}
+            ],
+            "nutrition": {"calories": true, "cholesterolContent": "10 mg"},
+            "tool": [{"@type": "HowToTool"}]
+        }));
+        assert_eq!(
+            warnings,
+            vec![
+                ("/author".into(), "Author without a name".into()),
+                ("/name".into(), "Expected a text".into()),
+                (
+                    "/nutrition/calories".into(),
+                    "Expected a text or a number".into()
+                ),
+                (
+                    "/nutrition/cholesterolContent".into(),
+                    "Unsupported nutrition property".into()
+                ),
+                (
+                    "/recipeIngredient/1".into(),
+                    "Ingredient is not a text".into()
+                ),
+                (
+                    "/recipeIngredient/2".into(),
+                    "Ingredient is not a text".into()
+                ),
+                ("/recipeInstructions/0".into(), "Step without a text".into()),
+                (
+                    "/recipeInstructions/1/name".into(),
+                    "Section name is not a text".into()
+                ),
+                (
+                    "/recipeInstructions/1/itemListElement/1".into(),
+                    "Expected a text or a HowToStep".into()
+                ),
+                ("/tool/0".into(), "Tool without a name".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_properties() {
+        let warnings = inspect(json!({
+            "name": "Pancakes",
+            "datePublished": "2024-03-10",
+            "recipeIngredients": ["2 eggs"],
+            "x/y": 1
+        }));
+        assert_eq!(
+            warnings,
+            vec![
+                ("/recipeIngredients".into(), "Unknown property".into()),
+                ("/x~1y".into(), "Unknown property".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn shorten_excerpt() {
+        let text = "a".repeat(100);
+        assert_eq!(excerpt(&json!(text)), format!("\"{}…", "a".repeat(79)));
+        assert_eq!(excerpt(&json!({"a": 1})), "{\"a\":1}");
+    }
+}
@@ -0,0 +1,317 @@
+//! Cleans up text fields that contain HTML tags or entities.
+
+use scraper::{ElementRef, Html, Node};
+
+/// How HTML inside the text fields of a recipe is converted
+///
+/// Many sites put HTML into their JSON-LD, e.g. "&lt;p&gt;Mix &amp;amp; stir&lt;/p&gt;".
+/// Entities are always decoded and whitespace is collapsed, the format decides what happens
+/// to the tags.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextFormat {
+    /// Removes all tags and keeps the text on a single line.
+    #[default]
+    Plain,
+    /// Converts bold and italic text, links, lists and line breaks to markdown and removes
+    /// all other tags.
+    Markdown,
+}
+
+/// Strips tags, decodes entities and collapses whitespace
+///
+/// It deals with:
+///     - "<p>Mix &amp; stir</p>", "Don&#39;t", "a<br/>b"
+///     - HTML that was encoded, e.g. "&lt;b&gt;Mix&lt;/b&gt;"
+///     - entities that were encoded twice, e.g. "Don&amp;#39;t"
+pub(crate) fn clean_text(text: &str, format: TextFormat) -> String {
+    // HTML that was encoded as text, e.g. "&lt;p&gt;Mix&lt;/p&gt;"
+    let decoded;
+    let text = if !text.contains('<') && text.contains("&lt;") {
+        decoded = decode_entities(text);
+        decoded.as_str()
+    } else {
+        text
+    };
+
+    let mut result = if text.contains(['<', '&']) {
+        html_to_text(text, format)
+    } else {
+        text.to_string()
+    };
+
+    // Entities that were encoded twice are still left after parsing the HTML
+    if result.contains('&') {
+        result = decode_entities(&result);
+    }
+
+    match format {
+        TextFormat::Plain => collapse_whitespace(&result),
+        TextFormat::Markdown => collapse_lines(&result),
+    }
+}
+
+/// Converts a fragment of HTML to text, decoding its entities
+fn html_to_text(html: &str, format: TextFormat) -> String {
+    let fragment = Html::parse_fragment(html);
+    let mut result = String::new();
+    write_children(fragment.root_element(), format, &mut result);
+    result
+}
+
+fn write_children(element: ElementRef, format: TextFormat, result: &mut String) {
+    let mut list_index = 0;
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => result.push_str(text),
+            Node::Element(_) => {
+                let Some(child) = ElementRef::wrap(child) else {
+                    continue;
+                };
+                if child.value().name() == "li" {
+                    list_index += 1;
+                }
+                write_element(child, element.value().name(), list_index, format, result);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn write_element(
+    element: ElementRef,
+    parent: &str,
+    list_index: usize,
+    format: TextFormat,
+    result: &mut String,
+) {
+    let markdown = format == TextFormat::Markdown;
+    match element.value().name() {
+        "script" | "style" => {}
+        "br" => result.push('\n'),
+        "li" => {
+            result.push('\n');
+            if markdown && parent == "ol" {
+                result.push_str(&format!("{list_index}. "));
+            } else if markdown {
+                result.push_str("- ");
+            }
+            write_children(element, format, result);
+        }
+        "p" | "div" | "ul" | "ol" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "tr" => {
+            result.push_str("\n\n");
+            write_children(element, format, result);
+            result.push_str("\n\n");
+        }
+        "b" | "strong" if markdown => {
+            result.push_str("**");
+            write_children(element, format, result);
+            result.push_str("**");
+        }
+        "i" | "em" if markdown => {
+            result.push('*');
+            write_children(element, format, result);
+            result.push('*');
+        }
+        "a" if markdown => match element.value().attr("href") {
+            Some(href) => {
+                result.push('[');
+                write_children(element, format, result);
+                result.push_str(&format!("]({href})"));
+            }
+            None => write_children(element, format, result),
+        },
+        _ => write_children(element, format, result),
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Collapses the whitespace within lines and keeps at most one empty line between them
+fn collapse_lines(text: &str) -> String {
+    let mut result: Vec<String> = Vec::new();
+    for line in text.lines().map(collapse_whitespace) {
+        if !line.is_empty() || result.last().is_some_and(|last| !last.is_empty()) {
+            result.push(line);
+        }
+    }
+    while result.last().is_some_and(String::is_empty) {
+        result.pop();
+    }
+    result.join("\n")
+}
+
+/// Decodes named and numeric entities, unknown entities are kept as they are
+pub(crate) fn decode_entities(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| Some((decode_entity(&rest[1..end])?, end)));
+        match decoded {
+            Some((decoded, end)) => {
+                result.push_str(decoded.as_str());
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Decodes a single entity without the surrounding "&" and ";", e.g. "amp" or "#39"
+fn decode_entity(entity: &str) -> Option<String> {
+    if let Some(number) = entity.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code).map(String::from);
+    }
+    ENTITIES
+        .iter()
+        .find(|(name, _)| *name == entity)
+        .map(|(_, value)| value.to_string())
+}
+
+/// Named entities commonly found in recipes
+const ENTITIES: &[(&str, &str)] = &[
+    ("amp", "&"),
+    ("lt", "<"),
+    ("gt", ">"),
+    ("quot", "\""),
+    ("apos", "'"),
+    ("nbsp", "\u{a0}"),
+    ("shy", ""),
+    ("ndash", "–"),
+    ("mdash", "—"),
+    ("hellip", "…"),
+    ("lsquo", "‘"),
+    ("rsquo", "’"),
+    ("ldquo", "“"),
+    ("rdquo", "”"),
+    ("bdquo", "„"),
+    ("laquo", "«"),
+    ("raquo", "»"),
+    ("deg", "°"),
+    ("times", "×"),
+    ("frac12", "½"),
+    ("frac13", "⅓"),
+    ("frac14", "¼"),
+    ("frac34", "¾"),
+    ("copy", "©"),
+    ("reg", "®"),
+    ("trade", "™"),
+    ("auml", "ä"),
+    ("ouml", "ö"),
+    ("uuml", "ü"),
+    ("Auml", "Ä"),
+    ("Ouml", "Ö"),
+    ("Uuml", "Ü"),
+    ("szlig", "ß"),
+    ("eacute", "é"),
+    ("egrave", "è"),
+    ("ecirc", "ê"),
+    ("agrave", "à"),
+    ("aacute", "á"),
+    ("iacute", "í"),
+    ("oacute", "ó"),
+    ("uacute", "ú"),
+    ("ccedil", "ç"),
+    ("ntilde", "ñ"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod plain {
+        use super::*;
+
+        fn clean(text: &str) -> String {
+            clean_text(text, TextFormat::Plain)
+        }
+
+        #[test]
+        fn strip_tags() {
+            assert_eq!(clean("<p>Mix &amp; stir</p>"), "Mix & stir");
+            assert_eq!(clean("Mix<br/>stir"), "Mix stir");
+            assert_eq!(clean("<b>Bold</b> text<script>x()</script>"), "Bold text");
+        }
+
+        #[test]
+        fn decode_entities() {
+            assert_eq!(clean("Don&#39;t"), "Don't");
+            assert_eq!(clean("Don&#x27;t"), "Don't");
+            assert_eq!(clean("Cr&egrave;me fra&icirc;che"), "Crème fraîche");
+            assert_eq!(clean("180&deg;C"), "180°C");
+        }
+
+        #[test]
+        fn decode_twice_encoded() {
+            assert_eq!(clean("Don&amp;#39;t"), "Don't");
+            assert_eq!(clean("&lt;p&gt;Mix &amp;amp; stir&lt;/p&gt;"), "Mix & stir");
+        }
+
+        #[test]
+        fn collapse_whitespace() {
+            assert_eq!(clean("  Mix \n\t stir  "), "Mix stir");
+            assert_eq!(clean("1&nbsp;cup"), "1 cup");
+        }
+
+        #[test]
+        fn keep_plain_text() {
+            assert_eq!(clean("Salt & pepper"), "Salt & pepper");
+            assert_eq!(clean("a < b"), "a < b");
+        }
+    }
+
+    mod markdown {
+        use super::*;
+
+        fn clean(text: &str) -> String {
+            clean_text(text, TextFormat::Markdown)
+        }
+
+        #[test]
+        fn convert_emphasis() {
+            assert_eq!(
+                clean("<b>Mix</b> the <em>flour</em>"),
+                "**Mix** the *flour*"
+            );
+        }
+
+        #[test]
+        fn convert_links() {
+            assert_eq!(
+                clean(r#"See <a href="https://example.org">here</a>"#),
+                "See [here](https://example.org)"
+            );
+        }
+
+        #[test]
+        fn convert_lists() {
+            assert_eq!(
+                clean("Tips:<ul><li>Mix</li><li>Stir</li></ul><ol><li>One</li><li>Two</li></ol>"),
+                "Tips:\n\n- Mix\n- Stir\n\n1. One\n2. Two"
+            );
+        }
+
+        #[test]
+        fn convert_paragraphs() {
+            assert_eq!(
+                clean("<p>First</p><p>Second<br>line</p>"),
+                "First\n\nSecond\nline"
+            );
+        }
+    }
+}
@@ -0,0 +1,408 @@
+use crate::Recipe;
+use crate::food::{FoodCategory, normalize_name};
+use crate::ingredient::{COUNT_NOUNS, parse_ingredient};
+use crate::text::{normalize_text, pluralize};
+use crate::units::{Quantity, format_decimal};
+
+#[cfg(feature = "markdown")]
+use std::fmt::Write;
+
+/// A single entry of a [shopping list](ShoppingList)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShoppingItem {
+    /// The name of the item as it appeared in the first recipe using it, or in the plural
+    /// as written in a later recipe, e.g. "onions" for "1 onion" and "2 onions".
+    ///
+    /// For lines that could not be merged this is the complete ingredient line.
+    pub name: String,
+    /// The summed quantity, if the ingredients had one (e.g. "228 g").
+    pub quantity: Option<Quantity>,
+    /// The summed count, if the ingredients had an amount but no unit (e.g. "5" eggs).
+    pub count: Option<f64>,
+    /// The store aisle category of the item.
+    pub category: FoodCategory,
+    /// The canonical food id or normalized name used to merge ingredients, empty for lines
+    /// that were not merged
+    key: String,
+    /// Whether the name was written for a count of one, e.g. "onion" in "1 onion"
+    singular: bool,
+}
+
+impl ShoppingItem {
+    /// Formats the item for display, e.g. "228 g butter" or "5 eggs"
+    ///
+    /// The name of a count is put in plural if the count is more than one, e.g. "3 onions"
+    /// for "1 onion" and "2 onions".
+    pub fn format(&self) -> String {
+        match (self.quantity, self.count) {
+            (Some(quantity), _) => format!("{} {}", quantity.format(), self.name),
+            (None, Some(count)) if count > 1.0 && self.singular => {
+                format!("{} {}", format_decimal(count), plural_name(&self.name))
+            }
+            (None, Some(count)) => format!("{} {}", format_decimal(count), self.name),
+            (None, None) => self.name.clone(),
+        }
+    }
+
+    /// Tries to add the ingredient to this item, returns false if they are not compatible
+    ///
+    /// Counts are only added if they count the same thing, e.g. not "2 cloves garlic" and
+    /// "1 garlic bulb".
+    fn merge(
+        &mut self,
+        key: &str,
+        name: &str,
+        amount: Option<f64>,
+        quantity: Option<Quantity>,
+    ) -> bool {
+        if self.key.is_empty() || self.key != key {
+            return false;
+        }
+        match (&mut self.quantity, &mut self.count, quantity, amount) {
+            (Some(existing), _, Some(quantity), _) => {
+                match quantity.to_for(existing.unit, &self.name) {
+                    Some(converted) => {
+                        existing.value += converted.value;
+                        true
+                    }
+                    None => false,
+                }
+            }
+            (None, Some(count), None, Some(amount))
+                if count_noun(&self.name) == count_noun(name) =>
+            {
+                *count += amount;
+                if self.singular && amount > 1.0 {
+                    // Use the plural as written, e.g. "onions" of "2 onions"
+                    self.name = name.to_string();
+                    self.singular = false;
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A shopping list combining the ingredients of multiple recipes
+///
+/// Ingredients of the same [food](crate::Food) and compatible units are summed up
+/// ("200 g butter" and "2 tbsp butter"), volumes and weights are combined using the
+/// built-in density table. Ingredients without an amount are kept as separate lines, unless
+/// the same line was already added.
+///
+/// ## Example
+///
+/// ```
+/// use reget::{IngredientSection, Recipe, ShoppingList};
+///
+/// let recipe = |ingredients: &[&str]| Recipe {
+///     ingredient_sections: vec![IngredientSection {
+///         name: None,
+///         ingredients: ingredients.iter().map(|i| i.to_string()).collect(),
+///     }],
+///     ..Default::default()
+/// };
+/// let list = ShoppingList::from_recipes(&[
+///     recipe(&["200 g butter", "2 eggs"]),
+///     recipe(&["2 tbsp butter", "3 eggs, beaten", "salt"]),
+/// ]);
+/// let items: Vec<String> = list.items.iter().map(|item| item.format()).collect();
+/// assert_eq!(items, vec!["228 g butter", "5 eggs", "salt"]);
+/// ```
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ShoppingList {
+    /// The items of the list, in the order they first appeared.
+    pub items: Vec<ShoppingItem>,
+}
+
+impl ShoppingList {
+    /// Combines the ingredients of all recipes into a single shopping list
+    pub fn from_recipes(recipes: &[Recipe]) -> Self {
+        let mut list = ShoppingList::default();
+        for ingredient in recipes.iter().flat_map(Recipe::ingredients) {
+            list.add(ingredient);
+        }
+        list
+    }
+
+    /// Adds a single ingredient line to the list, merging it with a matching item
+    pub fn add(&mut self, ingredient: &str) {
+        let parsed = parse_ingredient(ingredient);
+        let key = parsed
+            .food
+            .clone()
+            .unwrap_or_else(|| normalize_name(&parsed.name));
+        let quantity = parsed.quantity();
+        let name = display_name(&parsed.name);
+
+        if parsed.amount.is_some()
+            && !key.is_empty()
+            && self
+                .items
+                .iter_mut()
+                .any(|item| item.merge(&key, &name, parsed.amount, quantity))
+        {
+            return;
+        }
+        if parsed.amount.is_none() {
+            let line = normalize_text(ingredient);
+            let added = |item: &ShoppingItem| {
+                item.key.is_empty() && item.count.is_none() && normalize_text(&item.name) == line
+            };
+            if self.items.iter().any(added) {
+                return;
+            }
+        }
+
+        let item = match parsed.amount {
+            Some(amount) => ShoppingItem {
+                category: parsed.category,
+                name,
+                quantity,
+                count: quantity.is_none().then_some(amount),
+                key,
+                singular: amount <= 1.0,
+            },
+            None => ShoppingItem {
+                category: parsed.category,
+                name: ingredient.trim().to_string(),
+                quantity: None,
+                count: None,
+                key: String::new(),
+                singular: false,
+            },
+        };
+        self.items.push(item);
+    }
+
+    /// Returns the items grouped by [category](FoodCategory), in store order
+    pub fn by_category(&self) -> Vec<(FoodCategory, Vec<&ShoppingItem>)> {
+        let mut groups: Vec<(FoodCategory, Vec<&ShoppingItem>)> = Vec::new();
+        for item in &self.items {
+            match groups.iter_mut().find(|(c, _)| *c == item.category) {
+                Some((_, items)) => items.push(item),
+                None => groups.push((item.category, vec![item])),
+            }
+        }
+        groups.sort_by_key(|(category, _)| *category);
+        groups
+    }
+
+    #[cfg(feature = "markdown")]
+    /// Converts the list to markdown, with a checklist for each category
+    ///
+    /// ```text
+    /// # Shopping List
+    ///
+    /// ## Dairy & Eggs
+    ///
+    /// - [ ] 228 g butter
+    /// - [ ] 5 eggs
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        let mut result = String::new();
+        writeln!(result, "# Shopping List").unwrap();
+        for (category, items) in self.by_category() {
+            writeln!(result).unwrap();
+            writeln!(result, "## {}", category.name()).unwrap();
+            writeln!(result).unwrap();
+            for item in items {
+                writeln!(result, "- [ ] {}", item.format()).unwrap();
+            }
+        }
+        result
+    }
+}
+
+/// Removes preparation notes after a comma or in parentheses, e.g. "eggs, beaten" -> "eggs"
+fn display_name(name: &str) -> String {
+    let name = name.split([',', '(']).next().unwrap_or_default();
+    name.trim().to_string()
+}
+
+/// Returns the word that says what is counted, e.g. "clove" for "cloves garlic", or None
+/// if the food itself is counted
+fn count_noun(name: &str) -> Option<&'static str> {
+    let name = normalize_text(name);
+    COUNT_NOUNS
+        .iter()
+        .find(|noun| name.split(' ').any(|word| word == **noun))
+        .copied()
+}
+
+/// Puts the name in plural, e.g. "large egg" -> "large eggs" or "clove garlic" -> "cloves
+/// garlic"
+fn plural_name(name: &str) -> String {
+    let mut words: Vec<String> = name.split(' ').map(String::from).collect();
+    let noun = count_noun(name);
+    let index = words
+        .iter()
+        .position(|word| noun.is_some_and(|noun| normalize_text(word) == noun))
+        .unwrap_or(words.len() - 1);
+    words[index] = pluralize(&words[index]);
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::Unit;
+
+    mod merging {
+        use super::*;
+
+        #[test]
+        fn merge_same_unit() {
+            let list = ShoppingList::from_recipes(&[
+                Recipe::from_lines(&["100 g flour"], &[]),
+                Recipe::from_lines(&["150 g flour"], &[]),
+            ]);
+            assert_eq!(list.items.len(), 1);
+            assert_eq!(
+                list.items[0].quantity,
+                Some(Quantity::new(250.0, Unit::Gram))
+            );
+        }
+
+        #[test]
+        fn merge_compatible_units() {
+            let list = ShoppingList::from_recipes(&[
+                Recipe::from_lines(&["1 kg potatoes"], &[]),
+                Recipe::from_lines(&["500 g potato"], &[]),
+            ]);
+            assert_eq!(list.items.len(), 1);
+            assert_eq!(list.items[0].format(), "1.5 kg potatoes");
+        }
+
+        #[test]
+        fn merge_volume_and_weight() {
+            let list = ShoppingList::from_recipes(&[Recipe::from_lines(
+                &["200 g butter", "2 tbsp butter"],
+                &[],
+            )]);
+            assert_eq!(list.items.len(), 1);
+            assert_eq!(list.items[0].format(), "228 g butter");
+        }
+
+        #[test]
+        fn merge_counts() {
+            let list = ShoppingList::from_recipes(&[
+                Recipe::from_lines(&["2 eggs"], &[]),
+                Recipe::from_lines(&["1 egg, beaten"], &[]),
+            ]);
+            assert_eq!(list.items.len(), 1);
+            assert_eq!(list.items[0].format(), "3 eggs");
+        }
+
+        #[test]
+        fn merge_synonyms() {
+            let list = ShoppingList::from_recipes(&[
+                Recipe::from_lines(&["2 large eggs"], &[]),
+                Recipe::from_lines(&["3 Eier"], &[]),
+            ]);
+            assert_eq!(list.items.len(), 1);
+            assert_eq!(list.items[0].format(), "5 large eggs");
+            assert_eq!(list.items[0].category, FoodCategory::Dairy);
+        }
+
+        #[test]
+        fn merge_unknown_foods_by_name() {
+            let list =
+                ShoppingList::from_recipes(&[Recipe::from_lines(&["2 quinces", "1 quince"], &[])]);
+            assert_eq!(list.items.len(), 1);
+            assert_eq!(list.items[0].category, FoodCategory::Other);
+        }
+
+        #[test]
+        fn keep_incompatible_units() {
+            let list = ShoppingList::from_recipes(&[Recipe::from_lines(
+                &["1 cup milk", "200 g milk"],
+                &[],
+            )]);
+            assert_eq!(list.items.len(), 2);
+        }
+
+        #[test]
+        fn keep_count_and_quantity() {
+            let list = ShoppingList::from_recipes(&[Recipe::from_lines(
+                &["2 onions", "200 g onions"],
+                &[],
+            )]);
+            assert_eq!(list.items.len(), 2);
+        }
+
+        #[test]
+        fn keep_lines_without_amount() {
+            let list = ShoppingList::from_recipes(&[
+                Recipe::from_lines(&["salt", "pepper to taste"], &[]),
+                Recipe::from_lines(&["Salt", "pepper"], &[]),
+            ]);
+            let items: Vec<String> = list.items.iter().map(ShoppingItem::format).collect();
+            assert_eq!(items, vec!["salt", "pepper to taste", "pepper"]);
+        }
+
+        #[test]
+        fn keep_different_count_nouns() {
+            let list = ShoppingList::from_recipes(&[
+                Recipe::from_lines(&["2 cloves garlic", "1 garlic bulb"], &[]),
+                Recipe::from_lines(&["1 clove garlic"], &[]),
+            ]);
+            let items: Vec<String> = list.items.iter().map(ShoppingItem::format).collect();
+            assert_eq!(items, vec!["3 cloves garlic", "1 garlic bulb"]);
+        }
+
+        #[test]
+        fn plural_of_merged_counts() {
+            let list = ShoppingList::from_recipes(&[
+                Recipe::from_lines(&["1 onion"], &[]),
+                Recipe::from_lines(&["2 onions"], &[]),
+            ]);
+            assert_eq!(list.items[0].format(), "3 onions");
+            let list =
+                ShoppingList::from_recipes(&[Recipe::from_lines(&["1 onion", "1 onion"], &[])]);
+            assert_eq!(list.items[0].format(), "2 onions");
+            let list = ShoppingList::from_recipes(&[Recipe::from_lines(
+                &["1 clove garlic", "1 clove garlic"],
+                &[],
+            )]);
+            assert_eq!(list.items[0].format(), "2 cloves garlic");
+            let list = ShoppingList::from_recipes(&[Recipe::from_lines(&["3 Eier", "1 egg"], &[])]);
+            assert_eq!(list.items[0].format(), "4 Eier");
+        }
+    }
+
+    mod categories {
+        use super::*;
+
+        #[test]
+        fn group_in_store_order() {
+            let list = ShoppingList::from_recipes(&[Recipe::from_lines(
+                &["2 eggs", "1 onion", "salt", "1 l milk"],
+                &[],
+            )]);
+            let groups = list.by_category();
+            let categories: Vec<_> = groups.iter().map(|(c, _)| *c).collect();
+            assert_eq!(
+                categories,
+                vec![
+                    FoodCategory::Produce,
+                    FoodCategory::Dairy,
+                    FoodCategory::Spices
+                ]
+            );
+            assert_eq!(groups[1].1.len(), 2);
+        }
+
+        #[cfg(feature = "markdown")]
+        #[test]
+        fn render_markdown() {
+            let list =
+                ShoppingList::from_recipes(&[Recipe::from_lines(&["2 eggs", "1 onion"], &[])]);
+            assert_eq!(
+                list.to_markdown(),
+                "# Shopping List\n\n## Produce\n\n- [ ] 1 onion\n\n## Dairy & Eggs\n\n- [ ] 2 eggs\n"
+            );
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use crate::food::FoodDictionary;
+use crate::ingredient::parse_ingredient;
+use crate::text::{contains_phrase, find_normalized, is_verb, normalize_text};
+use crate::{HowToStep, Ingredient, Recipe};
+
+use std::ops::Range;
+
+/// A single step of a [recipe](Recipe) together with its position
+///
+/// Created using [Recipe::steps].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step<'a> {
+    /// The recipe the step belongs to
+    recipe: &'a Recipe,
+    /// The index of the [how-to-section](crate::HowToSection) of the step.
+    pub section: usize,
+    /// The index of the step within its section.
+    pub index: usize,
+    /// The text of the step.
+    pub text: &'a HowToStep,
+}
+
+/// An ingredient used by a [step](Step)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IngredientRef<'a> {
+    /// The index of the [ingredient-section](crate::IngredientSection) of the ingredient.
+    pub section: usize,
+    /// The index of the ingredient within its section.
+    pub index: usize,
+    /// The ingredient itself.
+    pub ingredient: &'a Ingredient,
+}
+
+impl<'a> Step<'a> {
+    pub(crate) fn new(recipe: &'a Recipe, section: usize, index: usize) -> Option<Self> {
+        let text = recipe.how_to_sections.get(section)?.steps.get(index)?;
+        Some(Step {
+            recipe,
+            section,
+            index,
+            text,
+        })
+    }
+
+    /// Returns the ingredients mentioned in the text of this step
+    ///
+    /// Ingredients are matched by their full name, the names of their canonical
+    /// [food](crate::Food) and the last word of their name, ignoring plural forms. So
+    /// "2 cups all-purpose flour" is found in "Stir in the flour mixture". Names used as a
+    /// verb are skipped, so "4 bread rolls" is not found in "Roll out the dough". Names
+    /// shared by several ingredients only count in full, see [match_terms].
+    pub fn ingredient_refs(&self) -> Vec<IngredientRef<'a>> {
+        let ingredients: Vec<(usize, usize, &'a Ingredient)> = self
+            .recipe
+            .ingredient_sections
+            .iter()
+            .enumerate()
+            .flat_map(|(section_index, section)| {
+                section
+                    .ingredients
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, ingredient)| (section_index, index, ingredient))
+            })
+            .collect();
+        let names: Vec<&str> = ingredients.iter().map(|(_, _, i)| i.as_str()).collect();
+        let terms = match_terms(&names);
+        let mentions = find_mentions(self.text, terms.iter().map(Vec::as_slice));
+
+        ingredients
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| mentions.iter().any(|(_, other)| other == i))
+            .map(|(_, (section, index, ingredient))| IngredientRef {
+                section,
+                index,
+                ingredient,
+            })
+            .collect()
+    }
+
+    /// Returns the durations mentioned in the text of this step, see [find_durations](crate::find_durations)
+    pub fn durations(&self) -> Vec<crate::DurationMention> {
+        crate::find_durations(self.text)
+    }
+}
+
+/// Words that are too generic to identify an ingredient on their own
+const GENERIC_WORDS: &[&str] = &[
+    "large", "small", "medium", "fresh", "piece", "pinch", "bunch", "can", "clove", "slice",
+    "taste", "more", "each", "whole",
+];
+
+/// Returns the normalized terms each of the ingredients can be referred to by in a step
+///
+/// The terms are the name of the ingredient, the names of its canonical food and the last
+/// word of its name. Names of the food are left out if they are another variety of it,
+/// e.g. "beef stock" for "chicken stock", or part of the name of another ingredient. The
+/// last word is left out if another ingredient ends with it as well, e.g. "pepper" for
+/// "red bell pepper" next to "salt and pepper".
+pub(crate) fn match_terms(ingredients: &[&str]) -> Vec<Vec<String>> {
+    let names: Vec<(String, Option<String>)> = ingredients
+        .iter()
+        .map(|ingredient| {
+            let parsed = parse_ingredient(ingredient);
+            let name = parsed.name.split([',', '(']).next().unwrap_or_default();
+            (normalize_text(name), parsed.food)
+        })
+        .collect();
+    let head = |name: &str| name.split(' ').next_back().unwrap_or_default().to_string();
+
+    names
+        .iter()
+        .enumerate()
+        .map(|(i, (name, food))| {
+            let others: Vec<&str> = names
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, (other, _))| other.as_str())
+                .collect();
+            let last = head(name);
+
+            let mut terms = vec![name.clone()];
+            if let Some(food) = food.as_ref().and_then(|id| {
+                FoodDictionary::builtin()
+                    .foods()
+                    .iter()
+                    .find(|f| &f.id == id)
+            }) {
+                for alias in food.names.iter().map(|n| normalize_text(n)) {
+                    let variety = head(&alias) == last && !contains_phrase(name, &alias);
+                    if !variety && !others.iter().any(|other| contains_phrase(other, &alias)) {
+                        terms.push(alias);
+                    }
+                }
+            }
+            if last.chars().count() >= 3 && !others.iter().any(|other| head(other) == last) {
+                terms.push(last);
+            }
+            let mut unique: Vec<String> = Vec::new();
+            for term in terms {
+                if !term.is_empty()
+                    && !GENERIC_WORDS.contains(&term.as_str())
+                    && !unique.contains(&term)
+                {
+                    unique.push(term);
+                }
+            }
+            unique
+        })
+        .collect()
+}
+
+/// Returns where the ingredients are mentioned in the text, with the index of the
+/// ingredient, ordered by position
+///
+/// The terms are the [match terms](match_terms) of each ingredient. Names used as a verb
+/// are skipped, and so are mentions inside the longer mention of another ingredient,
+/// e.g. "milk" in "coconut milk".
+pub(crate) fn find_mentions<'t>(
+    text: &str,
+    terms: impl IntoIterator<Item = &'t [String]>,
+) -> Vec<(Range<usize>, usize)> {
+    let candidates: Vec<(Range<usize>, usize)> = terms
+        .into_iter()
+        .enumerate()
+        .flat_map(|(i, terms)| {
+            terms
+                .iter()
+                .flat_map(|term| find_normalized(text, term))
+                .filter(|range| !is_verb(text, range))
+                .map(move |range| (range, i))
+        })
+        .collect();
+    let mut mentions: Vec<(Range<usize>, usize)> = candidates
+        .iter()
+        .filter(|(range, i)| {
+            !candidates.iter().any(|(other, j)| {
+                j != i
+                    && other.start <= range.start
+                    && range.end <= other.end
+                    && other.len() > range.len()
+            })
+        })
+        .cloned()
+        .collect();
+    mentions.sort_by_key(|(range, i)| (range.start, std::cmp::Reverse(range.len()), *i));
+    mentions.dedup();
+    mentions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HowToSection, IngredientSection};
+
+    fn refs(recipe: &Recipe, index: usize) -> Vec<&str> {
+        recipe
+            .ingredients_for_step(0, index)
+            .iter()
+            .map(|r| r.ingredient.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn match_full_names() {
+        let recipe =
+            Recipe::from_lines(&["2 cups flour", "1 cup sugar"], &["Mix flour and sugar."]);
+        assert_eq!(refs(&recipe, 0), vec!["2 cups flour", "1 cup sugar"]);
+    }
+
+    #[test]
+    fn match_plurals() {
+        let recipe = Recipe::from_lines(
+            &["3 large eggs", "1 tomato"],
+            &["Beat the egg.", "Add tomatoes."],
+        );
+        assert_eq!(refs(&recipe, 0), vec!["3 large eggs"]);
+        assert_eq!(refs(&recipe, 1), vec!["1 tomato"]);
+    }
+
+    #[test]
+    fn match_partial_names() {
+        let recipe = Recipe::from_lines(
+            &["2 cups all-purpose flour", "100 g unsalted butter, melted"],
+            &["Stir the butter into the flour mixture."],
+        );
+        assert_eq!(
+            refs(&recipe, 0),
+            vec!["2 cups all-purpose flour", "100 g unsalted butter, melted"]
+        );
+    }
+
+    #[test]
+    fn match_synonyms() {
+        let recipe = Recipe::from_lines(&["2 Eier"], &["Whisk the eggs."]);
+        assert_eq!(refs(&recipe, 0), vec!["2 Eier"]);
+    }
+
+    #[test]
+    fn ignore_generic_words() {
+        let recipe = Recipe::from_lines(&["1 pinch", "2 eggs"], &["Use a large pinch of it."]);
+        assert_eq!(refs(&recipe, 0), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn ignore_similar_words() {
+        let recipe = Recipe::from_lines(
+            &["1 tsp salt", "200 ml buttermilk"],
+            &["Bring salted water and butter to a boil."],
+        );
+        assert_eq!(refs(&recipe, 0), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn ignore_verbs() {
+        let recipe = Recipe::from_lines(
+            &["4 bread rolls", "1 tsp pepper"],
+            &[
+                "Knead. Roll out the dough.",
+                "Pepper the steak, then serve with the rolls.",
+            ],
+        );
+        assert_eq!(refs(&recipe, 0), Vec::<&str>::new());
+        assert_eq!(refs(&recipe, 1), vec!["4 bread rolls"]);
+    }
+
+    #[test]
+    fn ignore_shared_last_words() {
+        let recipe = Recipe::from_lines(
+            &[
+                "1 red bell pepper",
+                "salt and pepper",
+                "1 can coconut milk",
+                "200 ml milk",
+            ],
+            &[
+                "Season with salt and pepper.",
+                "Add the milk.",
+                "Stir in the coconut milk.",
+            ],
+        );
+        assert_eq!(refs(&recipe, 0), vec!["salt and pepper"]);
+        assert_eq!(refs(&recipe, 1), vec!["200 ml milk"]);
+        assert_eq!(refs(&recipe, 2), vec!["1 can coconut milk"]);
+    }
+
+    #[test]
+    fn ignore_other_varieties() {
+        let terms = match_terms(&["500 ml chicken stock"]);
+        assert!(terms[0].contains(&"chicken stock".to_string()));
+        assert!(!terms[0].contains(&"beef stock".to_string()));
+
+        let recipe = Recipe::from_lines(
+            &["500 ml chicken stock", "1 tbsp beef stock paste"],
+            &[
+                "Whisk in the beef stock paste.",
+                "Pour in the chicken stock.",
+            ],
+        );
+        assert_eq!(refs(&recipe, 0), vec!["1 tbsp beef stock paste"]);
+        assert_eq!(refs(&recipe, 1), vec!["500 ml chicken stock"]);
+    }
+
+    #[test]
+    fn last_word_of_single_ingredient() {
+        let recipe = Recipe::from_lines(&["1 red bell pepper"], &["Slice the pepper."]);
+        assert_eq!(refs(&recipe, 0), vec!["1 red bell pepper"]);
+    }
+
+    #[test]
+    fn positions() {
+        let mut recipe = Recipe::from_lines(&["2 eggs"], &["Boil water."]);
+        recipe.ingredient_sections.push(IngredientSection {
+            name: Some("Topping".into()),
+            ingredients: vec!["salt".into()],
+        });
+        recipe.how_to_sections.push(HowToSection {
+            name: None,
+            steps: vec!["Peel the eggs and add salt.".into()],
+        });
+        let steps: Vec<Step> = recipe.steps().collect();
+        assert_eq!(steps.len(), 2);
+        assert_eq!((steps[1].section, steps[1].index), (1, 0));
+        let refs = steps[1].ingredient_refs();
+        assert_eq!((refs[0].section, refs[0].index), (0, 0));
+        assert_eq!((refs[1].section, refs[1].index), (1, 0));
+    }
+
+    #[test]
+    fn invalid_step() {
+        let recipe = Recipe::from_lines(&["2 eggs"], &["Boil the eggs."]);
+        assert!(recipe.ingredients_for_step(1, 0).is_empty());
+        assert!(recipe.ingredients_for_step(0, 1).is_empty());
+    }
+}
@@ -0,0 +1,249 @@
+//! Exports recipes to the import JSON of Tandoor.
+
+use crate::ingredient::{parse_amount, parse_ingredient};
+use crate::timers::parse_time;
+use crate::{NutritionInformation, Recipe};
+
+use serde_json::{Value, json};
+
+/// Converts the recipe to the `recipe.json` Tandoor imports
+///
+/// Tandoor attaches ingredients to steps, so all ingredients are listed in the first step,
+/// with a header for every named section. The first step of a named section gets the name
+/// of the section. The prep time is written as working time and the cook time as waiting
+/// time, both in minutes.
+pub(crate) fn to_tandoor_json(recipe: &Recipe) -> Value {
+    let mut ingredients = Vec::new();
+    for section in &recipe.ingredient_sections {
+        if let Some(name) = &section.name {
+            ingredients.push(json!({
+                "food": null,
+                "unit": null,
+                "amount": 0,
+                "note": name,
+                "order": ingredients.len(),
+                "is_header": true,
+                "no_amount": true,
+                "original_text": name,
+            }));
+        }
+        for ingredient in &section.ingredients {
+            let parsed = parse_ingredient(ingredient);
+            let unit = parsed
+                .unit
+                .map(|unit| json!({ "name": unit.symbol(parsed.amount.unwrap_or(1.0)) }));
+            ingredients.push(json!({
+                "food": { "name": parsed.name },
+                "unit": unit,
+                "amount": parsed.amount.unwrap_or(0.0),
+                "note": "",
+                "order": ingredients.len(),
+                "is_header": false,
+                "no_amount": parsed.amount.is_none(),
+                "original_text": ingredient,
+            }));
+        }
+    }
+
+    let mut steps: Vec<Value> = recipe
+        .how_to_sections
+        .iter()
+        .flat_map(|section| {
+            section.steps.iter().enumerate().map(|(i, step)| {
+                let name = section.name.as_deref().filter(|_| i == 0);
+                json!({
+                    "name": name.unwrap_or_default(),
+                    "instruction": step,
+                    "ingredients": [],
+                    "time": 0,
+                    "show_as_header": name.is_some(),
+                    "show_ingredients_table": true,
+                })
+            })
+        })
+        .collect();
+    if steps.is_empty() && !ingredients.is_empty() {
+        steps.push(json!({
+            "name": "",
+            "instruction": "",
+            "ingredients": [],
+            "time": 0,
+            "show_as_header": false,
+            "show_ingredients_table": true,
+        }));
+    }
+    if let Some(first) = steps.first_mut() {
+        first["ingredients"] = ingredients.into();
+    }
+    for (i, step) in steps.iter_mut().enumerate() {
+        step["order"] = i.into();
+    }
+
+    let minutes = |time: &Option<String>| {
+        time.as_deref()
+            .and_then(parse_time)
+            .map_or(0, |duration| duration.as_secs() / 60)
+    };
+    json!({
+        "name": recipe.name.as_deref().unwrap_or("Recipe"),
+        "description": recipe.description,
+        "keywords": [],
+        "steps": steps,
+        "working_time": minutes(&recipe.prep_time),
+        "waiting_time": minutes(&recipe.cook_time),
+        "internal": true,
+        "nutrition": recipe.nutrition.as_ref().and_then(nutrition_to_json),
+        "servings": recipe.servings().map_or(1, |servings| servings.round() as u64),
+        "servings_text": recipe.recipe_yield.as_deref().unwrap_or_default(),
+        "source_url": null,
+    })
+}
+
+/// Returns the numbers of the nutrition, e.g. 540 for "540 kcal", or None if there are
+/// no calories, fats, carbohydrates or proteins
+fn nutrition_to_json(nutrition: &NutritionInformation) -> Option<Value> {
+    let number = |value: &Option<String>| {
+        value
+            .as_deref()
+            .and_then(|value| parse_amount(value.trim()))
+            .map(|(number, _)| number)
+    };
+    let values = [
+        number(&nutrition.calories),
+        number(&nutrition.carbohydrate_content),
+        number(&nutrition.fat_content),
+        number(&nutrition.protein_content),
+    ];
+    if values.iter().all(Option::is_none) {
+        return None;
+    }
+    let [calories, carbohydrates, fats, proteins] = values.map(|value| value.unwrap_or(0.0));
+    Some(json!({
+        "calories": calories,
+        "carbohydrates": carbohydrates,
+        "fats": fats,
+        "proteins": proteins,
+        "source": "",
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HowToSection, IngredientSection};
+
+    #[test]
+    fn write_json() {
+        let recipe = Recipe {
+            name: Some("Pancakes".into()),
+            recipe_yield: Some("4 servings".into()),
+            prep_time: Some("PT10M".into()),
+            cook_time: Some("about 20 minutes".into()),
+            ingredient_sections: vec![
+                IngredientSection {
+                    name: None,
+                    ingredients: vec!["2 eggs".into()],
+                },
+                IngredientSection {
+                    name: Some("Topping".into()),
+                    ingredients: vec!["1/2 cup maple syrup".into()],
+                },
+            ],
+            how_to_sections: vec![
+                HowToSection {
+                    name: None,
+                    steps: vec!["Mix.".into()],
+                },
+                HowToSection {
+                    name: Some("Frying".into()),
+                    steps: vec!["Fry.".into()],
+                },
+            ],
+            nutrition: Some(NutritionInformation {
+                calories: Some("240 kcal".into()),
+                protein_content: Some("8.5 g".into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let json = recipe.to_tandoor_json();
+        assert_eq!(json["working_time"], 10);
+        assert_eq!(json["waiting_time"], 20);
+        assert_eq!(json["servings"], 4);
+        assert_eq!(json["servings_text"], "4 servings");
+        assert_eq!(
+            json["nutrition"],
+            json!({"calories": 240.0, "carbohydrates": 0.0, "fats": 0.0, "proteins": 8.5, "source": ""})
+        );
+        assert_eq!(
+            json["steps"],
+            json!([
+                {
+                    "name": "",
+                    "instruction": "Mix.",
+                    "ingredients": [
+                        {
+                            "food": {"name": "eggs"},
+                            "unit": null,
+                            "amount": 2.0,
+                            "note": "",
+                            "order": 0,
+                            "is_header": false,
+                            "no_amount": false,
+                            "original_text": "2 eggs"
+                        },
+                        {
+                            "food": null,
+                            "unit": null,
+                            "amount": 0,
+                            "note": "Topping",
+                            "order": 1,
+                            "is_header": true,
+                            "no_amount": true,
+                            "original_text": "Topping"
+                        },
+                        {
+                            "food": {"name": "maple syrup"},
+                            "unit": {"name": "cup"},
+                            "amount": 0.5,
+                            "note": "",
+                            "order": 2,
+                            "is_header": false,
+                            "no_amount": false,
+                            "original_text": "1/2 cup maple syrup"
+                        }
+                    ],
+                    "time": 0,
+                    "order": 0,
+                    "show_as_header": false,
+                    "show_ingredients_table": true
+                },
+                {
+                    "name": "Frying",
+                    "instruction": "Fry.",
+                    "ingredients": [],
+                    "time": 0,
+                    "order": 1,
+                    "show_as_header": true,
+                    "show_ingredients_table": true
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn ingredients_without_steps() {
+        let recipe = Recipe {
+            ingredient_sections: vec![IngredientSection {
+                name: None,
+                ingredients: vec!["salt".into()],
+            }],
+            ..Default::default()
+        };
+        let json = recipe.to_tandoor_json();
+        assert_eq!(json["name"], "Recipe");
+        assert_eq!(json["servings"], 1);
+        assert_eq!(json["nutrition"], Value::Null);
+        assert_eq!(json["steps"][0]["ingredients"][0]["no_amount"], true);
+    }
+}
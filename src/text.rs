@@ -0,0 +1,230 @@
+//! Helpers to search and normalize the free text of ingredients and steps.
+
+/// Checks whether the phrase occurs in the text as whole words.
+pub(crate) fn contains_phrase(text: &str, phrase: &str) -> bool {
+    find_phrase(text, phrase).next().is_some()
+}
+
+/// Returns the byte ranges where the phrase occurs in the text as whole words.
+pub(crate) fn find_phrase<'a>(
+    text: &'a str,
+    phrase: &'a str,
+) -> impl Iterator<Item = std::ops::Range<usize>> + 'a {
+    text.match_indices(phrase)
+        .filter(move |(start, _)| {
+            let before = text[..*start].chars().next_back();
+            let after = text[start + phrase.len()..].chars().next();
+            !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+        })
+        .map(move |(start, _)| start..start + phrase.len())
+}
+
+/// Returns the byte ranges in the original text where a normalized phrase occurs, see
+/// [normalize_text], e.g. "egg" is found as "Eggs" in "Beat the Eggs."
+pub(crate) fn find_normalized(text: &str, phrase: &str) -> Vec<std::ops::Range<usize>> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        match (start, c.is_alphanumeric()) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                words.push((s..i, singularize(&text[s..i].to_lowercase())));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    let phrase: Vec<&str> = phrase.split(' ').filter(|word| !word.is_empty()).collect();
+    if phrase.is_empty() {
+        return Vec::new();
+    }
+    words
+        .windows(phrase.len())
+        .filter(|window| window.iter().zip(&phrase).all(|((_, word), p)| word == p))
+        .map(|window| window[0].0.start..window[window.len() - 1].0.end)
+        .collect()
+}
+
+/// Checks whether the word at the range is used as a verb: it starts a clause and is
+/// followed by an article or a particle, e.g. "Roll" in "Knead. Roll out the dough." or
+/// "pepper" in "Drain and pepper the steak"
+pub(crate) fn is_verb(text: &str, range: &std::ops::Range<usize>) -> bool {
+    starts_clause(text, range) && precedes_object(text, range)
+}
+
+/// Checks whether the word at the range starts a sentence or a clause, e.g. after "." or
+/// "then"
+pub(crate) fn starts_clause(text: &str, range: &std::ops::Range<usize>) -> bool {
+    let before = text[..range.start].trim_end();
+    before.is_empty()
+        || before.ends_with(['.', '!', '?', ';', ':', ','])
+        || before
+            .rsplit(|c: char| !c.is_alphanumeric())
+            .next()
+            .is_some_and(|word| CLAUSE_WORDS.contains(&word.to_lowercase().as_str()))
+}
+
+/// Checks whether the word at the range is followed by an article or a particle, e.g.
+/// "the" in "Grill the chicken"
+pub(crate) fn precedes_object(text: &str, range: &std::ops::Range<usize>) -> bool {
+    text[range.end..]
+        .split(|c: char| !c.is_alphanumeric())
+        .find(|word| !word.is_empty())
+        .is_some_and(|word| VERB_OBJECTS.contains(&word.to_lowercase().as_str()))
+}
+
+/// Words that start a clause within a sentence, in English and German
+const CLAUSE_WORDS: &[&str] = &["then", "and", "dann", "und"];
+
+/// Words following a verb at the start of a clause, e.g. "the" in "Pepper the steak"
+const VERB_OBJECTS: &[&str] = &[
+    "the", "a", "an", "it", "them", "out", "up", "den", "die", "das",
+];
+
+/// Turns a recipe name into a name for a file or folder, replacing the characters that
+/// are not allowed on common file systems, e.g. "Mac/Cheese?" -> "Mac Cheese"
+#[cfg(any(feature = "nextcloud", feature = "paprika"))]
+pub(crate) fn file_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => ' ',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    let name = name.trim_matches('.');
+    if name.is_empty() {
+        "Recipe".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Returns the singular form of an english word, e.g. "tomatoes" -> "tomato".
+///
+/// Only handles regular plurals, irregular ones are returned unchanged.
+pub(crate) fn singularize(word: &str) -> String {
+    if word.chars().count() <= 3 || word.ends_with("ss") || word.ends_with("us") {
+        return word.to_string();
+    }
+    if let Some(stem) = word.strip_suffix("ies") {
+        return format!("{stem}y");
+    }
+    for suffix in ["oes", "ches", "shes", "sses", "xes"] {
+        if word.ends_with(suffix) {
+            return word[..word.len() - 2].to_string();
+        }
+    }
+    word.strip_suffix('s').unwrap_or(word).to_string()
+}
+
+/// Returns the plural form of an english word, e.g. "tomato" -> "tomatoes".
+///
+/// Only handles regular plurals.
+pub(crate) fn pluralize(word: &str) -> String {
+    let consonant_y = word
+        .strip_suffix('y')
+        .filter(|stem| !stem.ends_with(['a', 'e', 'i', 'o', 'u']));
+    if let Some(stem) = consonant_y {
+        return format!("{stem}ies");
+    }
+    if ["s", "x", "z", "ch", "sh", "o"]
+        .iter()
+        .any(|suffix| word.ends_with(suffix))
+    {
+        return format!("{word}es");
+    }
+    format!("{word}s")
+}
+
+/// Normalizes free text for matching: lower case, words separated by single spaces,
+/// punctuation removed and every word in singular, e.g. "The Eggs, beaten." -> "the egg beaten"
+pub(crate) fn normalize_text(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(singularize)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phrase_whole_words() {
+        assert!(contains_phrase("unsalted butter", "butter"));
+        assert!(contains_phrase("butter, softened", "butter"));
+        assert!(!contains_phrase("buttermilk", "butter"));
+        assert!(!contains_phrase("peanutbutter", "butter"));
+    }
+
+    #[test]
+    fn phrase_ranges() {
+        let ranges: Vec<_> = find_phrase("milk and oat milk", "milk").collect();
+        assert_eq!(ranges, vec![0..4, 13..17]);
+    }
+
+    #[test]
+    fn normalized_ranges() {
+        let text = "Beat the Eggs, then add the egg whites.";
+        assert_eq!(find_normalized(text, "egg"), vec![9..13, 28..31]);
+        assert_eq!(find_normalized(text, "egg white"), vec![28..38]);
+        assert_eq!(find_normalized(text, "eggwhite"), vec![]);
+        assert_eq!(find_normalized(text, ""), vec![]);
+    }
+
+    #[test]
+    fn verbs() {
+        let text = "Knead. Roll out the dough, then roll it up and serve the rolls.";
+        let verbs: Vec<_> = find_normalized(text, "roll")
+            .into_iter()
+            .map(|range| is_verb(text, &range))
+            .collect();
+        assert_eq!(verbs, vec![true, true, false]);
+        assert!(is_verb("pepper the steak", &(0..6)));
+        assert!(!is_verb("salt and pepper", &(9..15)));
+        assert!(!is_verb("Add the milk and oil into the pot", &(17..20)));
+    }
+
+    #[cfg(any(feature = "nextcloud", feature = "paprika"))]
+    #[test]
+    fn file_names() {
+        assert_eq!(file_name("Mac/Cheese?"), "Mac Cheese");
+        assert_eq!(file_name("  Crème brûlée "), "Crème brûlée");
+        assert_eq!(file_name(".."), "Recipe");
+    }
+
+    #[test]
+    fn normalize() {
+        assert_eq!(normalize_text("The Eggs, beaten."), "the egg beaten");
+        assert_eq!(normalize_text("all-purpose flour"), "all purpose flour");
+    }
+
+    #[test]
+    fn plural_forms() {
+        assert_eq!(pluralize("onion"), "onions");
+        assert_eq!(pluralize("tomato"), "tomatoes");
+        assert_eq!(pluralize("cherry"), "cherries");
+        assert_eq!(pluralize("bunch"), "bunches");
+        assert_eq!(pluralize("key"), "keys");
+    }
+
+    #[test]
+    fn singular_forms() {
+        assert_eq!(singularize("eggs"), "egg");
+        assert_eq!(singularize("tomatoes"), "tomato");
+        assert_eq!(singularize("berries"), "berry");
+        assert_eq!(singularize("peaches"), "peach");
+        assert_eq!(singularize("glass"), "glass");
+        assert_eq!(singularize("asparagus"), "asparagus");
+        assert_eq!(singularize("peas"), "pea");
+    }
+}
@@ -0,0 +1,444 @@
+use crate::ingredient::parse_amount;
+
+use std::ops::Range;
+use std::time::Duration;
+
+/// A duration mentioned in the text of a step, e.g. "10–15 minutes"
+///
+/// Created using [find_durations] or [Step::durations](crate::Step::durations).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DurationMention {
+    /// The lower bound of the duration.
+    pub min: Duration,
+    /// The upper bound of the duration, equal to [min](DurationMention::min) if no range
+    /// was given.
+    pub max: Duration,
+    /// The byte range of the mention within the text.
+    pub span: Range<usize>,
+}
+
+/// Finds the durations mentioned in a text, e.g. the instructions of a step.
+///
+/// It deals with:
+///     - "10 minutes", "1 hr", "30 Sekunden", "2 Std."
+///     - "10-15 minutes", "10 to 15 minutes", "10 bis 15 Minuten"
+///     - "1 hour 30 minutes", "1 Stunde und 30 Minuten"
+///     - "an hour", "half an hour", "eine halbe Stunde", "five minutes"
+///
+/// ## Example
+///
+/// ```
+/// use reget::find_durations;
+/// use std::time::Duration;
+///
+/// let text = "Simmer for 10–15 minutes, then let rest 1 hour.";
+/// let durations = find_durations(text);
+/// assert_eq!(durations[0].min, Duration::from_secs(10 * 60));
+/// assert_eq!(durations[0].max, Duration::from_secs(15 * 60));
+/// assert_eq!(&text[durations[1].span.clone()], "1 hour");
+/// ```
+pub fn find_durations(text: &str) -> Vec<DurationMention> {
+    let mut result = Vec::new();
+    let mut position = 0;
+
+    while position < text.len() {
+        let rest = &text[position..];
+        let at_word_start = !text[..position]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric);
+
+        if at_word_start && let Some((min, max, len)) = parse_duration(rest) {
+            // Durations too long to represent are skipped
+            if let (Ok(min), Ok(max)) = (
+                Duration::try_from_secs_f64(min),
+                Duration::try_from_secs_f64(max),
+            ) {
+                result.push(DurationMention {
+                    min,
+                    max,
+                    span: position..position + len,
+                });
+            }
+            position += len;
+        } else {
+            position += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    result
+}
+
+/// Parses a duration at the start of the text, including a following smaller part like the
+/// "30 minutes" of "1 hour and 30 minutes".
+///
+/// Returns the bounds in seconds and the length of the mention.
+fn parse_duration(text: &str) -> Option<(f64, f64, usize)> {
+    let (mut min, mut max, mut unit, mut len) = parse_single(text)?;
+
+    loop {
+        let rest = &text[len..];
+        let trimmed = rest.trim_start();
+        let after_joiner = [",", "and ", "und "]
+            .iter()
+            .find_map(|joiner| strip_prefix_ignore_case(trimmed, joiner))
+            .unwrap_or(trimmed)
+            .trim_start();
+        match parse_single(after_joiner) {
+            Some((part_min, part_max, part_unit, part_len)) if part_unit < unit => {
+                min += part_min;
+                max += part_max;
+                unit = part_unit;
+                len += rest.len() - after_joiner.len() + part_len;
+            }
+            _ => break,
+        }
+    }
+
+    Some((min, max, len))
+}
+
+/// Parses a phrase like "half an hour" or an amount or range followed by a unit.
+///
+/// Returns the bounds in seconds, the size of the unit in seconds and the length of the mention.
+fn parse_single(text: &str) -> Option<(f64, f64, f64, usize)> {
+    for (phrase, seconds, unit) in DURATION_PHRASES {
+        if let Some(rest) = strip_prefix_ignore_case(text, phrase)
+            && is_word_end(rest)
+        {
+            return Some((*seconds, *seconds, *unit, phrase.len()));
+        }
+    }
+
+    let (min, after_min) = parse_number(text)?;
+    let (max, after_max) = parse_range_end(after_min).unwrap_or((min, after_min));
+
+    let after_filler = after_max.trim_start();
+    let after_filler = FILLER_WORDS
+        .iter()
+        .find_map(|word| {
+            strip_prefix_ignore_case(after_filler, word)
+                .filter(|rest| rest.starts_with(char::is_whitespace))
+        })
+        .unwrap_or(after_filler)
+        .trim_start();
+
+    let unit_end = after_filler
+        .find(|c: char| !c.is_alphabetic())
+        .unwrap_or(after_filler.len());
+    let word = after_filler[..unit_end].to_lowercase();
+    let (_, unit) = DURATION_UNITS.iter().find(|(name, _)| *name == word)?;
+    let mut rest = &after_filler[unit_end..];
+    if let Some(after_dot) = rest.strip_prefix('.')
+        && ABBREVIATIONS.contains(&word.as_str())
+    {
+        rest = after_dot;
+    }
+    if min > max {
+        return None;
+    }
+    Some((min * unit, max * unit, *unit, text.len() - rest.len()))
+}
+
+/// Parses the end of a range like "-15", "–15", " to 15" or " bis 15".
+fn parse_range_end(text: &str) -> Option<(f64, &str)> {
+    let trimmed = text.trim_start();
+    let rest = ["-", "–", "—", "to ", "or ", "bis ", "oder "]
+        .iter()
+        .find_map(|separator| strip_prefix_ignore_case(trimmed, separator))?;
+    parse_number(rest.trim_start())
+}
+
+/// Parses a number like "10", "1.5", "1 1/2", "½" or "five".
+fn parse_number(text: &str) -> Option<(f64, &str)> {
+    if let Some((amount, rest)) = parse_amount(text) {
+        return Some((amount, rest));
+    }
+    let end = text
+        .find(|c: char| !c.is_alphabetic())
+        .unwrap_or(text.len());
+    let word = text[..end].to_lowercase();
+    NUMBER_WORDS
+        .iter()
+        .find(|(name, _)| *name == word)
+        .map(|(_, value)| (*value, &text[end..]))
+}
+
+fn strip_prefix_ignore_case<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = text.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &text[prefix.len()..])
+}
+
+fn is_word_end(text: &str) -> bool {
+    !text.starts_with(char::is_alphanumeric)
+}
+
+/// Parses a time of a recipe, either an ISO 8601 duration or the upper bound of the first
+/// duration mentioned in a text like "about 1 hour 30 minutes"
+pub(crate) fn parse_time(text: &str) -> Option<Duration> {
+    parse_iso_duration(text).or_else(|| find_durations(text).first().map(|mention| mention.max))
+}
+
+/// Formats a duration for display, e.g. "1 hour 30 minutes" or "45 seconds"
+#[cfg(any(feature = "cooklang", feature = "mealie", feature = "paprika"))]
+pub(crate) fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds < 60 {
+        return format!("{seconds} seconds");
+    }
+    let (hours, minutes) = (seconds / 3600, seconds / 60 % 60);
+    let parts: Vec<String> = [(hours, "hour"), (minutes, "minute")]
+        .into_iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| match value {
+            1 => format!("1 {unit}"),
+            value => format!("{value} {unit}s"),
+        })
+        .collect();
+    parts.join(" ")
+}
+
+/// Parses an ISO 8601 duration as used by schema.org, e.g. "PT1H30M" or "P1DT2H".
+pub(crate) fn parse_iso_duration(text: &str) -> Option<Duration> {
+    let text = text.trim().strip_prefix(['P', 'p'])?;
+    let mut seconds = 0.0;
+    let mut in_time = false;
+    let mut number = String::new();
+    let mut found = false;
+
+    for c in text.chars() {
+        match c.to_ascii_uppercase() {
+            'T' if number.is_empty() => in_time = true,
+            '0'..='9' | '.' | ',' => number.push(if c == ',' { '.' } else { c }),
+            designator => {
+                let value: f64 = number.parse().ok()?;
+                number.clear();
+                seconds += value
+                    * match (designator, in_time) {
+                        ('W', false) => 604800.0,
+                        ('D', false) => 86400.0,
+                        ('H', true) => 3600.0,
+                        ('M', true) => 60.0,
+                        ('S', true) => 1.0,
+                        _ => return None,
+                    };
+                found = true;
+            }
+        }
+    }
+
+    if !found || !number.is_empty() {
+        return None;
+    }
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// Words for a duration unit and its size in seconds, in English and German
+const DURATION_UNITS: &[(&str, f64)] = &[
+    ("sec", 1.0),
+    ("secs", 1.0),
+    ("second", 1.0),
+    ("seconds", 1.0),
+    ("sek", 1.0),
+    ("sekunde", 1.0),
+    ("sekunden", 1.0),
+    ("min", 60.0),
+    ("mins", 60.0),
+    ("minute", 60.0),
+    ("minutes", 60.0),
+    ("minuten", 60.0),
+    ("h", 3600.0),
+    ("hr", 3600.0),
+    ("hrs", 3600.0),
+    ("hour", 3600.0),
+    ("hours", 3600.0),
+    ("std", 3600.0),
+    ("stunde", 3600.0),
+    ("stunden", 3600.0),
+    ("day", 86400.0),
+    ("days", 86400.0),
+    ("tag", 86400.0),
+    ("tage", 86400.0),
+    ("tagen", 86400.0),
+];
+
+/// Units that may be followed by a period, e.g. "2 Std."
+const ABBREVIATIONS: &[&str] = &["sec", "secs", "sek", "min", "mins", "hr", "hrs", "std"];
+
+/// Numbers written as words, in English and German
+const NUMBER_WORDS: &[(&str, f64)] = &[
+    ("a", 1.0),
+    ("an", 1.0),
+    ("one", 1.0),
+    ("two", 2.0),
+    ("three", 3.0),
+    ("four", 4.0),
+    ("five", 5.0),
+    ("six", 6.0),
+    ("seven", 7.0),
+    ("eight", 8.0),
+    ("nine", 9.0),
+    ("ten", 10.0),
+    ("twelve", 12.0),
+    ("fifteen", 15.0),
+    ("twenty", 20.0),
+    ("thirty", 30.0),
+    ("ein", 1.0),
+    ("eine", 1.0),
+    ("einer", 1.0),
+    ("einen", 1.0),
+    ("zwei", 2.0),
+    ("drei", 3.0),
+    ("vier", 4.0),
+    ("fünf", 5.0),
+    ("sechs", 6.0),
+    ("sieben", 7.0),
+    ("acht", 8.0),
+    ("neun", 9.0),
+    ("zehn", 10.0),
+    ("zwölf", 12.0),
+    ("fünfzehn", 15.0),
+    ("zwanzig", 20.0),
+    ("dreißig", 30.0),
+];
+
+/// Phrases for durations that do not start with a number, with their length and unit in seconds
+const DURATION_PHRASES: &[(&str, f64, f64)] = &[
+    ("an hour and a half", 5400.0, 3600.0),
+    ("half an hour", 1800.0, 60.0),
+    ("a half hour", 1800.0, 60.0),
+    ("eine halbe stunde", 1800.0, 60.0),
+    ("einer halben stunde", 1800.0, 60.0),
+    ("eineinhalb stunden", 5400.0, 3600.0),
+    ("anderthalb stunden", 5400.0, 3600.0),
+];
+
+/// Words that may stand between the number and the unit, e.g. "5 more minutes"
+const FILLER_WORDS: &[&str] = &["more", "additional", "further", "extra", "weitere"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minutes(min: u64, max: u64) -> (Duration, Duration) {
+        (Duration::from_secs(min * 60), Duration::from_secs(max * 60))
+    }
+
+    fn bounds(text: &str) -> Vec<(Duration, Duration)> {
+        find_durations(text)
+            .into_iter()
+            .map(|d| (d.min, d.max))
+            .collect()
+    }
+
+    mod mentions {
+        use super::*;
+
+        #[test]
+        fn find_single() {
+            assert_eq!(bounds("Bake for 25 minutes."), vec![minutes(25, 25)]);
+            assert_eq!(bounds("Let rest 1 hr"), vec![minutes(60, 60)]);
+            assert_eq!(
+                bounds("Cook 90 sec."),
+                vec![(Duration::from_secs(90), Duration::from_secs(90))]
+            );
+        }
+
+        #[test]
+        fn find_ranges() {
+            assert_eq!(bounds("simmer for 10–15 minutes"), vec![minutes(10, 15)]);
+            assert_eq!(bounds("simmer for 10-15 mins"), vec![minutes(10, 15)]);
+            assert_eq!(bounds("bake 20 to 25 minutes"), vec![minutes(20, 25)]);
+        }
+
+        #[test]
+        fn find_compound() {
+            assert_eq!(bounds("Roast 1 hour and 30 minutes"), vec![minutes(90, 90)]);
+            assert_eq!(bounds("Roast 1 h 15 min"), vec![minutes(75, 75)]);
+        }
+
+        #[test]
+        fn find_words() {
+            assert_eq!(bounds("Let rest an hour"), vec![minutes(60, 60)]);
+            assert_eq!(bounds("Chill for half an hour"), vec![minutes(30, 30)]);
+            assert_eq!(bounds("Stir for five more minutes"), vec![minutes(5, 5)]);
+            assert_eq!(bounds("Cook for 1 1/2 hours"), vec![minutes(90, 90)]);
+        }
+
+        #[test]
+        fn find_german() {
+            assert_eq!(
+                bounds("10 bis 15 Minuten köcheln lassen"),
+                vec![minutes(10, 15)]
+            );
+            assert_eq!(
+                bounds("Eine halbe Stunde ruhen lassen"),
+                vec![minutes(30, 30)]
+            );
+            assert_eq!(bounds("2 Std. gehen lassen"), vec![minutes(120, 120)]);
+            assert_eq!(
+                bounds("1 Stunde und 15 Minuten backen"),
+                vec![minutes(75, 75)]
+            );
+        }
+
+        #[test]
+        fn find_multiple_with_spans() {
+            let text = "Boil 10 minutes, then bake 1 hour.";
+            let durations = find_durations(text);
+            assert_eq!(durations.len(), 2);
+            assert_eq!(&text[durations[0].span.clone()], "10 minutes");
+            assert_eq!(&text[durations[1].span.clone()], "1 hour");
+        }
+
+        #[test]
+        fn ignore_other_numbers() {
+            assert!(find_durations("Bake at 350 F with 2 eggs in a 9-inch pan").is_empty());
+            assert!(find_durations("Add a handful of herbs").is_empty());
+        }
+
+        #[test]
+        fn ignore_overflow() {
+            assert!(find_durations("Rest 99999999999999999999 days.").is_empty());
+            assert_eq!(
+                bounds("Rest 99999999999999999999 days, then 5 minutes."),
+                vec![minutes(5, 5)]
+            );
+        }
+    }
+
+    mod iso {
+        use super::*;
+
+        #[test]
+        fn parse_time() {
+            assert_eq!(
+                parse_iso_duration("PT1H30M"),
+                Some(Duration::from_secs(5400))
+            );
+            assert_eq!(parse_iso_duration("PT45M"), Some(Duration::from_secs(2700)));
+            assert_eq!(
+                parse_iso_duration("PT0.5H"),
+                Some(Duration::from_secs(1800))
+            );
+        }
+
+        #[test]
+        fn parse_days() {
+            assert_eq!(
+                parse_iso_duration("P1DT2H"),
+                Some(Duration::from_secs(93600))
+            );
+        }
+
+        #[test]
+        fn parse_invalid() {
+            assert_eq!(parse_iso_duration("1 hour"), None);
+            assert_eq!(parse_iso_duration("PT"), None);
+            assert_eq!(parse_iso_duration("PT5"), None);
+            assert_eq!(parse_iso_duration("P5H"), None);
+            assert_eq!(parse_iso_duration("PT99999999999999999999H"), None);
+        }
+    }
+}
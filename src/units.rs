@@ -0,0 +1,424 @@
+use crate::text::contains_phrase;
+
+/// A system of measurement that quantities can be [converted](crate::Recipe::convert_units) to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnitSystem {
+    /// Grams, kilograms, milliliters and liters.
+    Metric,
+    /// Cups, fluid ounces, ounces and pounds.
+    UsCustomary,
+}
+
+/// Whether a [unit](Unit) measures volume or mass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Dimension {
+    Volume,
+    Mass,
+}
+
+/// A unit of measurement found in ingredient lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Unit {
+    Teaspoon,
+    Tablespoon,
+    Cup,
+    FluidOunce,
+    Pint,
+    Quart,
+    Gallon,
+    Ounce,
+    Pound,
+    Milliliter,
+    Centiliter,
+    Deciliter,
+    Liter,
+    Gram,
+    Kilogram,
+}
+
+impl Unit {
+    /// Parses a unit from a single word, e.g. "cups", "Tbsp", "g" or "EL".
+    pub fn parse(word: &str) -> Option<Unit> {
+        let unit = match word.to_lowercase().trim_end_matches('.') {
+            "tsp" | "tsps" | "teaspoon" | "teaspoons" | "tl" => Unit::Teaspoon,
+            "tbsp" | "tbsps" | "tbs" | "tablespoon" | "tablespoons" | "el" => Unit::Tablespoon,
+            "cup" | "cups" => Unit::Cup,
+            "floz" => Unit::FluidOunce,
+            "pint" | "pints" | "pt" => Unit::Pint,
+            "quart" | "quarts" | "qt" => Unit::Quart,
+            "gallon" | "gallons" | "gal" => Unit::Gallon,
+            "oz" | "ounce" | "ounces" => Unit::Ounce,
+            "lb" | "lbs" | "pound" | "pounds" => Unit::Pound,
+            "ml" | "milliliter" | "milliliters" | "millilitre" | "millilitres" => Unit::Milliliter,
+            "cl" => Unit::Centiliter,
+            "dl" => Unit::Deciliter,
+            "l" | "liter" | "liters" | "litre" | "litres" => Unit::Liter,
+            "g" | "gr" | "gram" | "grams" | "gramm" => Unit::Gram,
+            "kg" | "kilogram" | "kilograms" | "kilogramm" => Unit::Kilogram,
+            _ => return None,
+        };
+        Some(unit)
+    }
+
+    /// Returns whether this unit measures volume or mass.
+    pub fn dimension(&self) -> Dimension {
+        match self {
+            Unit::Ounce | Unit::Pound | Unit::Gram | Unit::Kilogram => Dimension::Mass,
+            _ => Dimension::Volume,
+        }
+    }
+
+    /// Returns the system this unit belongs to.
+    ///
+    /// Teaspoons and tablespoons are used in both systems and return `None`. They are
+    /// still [converted](Quantity::convert) to metric units.
+    pub fn system(&self) -> Option<UnitSystem> {
+        match self {
+            Unit::Teaspoon | Unit::Tablespoon => None,
+            Unit::Cup
+            | Unit::FluidOunce
+            | Unit::Pint
+            | Unit::Quart
+            | Unit::Gallon
+            | Unit::Ounce
+            | Unit::Pound => Some(UnitSystem::UsCustomary),
+            _ => Some(UnitSystem::Metric),
+        }
+    }
+
+    /// Returns the size of this unit in milliliters (volume) or grams (mass).
+    pub fn base_factor(&self) -> f64 {
+        match self {
+            Unit::Teaspoon => 4.929,
+            Unit::Tablespoon => 14.787,
+            Unit::Cup => 236.588,
+            Unit::FluidOunce => 29.574,
+            Unit::Pint => 473.176,
+            Unit::Quart => 946.353,
+            Unit::Gallon => 3785.41,
+            Unit::Ounce => 28.3495,
+            Unit::Pound => 453.592,
+            Unit::Milliliter => 1.0,
+            Unit::Centiliter => 10.0,
+            Unit::Deciliter => 100.0,
+            Unit::Liter => 1000.0,
+            Unit::Gram => 1.0,
+            Unit::Kilogram => 1000.0,
+        }
+    }
+
+    /// Returns the abbreviation of the unit, pluralized if needed.
+    pub fn symbol(&self, amount: f64) -> &'static str {
+        match self {
+            Unit::Teaspoon => "tsp",
+            Unit::Tablespoon => "tbsp",
+            Unit::Cup if amount > 1.0 => "cups",
+            Unit::Cup => "cup",
+            Unit::FluidOunce => "fl oz",
+            Unit::Pint => "pt",
+            Unit::Quart => "qt",
+            Unit::Gallon => "gal",
+            Unit::Ounce => "oz",
+            Unit::Pound => "lb",
+            Unit::Milliliter => "ml",
+            Unit::Centiliter => "cl",
+            Unit::Deciliter => "dl",
+            Unit::Liter => "l",
+            Unit::Gram => "g",
+            Unit::Kilogram => "kg",
+        }
+    }
+}
+
+/// An amount together with its [unit](Unit).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quantity {
+    /// The amount, e.g. `1.5`.
+    pub value: f64,
+    /// The unit of the amount.
+    pub unit: Unit,
+}
+
+impl Quantity {
+    /// Constructs a new quantity.
+    pub fn new(value: f64, unit: Unit) -> Self {
+        Quantity { value, unit }
+    }
+
+    /// Returns the amount in milliliters (volume) or grams (mass).
+    pub fn base_value(&self) -> f64 {
+        self.value * self.unit.base_factor()
+    }
+
+    /// Converts the quantity to the given unit, if both measure the same [dimension](Dimension).
+    pub fn to(&self, unit: Unit) -> Option<Quantity> {
+        if self.unit.dimension() != unit.dimension() {
+            return None;
+        }
+        Some(Quantity::new(self.base_value() / unit.base_factor(), unit))
+    }
+
+    /// Converts the quantity to the given unit, using the density of the ingredient to
+    /// convert between volume and mass where needed.
+    pub fn to_for(&self, unit: Unit, ingredient: &str) -> Option<Quantity> {
+        if let Some(quantity) = self.to(unit) {
+            return Some(quantity);
+        }
+        let density = density(ingredient)?;
+        let base = match self.unit.dimension() {
+            Dimension::Volume => self.base_value() * density,
+            Dimension::Mass => self.base_value() / density,
+        };
+        Some(Quantity::new(base / unit.base_factor(), unit))
+    }
+
+    /// Converts the quantity to a fitting unit of the given system.
+    ///
+    /// If a density is known for the ingredient, volumes are converted to weights for
+    /// [metric](UnitSystem::Metric) and weights to cups for [US customary](UnitSystem::UsCustomary).
+    ///
+    /// Quantities that already belong to the target system are returned unchanged.
+    /// Teaspoons and tablespoons are converted to metric units, but kept for
+    /// [US customary](UnitSystem::UsCustomary).
+    pub fn convert(&self, system: UnitSystem, ingredient: &str) -> Quantity {
+        match (self.unit.system(), system) {
+            (Some(s), _) if s == system => return *self,
+            (None, UnitSystem::UsCustomary) => return *self,
+            _ => {}
+        }
+
+        let density = density(ingredient);
+        match (system, self.unit.dimension(), density) {
+            (UnitSystem::Metric, Dimension::Volume, Some(density)) => {
+                metric_mass(self.base_value() * density)
+            }
+            (UnitSystem::Metric, Dimension::Volume, None) => metric_volume(self.base_value()),
+            (UnitSystem::Metric, Dimension::Mass, _) => metric_mass(self.base_value()),
+            (UnitSystem::UsCustomary, Dimension::Mass, Some(density)) => {
+                us_volume(self.base_value() / density)
+            }
+            (UnitSystem::UsCustomary, Dimension::Mass, None) => us_mass(self.base_value()),
+            (UnitSystem::UsCustomary, Dimension::Volume, _) => us_volume(self.base_value()),
+        }
+    }
+
+    /// Formats the quantity for display, e.g. "1 1/2 cups" or "250 g".
+    pub fn format(&self) -> String {
+        let amount = match self.unit.system() {
+            Some(UnitSystem::Metric) => format_decimal(self.value),
+            _ => format_fraction(self.value),
+        };
+        format!("{amount} {}", self.unit.symbol(self.value))
+    }
+}
+
+fn metric_mass(grams: f64) -> Quantity {
+    if grams >= 1000.0 {
+        Quantity::new(grams / 1000.0, Unit::Kilogram)
+    } else {
+        Quantity::new(grams, Unit::Gram)
+    }
+}
+
+fn metric_volume(milliliters: f64) -> Quantity {
+    if milliliters >= 1000.0 {
+        Quantity::new(milliliters / 1000.0, Unit::Liter)
+    } else {
+        Quantity::new(milliliters, Unit::Milliliter)
+    }
+}
+
+fn us_mass(grams: f64) -> Quantity {
+    if grams >= Unit::Pound.base_factor() {
+        Quantity::new(grams / Unit::Pound.base_factor(), Unit::Pound)
+    } else {
+        Quantity::new(grams / Unit::Ounce.base_factor(), Unit::Ounce)
+    }
+}
+
+fn us_volume(milliliters: f64) -> Quantity {
+    let unit = if milliliters < Unit::Tablespoon.base_factor() {
+        Unit::Teaspoon
+    } else if milliliters < Unit::Cup.base_factor() / 4.0 {
+        Unit::Tablespoon
+    } else {
+        Unit::Cup
+    };
+    Quantity::new(milliliters / unit.base_factor(), unit)
+}
+
+/// Formats metric amounts: whole numbers from 10 on, one decimal below, two for kg and l.
+pub(crate) fn format_decimal(value: f64) -> String {
+    let rounded = if value >= 10.0 {
+        value.round()
+    } else if value >= 1.0 {
+        (value * 10.0).round() / 10.0
+    } else {
+        (value * 100.0).round() / 100.0
+    };
+    let mut s = format!("{rounded:.2}");
+    while s.ends_with('0') {
+        s.pop();
+    }
+    s.trim_end_matches('.').to_string()
+}
+
+/// Formats US customary amounts as mixed fractions rounded to the nearest eighth.
+pub(crate) fn format_fraction(value: f64) -> String {
+    let eighths = (value * 8.0).round() as u64;
+    let eighths = eighths.max(1);
+    let whole = eighths / 8;
+    let rest = eighths % 8;
+    let fraction = match rest {
+        0 => None,
+        2 => Some("1/4"),
+        4 => Some("1/2"),
+        6 => Some("3/4"),
+        1 => Some("1/8"),
+        3 => Some("3/8"),
+        5 => Some("5/8"),
+        _ => Some("7/8"),
+    };
+    match (whole, fraction) {
+        (0, Some(f)) => f.to_string(),
+        (w, Some(f)) => format!("{w} {f}"),
+        (w, None) => w.to_string(),
+    }
+}
+
+/// Densities in g/ml for common ingredients, used to convert between volume and mass.
+///
+/// More specific names must come before the generic ones (e.g. "brown sugar" before "sugar").
+const DENSITIES: &[(&str, f64)] = &[
+    ("almond flour", 0.41),
+    ("whole wheat flour", 0.51),
+    ("flour", 0.53),
+    ("brown sugar", 0.93),
+    ("powdered sugar", 0.51),
+    ("icing sugar", 0.51),
+    ("confectioners sugar", 0.51),
+    ("sugar", 0.85),
+    ("butter", 0.96),
+    ("rice", 0.78),
+    ("rolled oats", 0.38),
+    ("oats", 0.38),
+    ("cocoa powder", 0.42),
+    ("cocoa", 0.42),
+    ("honey", 1.42),
+    ("maple syrup", 1.32),
+    ("salt", 1.22),
+    ("cornstarch", 0.54),
+    ("baking soda", 0.93),
+    ("baking powder", 0.81),
+    ("breadcrumbs", 0.45),
+    ("grated parmesan", 0.42),
+    ("chocolate chips", 0.72),
+    ("peanut butter", 1.09),
+    ("semolina", 0.71),
+];
+
+/// Looks up the density of an ingredient in g/ml.
+pub(crate) fn density(ingredient: &str) -> Option<f64> {
+    let ingredient = ingredient.to_lowercase();
+    DENSITIES
+        .iter()
+        .find(|(name, _)| contains_phrase(&ingredient, name))
+        .map(|(_, density)| *density)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod quantity {
+        use super::*;
+
+        #[test]
+        fn to_same_dimension() {
+            let result = Quantity::new(2.0, Unit::Kilogram).to(Unit::Gram);
+            assert_eq!(result, Some(Quantity::new(2000.0, Unit::Gram)));
+        }
+
+        #[test]
+        fn to_other_dimension() {
+            let result = Quantity::new(2.0, Unit::Cup).to(Unit::Gram);
+            assert_eq!(result, None);
+        }
+
+        #[test]
+        fn to_for_with_density() {
+            let result = Quantity::new(1.0, Unit::Cup)
+                .to_for(Unit::Gram, "flour")
+                .unwrap();
+            assert_eq!(result.value.round(), 125.0);
+        }
+
+        #[test]
+        fn convert_volume_to_metric_mass() {
+            let result = Quantity::new(2.0, Unit::Cup).convert(UnitSystem::Metric, "flour");
+            assert_eq!(result.unit, Unit::Gram);
+            assert_eq!(result.format(), "251 g");
+        }
+
+        #[test]
+        fn convert_volume_to_metric_volume() {
+            let result = Quantity::new(1.0, Unit::Cup).convert(UnitSystem::Metric, "milk");
+            assert_eq!(result.format(), "237 ml");
+        }
+
+        #[test]
+        fn convert_mass_to_us() {
+            let result =
+                Quantity::new(1.0, Unit::Kilogram).convert(UnitSystem::UsCustomary, "beef");
+            assert_eq!(result.format(), "2 1/4 lb");
+        }
+
+        #[test]
+        fn convert_mass_to_us_cups() {
+            let result = Quantity::new(200.0, Unit::Gram).convert(UnitSystem::UsCustomary, "sugar");
+            assert_eq!(result.format(), "1 cup");
+        }
+
+        #[test]
+        fn convert_spoons_to_metric() {
+            let sugar = Quantity::new(2.0, Unit::Tablespoon).convert(UnitSystem::Metric, "sugar");
+            assert_eq!(sugar.format(), "25 g");
+            let oil = Quantity::new(1.0, Unit::Tablespoon).convert(UnitSystem::Metric, "oil");
+            assert_eq!(oil.format(), "15 ml");
+            let salt = Quantity::new(0.5, Unit::Teaspoon).convert(UnitSystem::Metric, "salt");
+            assert_eq!(salt.format(), "3 g");
+        }
+
+        #[test]
+        fn convert_keeps_spoons_for_us() {
+            let quantity = Quantity::new(2.0, Unit::Tablespoon);
+            assert_eq!(quantity.convert(UnitSystem::UsCustomary, "sugar"), quantity);
+        }
+
+        #[test]
+        fn convert_keeps_target_system() {
+            let quantity = Quantity::new(250.0, Unit::Gram);
+            assert_eq!(quantity.convert(UnitSystem::Metric, "flour"), quantity);
+        }
+    }
+
+    mod density {
+        use super::*;
+
+        #[test]
+        fn specific_before_generic() {
+            assert_eq!(density("packed brown sugar"), Some(0.93));
+            assert_eq!(density("granulated sugar"), Some(0.85));
+        }
+
+        #[test]
+        fn whole_words_only() {
+            assert_eq!(density("buttermilk"), None);
+            assert_eq!(density("unsalted butter"), Some(0.96));
+        }
+    }
+}
@@ -0,0 +1,665 @@
+//! Lints the JSON-LD of a recipe against schema.org and the requirements of Google's recipe
+//! rich results.
+
+use crate::constants::LdFields;
+use crate::parser::{Strictness, is_recipe_type};
+use crate::timers::parse_iso_duration;
+
+use serde_json::{Map, Value};
+
+/// How severe a [Diagnostic] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    /// The markup is invalid or misses a required property, so the recipe is not eligible
+    /// for rich results.
+    Error,
+    /// A recommended property is missing or should be written differently.
+    Warning,
+}
+
+/// A problem found by [validating](crate::validate_recipe) the JSON-LD of a recipe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// How severe the problem is.
+    pub severity: Severity,
+    /// The JSON pointer to the offending value within its script, e.g.
+    /// "/@graph/1/recipeIngredient/0". Missing properties point to where they are expected.
+    pub path: String,
+    /// A description of the problem.
+    pub message: String,
+}
+
+/// Properties Google recommends for recipes, see
+/// <https://developers.google.com/search/docs/appearance/structured-data/recipe>
+const RECOMMENDED: &[&str] = &[
+    LdFields::AUTHOR,
+    LdFields::DESCRIPTION,
+    LdFields::DATE_PUBLISHED,
+    LdFields::PREP_TIME,
+    LdFields::COOK_TIME,
+    LdFields::TOTAL_TIME,
+    LdFields::KEYWORDS,
+    LdFields::RECIPE_YIELD,
+    LdFields::RECIPE_CATEGORY,
+    LdFields::RECIPE_CUISINE,
+    LdFields::NUTRITION,
+    LdFields::RECIPE_INGREDIENT,
+    LdFields::RECIPE_INSTRUCTIONS,
+    LdFields::AGGREGATE_RATING,
+    LdFields::VIDEO,
+];
+
+/// The members of the schema.org RestrictedDiet enumeration
+const RESTRICTED_DIETS: &[&str] = &[
+    "DiabeticDiet",
+    "GlutenFreeDiet",
+    "HalalDiet",
+    "HinduDiet",
+    "KosherDiet",
+    "LowCalorieDiet",
+    "LowFatDiet",
+    "LowLactoseDiet",
+    "LowSaltDiet",
+    "VeganDiet",
+    "VegetarianDiet",
+];
+
+/// Validates the first recipe found in the given JSON-LD scripts
+///
+/// Scripts that are not valid JSON are reported as well, since search engines ignore them.
+pub(crate) fn validate_scripts<'a>(scripts: impl IntoIterator<Item = &'a str>) -> Vec<Diagnostic> {
+    let mut validator = Validator::default();
+    for (index, script) in scripts.into_iter().enumerate() {
+        let value = match serde_json::from_str::<Value>(script) {
+            Ok(value) => value,
+            Err(err) => {
+                validator.error("", format!("Script {index} is not valid JSON: {err}"));
+                continue;
+            }
+        };
+        if let Some((path, recipe, has_context)) = find_recipe(&value, String::new(), false) {
+            if !has_context {
+                validator.error(
+                    &path,
+                    "Missing \"@context\": \"https://schema.org\"".to_string(),
+                );
+            }
+            validator.recipe(recipe, &path);
+            return validator.diagnostics;
+        }
+    }
+    validator.error("", "No Recipe found in the JSON-LD".to_string());
+    validator.diagnostics
+}
+
+/// Finds the first recipe, returning its JSON pointer and whether a schema.org context
+/// applies to it
+fn find_recipe(
+    value: &Value,
+    path: String,
+    has_context: bool,
+) -> Option<(String, &Map<String, Value>, bool)> {
+    match value {
+        Value::Object(obj) => {
+            let has_context = has_context
+                || obj
+                    .get(LdFields::CONTEXT)
+                    .is_some_and(|context| context.to_string().contains("schema.org"));
+            if is_recipe_type(obj, Strictness::Lenient) {
+                return Some((path, obj, has_context));
+            }
+            obj.iter()
+                .find_map(|(key, v)| find_recipe(v, pointer(&path, key), has_context))
+        }
+        Value::Array(arr) => arr
+            .iter()
+            .enumerate()
+            .find_map(|(i, v)| find_recipe(v, pointer(&path, &i.to_string()), has_context)),
+        _ => None,
+    }
+}
+
+/// Appends a key to a JSON pointer, escaping "~" and "/"
+pub(crate) fn pointer(path: &str, key: &str) -> String {
+    format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+#[derive(Default)]
+struct Validator {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Validator {
+    fn error(&mut self, path: &str, message: String) {
+        self.push(Severity::Error, path, message);
+    }
+
+    fn warning(&mut self, path: &str, message: String) {
+        self.push(Severity::Warning, path, message);
+    }
+
+    fn push(&mut self, severity: Severity, path: &str, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            path: path.to_string(),
+            message,
+        });
+    }
+
+    fn recipe(&mut self, recipe: &Map<String, Value>, path: &str) {
+        if recipe.get(LdFields::TYPE) != Some(&Value::String("Recipe".into()))
+            && !recipe
+                .get(LdFields::TYPE)
+                .and_then(Value::as_array)
+                .is_some_and(|types| types.contains(&Value::String("Recipe".into())))
+        {
+            self.warning(
+                &pointer(path, LdFields::TYPE),
+                "Use \"Recipe\" without a prefix".to_string(),
+            );
+        }
+
+        for key in [LdFields::NAME, LdFields::IMAGE] {
+            if !recipe.contains_key(key) {
+                self.error(&pointer(path, key), "Missing required property".to_string());
+            }
+        }
+        for key in RECOMMENDED {
+            if !recipe.contains_key(*key) {
+                self.warning(
+                    &pointer(path, key),
+                    "Missing recommended property".to_string(),
+                );
+            }
+        }
+
+        for (key, value) in recipe {
+            let path = pointer(path, key);
+            match key.as_str() {
+                LdFields::NAME | LdFields::DESCRIPTION => self.text(value, &path),
+                LdFields::IMAGE => self.image(value, &path),
+                LdFields::AUTHOR => self.author(value, &path),
+                LdFields::DATE_PUBLISHED => self.date(value, &path),
+                LdFields::PREP_TIME | LdFields::COOK_TIME | LdFields::TOTAL_TIME => {
+                    self.duration(value, &path)
+                }
+                LdFields::KEYWORDS | LdFields::RECIPE_CATEGORY | LdFields::RECIPE_CUISINE => {
+                    self.texts(value, &path)
+                }
+                LdFields::RECIPE_YIELD => self.recipe_yield(value, &path),
+                LdFields::RECIPE_INGREDIENT => self.ingredients(value, &path),
+                LdFields::RECIPE_INSTRUCTIONS => self.instructions(value, &path),
+                LdFields::NUTRITION => self.nutrition(value, &path),
+                LdFields::SUITABLE_FOR_DIET => self.diets(value, &path),
+                LdFields::AGGREGATE_RATING => self.rating(value, &path),
+                LdFields::VIDEO => self.video(value, &path),
+                _ => {}
+            }
+        }
+
+        // Google uses prep and cook time only together
+        let prep = recipe.contains_key(LdFields::PREP_TIME);
+        let cook = recipe.contains_key(LdFields::COOK_TIME);
+        if prep != cook {
+            let missing = if prep {
+                LdFields::COOK_TIME
+            } else {
+                LdFields::PREP_TIME
+            };
+            self.warning(
+                &pointer(path, missing),
+                "prepTime and cookTime should be used together".to_string(),
+            );
+        }
+    }
+
+    /// Expects a non-empty text
+    fn text(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::String(s) if !s.trim().is_empty() => {}
+            Value::String(_) => self.error(path, "Expected a non-empty text".to_string()),
+            _ => self.error(path, format!("Expected a text, found {}", kind(value))),
+        }
+    }
+
+    /// Expects a text or a list of texts
+    fn texts(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::Array(arr) => {
+                for (i, item) in arr.iter().enumerate() {
+                    self.text(item, &pointer(path, &i.to_string()));
+                }
+            }
+            _ => self.text(value, path),
+        }
+    }
+
+    /// Expects a URL, an ImageObject or a list of them
+    fn image(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::String(s) if !s.trim().is_empty() => {}
+            Value::Array(arr) if !arr.is_empty() => {
+                for (i, item) in arr.iter().enumerate() {
+                    self.image(item, &pointer(path, &i.to_string()));
+                }
+            }
+            Value::Object(obj) if obj.contains_key(LdFields::URL) => {
+                self.text(&obj[LdFields::URL], &pointer(path, LdFields::URL))
+            }
+            Value::Object(_) => self.error(
+                &pointer(path, LdFields::URL),
+                "Missing URL of the ImageObject".to_string(),
+            ),
+            _ => self.error(path, "Expected an image URL or ImageObject".to_string()),
+        }
+    }
+
+    /// Expects a Person or Organization with a name
+    fn author(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::Array(arr) => {
+                for (i, item) in arr.iter().enumerate() {
+                    self.author(item, &pointer(path, &i.to_string()));
+                }
+            }
+            Value::Object(obj) => match obj.get(LdFields::NAME) {
+                Some(name) => self.text(name, &pointer(path, LdFields::NAME)),
+                None => self.error(
+                    &pointer(path, LdFields::NAME),
+                    "Missing name of the author".to_string(),
+                ),
+            },
+            Value::String(_) => self.warning(
+                path,
+                "Use a Person or Organization instead of a text".to_string(),
+            ),
+            _ => self.error(path, "Expected a Person or Organization".to_string()),
+        }
+    }
+
+    /// Expects an ISO 8601 date, e.g. "2024-03-10" or "2024-03-10T08:00:00+01:00"
+    fn date(&mut self, value: &Value, path: &str) {
+        let valid = value.as_str().is_some_and(|date| {
+            let bytes = date.as_bytes();
+            bytes.len() >= 10
+                && bytes[4] == b'-'
+                && bytes[7] == b'-'
+                && [0..4, 5..7, 8..10]
+                    .into_iter()
+                    .all(|range| bytes[range].iter().all(u8::is_ascii_digit))
+        });
+        if !valid {
+            self.error(
+                path,
+                "Expected an ISO 8601 date like \"2024-03-10\"".to_string(),
+            );
+        }
+    }
+
+    /// Expects an ISO 8601 duration, e.g. "PT1H30M"
+    fn duration(&mut self, value: &Value, path: &str) {
+        if value.as_str().and_then(parse_iso_duration).is_none() {
+            self.error(
+                path,
+                "Expected an ISO 8601 duration like \"PT1H30M\"".to_string(),
+            );
+        }
+    }
+
+    /// Expects a text, a number or a list of them
+    fn recipe_yield(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::Number(_) => {}
+            Value::Array(arr) => {
+                for (i, item) in arr.iter().enumerate() {
+                    self.recipe_yield(item, &pointer(path, &i.to_string()));
+                }
+            }
+            _ => self.text(value, path),
+        }
+    }
+
+    /// Expects a list of texts
+    fn ingredients(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::Array(arr) if arr.is_empty() => {
+                self.warning(path, "The list of ingredients is empty".to_string())
+            }
+            Value::Array(_) => self.texts(value, path),
+            Value::String(_) => {
+                self.warning(path, "Use a list with one text per ingredient".to_string())
+            }
+            _ => self.error(path, format!("Expected a list, found {}", kind(value))),
+        }
+    }
+
+    /// Expects HowToSteps, HowToSections or texts
+    fn instructions(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::Array(arr) => {
+                for (i, item) in arr.iter().enumerate() {
+                    self.instruction(item, &pointer(path, &i.to_string()));
+                }
+            }
+            Value::String(_) => self.warning(
+                path,
+                "Use a list of HowToStep instead of a single text".to_string(),
+            ),
+            _ => self.instruction(value, path),
+        }
+    }
+
+    fn instruction(&mut self, value: &Value, path: &str) {
+        let Value::Object(obj) = value else {
+            return self.text(value, path);
+        };
+        match obj.get(LdFields::TYPE).and_then(Value::as_str) {
+            Some("HowToSection") => match obj.get(LdFields::ITEM_LIST_ELEMENT) {
+                Some(Value::Array(steps)) => {
+                    let path = pointer(path, LdFields::ITEM_LIST_ELEMENT);
+                    for (i, step) in steps.iter().enumerate() {
+                        self.instruction(step, &pointer(&path, &i.to_string()));
+                    }
+                }
+                _ => self.error(
+                    &pointer(path, LdFields::ITEM_LIST_ELEMENT),
+                    "Expected a list of HowToStep".to_string(),
+                ),
+            },
+            Some("HowToStep") | Some("HowToDirection") | Some("HowToTip") => {
+                match obj.get(LdFields::TEXT) {
+                    Some(text) => self.text(text, &pointer(path, LdFields::TEXT)),
+                    None => self.error(
+                        &pointer(path, LdFields::TEXT),
+                        "Missing text of the step".to_string(),
+                    ),
+                }
+            }
+            _ => self.error(
+                &pointer(path, LdFields::TYPE),
+                "Expected a HowToStep or HowToSection".to_string(),
+            ),
+        }
+    }
+
+    /// Expects NutritionInformation with texts including units
+    fn nutrition(&mut self, value: &Value, path: &str) {
+        let Value::Object(obj) = value else {
+            return self.error(path, "Expected NutritionInformation".to_string());
+        };
+        if obj.get(LdFields::TYPE).and_then(Value::as_str) != Some("NutritionInformation") {
+            self.warning(
+                &pointer(path, LdFields::TYPE),
+                "Expected \"@type\": \"NutritionInformation\"".to_string(),
+            );
+        }
+        if !obj.contains_key(LdFields::CALORIES) {
+            self.warning(
+                &pointer(path, LdFields::CALORIES),
+                "Missing recommended property".to_string(),
+            );
+        }
+        for (key, value) in obj.iter().filter(|(key, _)| !key.starts_with('@')) {
+            let path = pointer(path, key);
+            match value {
+                Value::Number(_) => self.warning(
+                    &path,
+                    "Use a text with a unit, e.g. \"240 calories\"".to_string(),
+                ),
+                _ => self.text(value, &path),
+            }
+        }
+    }
+
+    /// Expects members of the RestrictedDiet enumeration
+    fn diets(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::Array(arr) => {
+                for (i, item) in arr.iter().enumerate() {
+                    self.diets(item, &pointer(path, &i.to_string()));
+                }
+            }
+            Value::String(diet) => {
+                let name = diet.rsplit('/').next().unwrap_or_default();
+                let name = name.strip_prefix("schema:").unwrap_or(name);
+                if !RESTRICTED_DIETS.contains(&name) {
+                    self.error(path, format!("Unknown diet \"{diet}\""));
+                }
+            }
+            Value::Object(obj) if obj.contains_key(LdFields::ID) => {
+                self.diets(&obj[LdFields::ID], &pointer(path, LdFields::ID))
+            }
+            _ => self.error(path, "Expected a RestrictedDiet".to_string()),
+        }
+    }
+
+    /// Expects an AggregateRating with a value and a count
+    fn rating(&mut self, value: &Value, path: &str) {
+        let Value::Object(obj) = value else {
+            return self.error(path, "Expected an AggregateRating".to_string());
+        };
+        if !obj.contains_key(LdFields::RATING_VALUE) {
+            self.error(
+                &pointer(path, LdFields::RATING_VALUE),
+                "Missing required property".to_string(),
+            );
+        }
+        if !obj.contains_key(LdFields::RATING_COUNT) && !obj.contains_key(LdFields::REVIEW_COUNT) {
+            self.error(
+                &pointer(path, LdFields::RATING_COUNT),
+                "Missing ratingCount or reviewCount".to_string(),
+            );
+        }
+    }
+
+    /// Expects a VideoObject with the properties Google requires
+    fn video(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::Array(arr) => {
+                for (i, item) in arr.iter().enumerate() {
+                    self.video(item, &pointer(path, &i.to_string()));
+                }
+            }
+            Value::Object(obj) => {
+                for key in [
+                    LdFields::NAME,
+                    LdFields::DESCRIPTION,
+                    LdFields::THUMBNAIL_URL,
+                    LdFields::UPLOAD_DATE,
+                ] {
+                    if !obj.contains_key(key) {
+                        self.error(&pointer(path, key), "Missing required property".to_string());
+                    }
+                }
+            }
+            _ => self.error(path, "Expected a VideoObject".to_string()),
+        }
+    }
+}
+
+/// Describes the kind of the value for messages
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a text",
+        Value::Array(_) => "a list",
+        Value::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn validate(value: Value) -> Vec<Diagnostic> {
+        validate_scripts([value.to_string().as_str()])
+    }
+
+    fn errors(diagnostics: &[Diagnostic]) -> Vec<(&str, &str)> {
+        diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .map(|d| (d.path.as_str(), d.message.as_str()))
+            .collect()
+    }
+
+    fn complete() -> Value {
+        json!({
+            "@context": "https://schema.org",
+            "@type": "Recipe",
+            "name": "Pancakes",
+            "image": ["https://example.org/1x1.jpg"],
+            "author": {"@type": "Person", "name": "Jane"},
+            "datePublished": "2024-03-10",
+            "description": "Fluffy pancakes.",
+            "prepTime": "PT10M",
+            "cookTime": "PT20M",
+            "totalTime": "PT30M",
+            "keywords": "breakfast, sweet",
+            "recipeYield": ["4", "4 pancakes"],
+            "recipeCategory": "Breakfast",
+            "recipeCuisine": "American",
+            "nutrition": {"@type": "NutritionInformation", "calories": "240 calories"},
+            "recipeIngredient": ["2 eggs", "200 g flour"],
+            "recipeInstructions": [
+                {"@type": "HowToStep", "text": "Mix."},
+                {"@type": "HowToSection", "name": "Frying", "itemListElement": [
+                    {"@type": "HowToStep", "text": "Fry."}
+                ]}
+            ],
+            "suitableForDiet": "https://schema.org/VegetarianDiet",
+            "aggregateRating": {"@type": "AggregateRating", "ratingValue": "5", "ratingCount": "18"},
+            "video": {
+                "@type": "VideoObject",
+                "name": "Pancakes",
+                "description": "How to make pancakes",
+                "thumbnailUrl": "https://example.org/video.jpg",
+                "uploadDate": "2024-03-10"
+            }
+        })
+    }
+
+    #[test]
+    fn valid_recipe() {
+        assert_eq!(validate(complete()), vec![]);
+    }
+
+    #[test]
+    fn missing_properties() {
+        let diagnostics = validate(json!({
+            "@context": "https://schema.org",
+            "@type": "Recipe",
+            "name": "Pancakes"
+        }));
+        assert_eq!(
+            errors(&diagnostics),
+            vec![("/image", "Missing required property")]
+        );
+        assert!(diagnostics.iter().any(|d| d.severity == Severity::Warning
+            && d.path == "/recipeIngredient"
+            && d.message == "Missing recommended property"));
+    }
+
+    #[test]
+    fn invalid_values() {
+        let mut recipe = complete();
+        recipe["totalTime"] = json!("30 minutes");
+        recipe["datePublished"] = json!("10.03.2024");
+        recipe["recipeIngredient"] = json!(["2 eggs", {"name": "flour"}]);
+        recipe["recipeInstructions"][1]["itemListElement"][0] = json!({"@type": "HowToStep"});
+        recipe["suitableForDiet"] = json!(["VeganDiet", "KetoDiet"]);
+        assert_eq!(
+            errors(&validate(recipe)),
+            vec![
+                (
+                    "/datePublished",
+                    "Expected an ISO 8601 date like \"2024-03-10\""
+                ),
+                ("/recipeIngredient/1", "Expected a text, found an object"),
+                (
+                    "/recipeInstructions/1/itemListElement/0/text",
+                    "Missing text of the step"
+                ),
+                ("/suitableForDiet/1", "Unknown diet \"KetoDiet\""),
+                (
+                    "/totalTime",
+                    "Expected an ISO 8601 duration like \"PT1H30M\""
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn pointer_into_graph() {
+        let mut recipe = complete();
+        recipe.as_object_mut().unwrap().remove("@context");
+        recipe["image"] = json!({"@type": "ImageObject"});
+        let diagnostics = validate(json!({
+            "@context": "https://schema.org",
+            "@graph": [{"@type": "WebPage"}, recipe]
+        }));
+        assert_eq!(
+            errors(&diagnostics),
+            vec![("/@graph/1/image/url", "Missing URL of the ImageObject")]
+        );
+    }
+
+    #[test]
+    fn escape_pointer() {
+        assert_eq!(pointer("/a", "b/c~d"), "/a/b~1c~0d");
+    }
+
+    #[test]
+    fn missing_context_and_recipe() {
+        let mut recipe = complete();
+        recipe.as_object_mut().unwrap().remove("@context");
+        assert_eq!(
+            errors(&validate(recipe)),
+            vec![("", "Missing \"@context\": \"https://schema.org\"")]
+        );
+        assert_eq!(
+            errors(&validate_scripts(["{", "{\"@type\": \"WebPage\"}"])),
+            vec![
+                (
+                    "",
+                    "Script 0 is not valid JSON: EOF while parsing an object at line 1 column 1"
+                ),
+                ("", "No Recipe found in the JSON-LD"),
+            ]
+        );
+    }
+
+    #[test]
+    fn warnings() {
+        let mut recipe = complete();
+        recipe["author"] = json!("Jane");
+        recipe["recipeInstructions"] = json!("Mix and fry.");
+        recipe.as_object_mut().unwrap().remove("cookTime");
+        let warnings: Vec<(String, String)> = validate(recipe)
+            .into_iter()
+            .filter(|d| d.severity == Severity::Warning)
+            .map(|d| (d.path, d.message))
+            .collect();
+        assert_eq!(
+            warnings,
+            vec![
+                ("/cookTime".into(), "Missing recommended property".into()),
+                (
+                    "/author".into(),
+                    "Use a Person or Organization instead of a text".into()
+                ),
+                (
+                    "/recipeInstructions".into(),
+                    "Use a list of HowToStep instead of a single text".into()
+                ),
+                (
+                    "/cookTime".into(),
+                    "prepTime and cookTime should be used together".into()
+                ),
+            ]
+        );
+    }
+}